@@ -0,0 +1,286 @@
+use strum::{Display, EnumIter, EnumMessage, EnumString};
+
+use crate::pose::{PartRotation, PlayerPose};
+
+/// A pose at a given point of an animation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PoseKeyframe {
+    /// The point of the animation this keyframe is at, from `0.0` (start) to `1.0` (end).
+    pub time: f32,
+    pub pose: PlayerPose,
+}
+
+impl PoseKeyframe {
+    pub fn new(time: f32, pose: PlayerPose) -> Self {
+        Self { time, pose }
+    }
+}
+
+/// A time-based animation, made out of keyframed poses that are interpolated between.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerAnimation {
+    keyframes: Vec<PoseKeyframe>,
+    /// How long a full cycle of the animation lasts, in seconds.
+    pub duration: f32,
+    /// Whether the animation goes back to the first keyframe after the last one.
+    pub looping: bool,
+}
+
+impl PlayerAnimation {
+    pub fn new(mut keyframes: Vec<PoseKeyframe>, duration: f32, looping: bool) -> Self {
+        keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        Self {
+            keyframes,
+            duration,
+            looping,
+        }
+    }
+
+    pub fn keyframes(&self) -> &[PoseKeyframe] {
+        &self.keyframes
+    }
+
+    /// Sample the pose of the animation at the given point in time, from `0.0` (start) to `1.0` (end).
+    pub fn sample(&self, time: f32) -> PlayerPose {
+        let (Some(first), Some(last)) = (self.keyframes.first(), self.keyframes.last()) else {
+            return PlayerPose::default();
+        };
+
+        let time = time.clamp(0.0, 1.0);
+
+        if time <= first.time && !self.looping {
+            return first.pose;
+        }
+
+        if time >= last.time {
+            if !self.looping {
+                return last.pose;
+            }
+
+            // Wrap around from the last keyframe to the first one.
+            let span = 1.0 - last.time + first.time;
+            return last.pose.lerp(&first.pose, progress(time - last.time, span));
+        }
+
+        if time < first.time {
+            let span = 1.0 - last.time + first.time;
+            return last.pose.lerp(&first.pose, progress(1.0 - last.time + time, span));
+        }
+
+        self.keyframes
+            .windows(2)
+            .find(|window| time >= window[0].time && time < window[1].time)
+            .map_or(last.pose, |window| {
+                let (from, to) = (&window[0], &window[1]);
+                from.pose.lerp(&to.pose, progress(time - from.time, to.time - from.time))
+            })
+    }
+
+    /// Sample the animation into evenly spaced frames.
+    ///
+    /// Looping animations don't repeat their first frame at the end, so that the frames can be played in a loop.
+    pub fn sample_frames(&self, frames: usize) -> Vec<PlayerPose> {
+        let steps = if self.looping {
+            frames
+        } else {
+            frames.saturating_sub(1).max(1)
+        };
+
+        (0..frames)
+            .map(|frame| self.sample(frame as f32 / steps as f32))
+            .collect()
+    }
+}
+
+/// The eased progress between two keyframes that are `span` apart.
+fn progress(elapsed: f32, span: f32) -> f32 {
+    if span <= f32::EPSILON {
+        return 1.0;
+    }
+
+    let t = (elapsed / span).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// The animations that are available without having to define them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter, EnumString, EnumMessage, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum PlayerAnimationPreset {
+    #[strum(serialize = "walk", serialize = "walking")]
+    Walk,
+    #[strum(serialize = "idle", serialize = "breathing")]
+    Idle,
+    ArmSwing,
+    #[strum(serialize = "look_around", serialize = "look")]
+    LookAround,
+}
+
+impl PlayerAnimationPreset {
+    pub fn get_animation(&self) -> PlayerAnimation {
+        let keyframes = match self {
+            Self::Walk => {
+                let stride = PlayerPose {
+                    left_arm: PartRotation::pitch(-30.0),
+                    right_arm: PartRotation::pitch(30.0),
+                    left_leg: PartRotation::pitch(30.0),
+                    right_leg: PartRotation::pitch(-30.0),
+                    ..Default::default()
+                };
+
+                let mirrored = PlayerPose {
+                    left_arm: stride.right_arm,
+                    right_arm: stride.left_arm,
+                    left_leg: stride.right_leg,
+                    right_leg: stride.left_leg,
+                    ..stride
+                };
+
+                vec![
+                    PoseKeyframe::new(0.0, stride),
+                    PoseKeyframe::new(0.5, mirrored),
+                ]
+            }
+            Self::Idle => {
+                let breathe_in = PlayerPose {
+                    head: PartRotation::pitch(-2.0),
+                    left_arm: PartRotation::new(0.0, 0.0, 3.0),
+                    right_arm: PartRotation::new(0.0, 0.0, 3.0),
+                    ..Default::default()
+                };
+
+                vec![
+                    PoseKeyframe::new(0.0, PlayerPose::default()),
+                    PoseKeyframe::new(0.5, breathe_in),
+                ]
+            }
+            Self::ArmSwing => {
+                let swing = |pitch, yaw| PlayerPose {
+                    right_arm: PartRotation::new(pitch, yaw, 0.0),
+                    ..Default::default()
+                };
+
+                vec![
+                    PoseKeyframe::new(0.0, PlayerPose::default()),
+                    PoseKeyframe::new(0.3, swing(110.0, -20.0)),
+                    PoseKeyframe::new(0.6, swing(30.0, 30.0)),
+                ]
+            }
+            Self::LookAround => {
+                let look = |pitch, yaw| PlayerPose {
+                    head: PartRotation::new(pitch, yaw, 0.0),
+                    ..Default::default()
+                };
+
+                vec![
+                    PoseKeyframe::new(0.0, PlayerPose::default()),
+                    PoseKeyframe::new(0.25, look(0.0, 45.0)),
+                    PoseKeyframe::new(0.5, look(-15.0, 0.0)),
+                    PoseKeyframe::new(0.75, look(0.0, -45.0)),
+                ]
+            }
+        };
+
+        let duration = match self {
+            Self::Walk => 1.0,
+            Self::Idle => 3.0,
+            Self::ArmSwing => 0.5,
+            Self::LookAround => 4.0,
+        };
+
+        PlayerAnimation::new(keyframes, duration, true)
+    }
+}
+
+impl From<PlayerAnimationPreset> for PlayerAnimation {
+    fn from(preset: PlayerAnimationPreset) -> Self {
+        preset.get_animation()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlayerAnimation, PoseKeyframe};
+    use crate::pose::{PartRotation, PlayerPose};
+
+    fn arm_pose(pitch: f32) -> PlayerPose {
+        PlayerPose {
+            left_arm: PartRotation::pitch(pitch),
+            ..Default::default()
+        }
+    }
+
+    fn arm_pitch(pose: PlayerPose) -> f32 {
+        pose.left_arm.pitch
+    }
+
+    #[test]
+    fn test_sample_wraps_around_when_looping() {
+        let animation = PlayerAnimation::new(
+            vec![
+                PoseKeyframe::new(0.5, arm_pose(30.0)),
+                PoseKeyframe::new(0.0, arm_pose(-30.0)),
+            ],
+            1.0,
+            true,
+        );
+
+        assert_eq!(arm_pose(-30.0), animation.sample(0.0));
+        assert_eq!(arm_pose(30.0), animation.sample(0.5));
+        assert_eq!(0.0, arm_pitch(animation.sample(0.25)));
+        // Halfway between the last keyframe and the first one of the next cycle
+        assert_eq!(0.0, arm_pitch(animation.sample(0.75)));
+        assert_eq!(animation.sample(0.0), animation.sample(1.0));
+    }
+
+    #[test]
+    fn test_sample_clamps_when_not_looping() {
+        let animation = PlayerAnimation::new(
+            vec![
+                PoseKeyframe::new(0.2, arm_pose(-30.0)),
+                PoseKeyframe::new(0.8, arm_pose(30.0)),
+            ],
+            1.0,
+            false,
+        );
+
+        assert_eq!(arm_pose(-30.0), animation.sample(0.0));
+        assert_eq!(arm_pose(-30.0), animation.sample(-1.0));
+        assert_eq!(0.0, arm_pitch(animation.sample(0.5)));
+        assert_eq!(arm_pose(30.0), animation.sample(0.9));
+        assert_eq!(arm_pose(30.0), animation.sample(2.0));
+    }
+
+    #[test]
+    fn test_sample_single_keyframe() {
+        for looping in [true, false] {
+            let animation =
+                PlayerAnimation::new(vec![PoseKeyframe::new(0.4, arm_pose(45.0))], 1.0, looping);
+
+            for time in [0.0, 0.4, 0.7, 1.0] {
+                assert_eq!(arm_pose(45.0), animation.sample(time), "looping: {looping}, time: {time}");
+            }
+        }
+
+        let empty = PlayerAnimation::new(vec![], 1.0, true);
+        assert_eq!(PlayerPose::default(), empty.sample(0.5));
+    }
+
+    #[test]
+    fn test_sample_frames() {
+        let keyframes = vec![
+            PoseKeyframe::new(0.0, arm_pose(-30.0)),
+            PoseKeyframe::new(0.5, arm_pose(30.0)),
+        ];
+
+        let looping = PlayerAnimation::new(keyframes.clone(), 1.0, true);
+        let frames: Vec<_> = looping.sample_frames(4).into_iter().map(arm_pitch).collect();
+        assert_eq!(vec![-30.0, 0.0, 30.0, 0.0], frames, "Looping animations shouldn't repeat the first frame");
+
+        let once = PlayerAnimation::new(keyframes, 1.0, false);
+        let frames: Vec<_> = once.sample_frames(3).into_iter().map(arm_pitch).collect();
+        assert_eq!(vec![-30.0, 30.0, 30.0], frames, "The last frame should end on the last keyframe");
+
+        assert_eq!(1, once.sample_frames(1).len());
+    }
+}
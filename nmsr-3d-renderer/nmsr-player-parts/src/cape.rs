@@ -0,0 +1,140 @@
+use glam::Vec2;
+
+/// How the cape worn by a player hangs from their shoulders.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CapeState {
+    /// How far the cape swings out from the back, in degrees.
+    pub angle: f32,
+    /// The speed of the wind blowing against the cape, in blocks per second (or how fast the player is moving).
+    ///
+    /// When set, the cape is split into strips that bend like cloth, like the WavyCapes mod does.
+    pub wind: Option<f32>,
+}
+
+impl Default for CapeState {
+    fn default() -> Self {
+        Self {
+            angle: Self::DEFAULT_ANGLE,
+            wind: None,
+        }
+    }
+}
+
+impl CapeState {
+    /// The angle the cape hangs at in the game when the player is standing still.
+    pub const DEFAULT_ANGLE: f32 = 5.0;
+
+    /// The amount of strips a wavy cape is split into, one for each pixel row of the cape.
+    pub const STRIP_COUNT: usize = 16;
+
+    const RELAXATION_ITERATIONS: usize = 64;
+    const RELAXATION_RATE: f32 = 0.5;
+
+    /// How hard the wind pushes on a strip facing it, compared to the weight of the strip, per block per second.
+    const DRAG: f32 = 0.25;
+    /// How much each strip is pulled towards the angle of its neighbours.
+    const STIFFNESS: f32 = 0.25;
+
+    /// How much the cape flutters per block per second of wind, in degrees at the bottom of the cape.
+    const FLUTTER: f32 = 2.5;
+    const MAX_FLUTTER: f32 = 15.0;
+    /// The amount of strips between two crests of the ripples running down the cape.
+    const FLUTTER_WAVELENGTH: f32 = 8.0;
+
+    pub fn is_wavy(&self) -> bool {
+        self.wind.is_some()
+    }
+
+    /// Simulate the cape as a chain of strips hanging from the shoulders, blown back by the wind.
+    ///
+    /// The first strip stays at the angle of the cape. Every other strip settles along the pull of the strips below
+    /// it, which are weighed down by gravity (once the upper body leans forward by `body_lean` degrees) and pushed
+    /// back by the wind they catch. Ripples then run down the cape, growing towards its free end and with the wind.
+    ///
+    /// Returns the angle of each strip, in degrees from hanging straight down the back.
+    pub fn simulate_strip_angles(&self, body_lean: f32) -> [f32; Self::STRIP_COUNT] {
+        let wind = self.wind.unwrap_or_default().max(0.0);
+        let gravity = Vec2::new(body_lean.to_radians().sin(), -body_lean.to_radians().cos());
+
+        let mut angles = [self.angle; Self::STRIP_COUNT];
+
+        for _ in 0..Self::RELAXATION_ITERATIONS {
+            // Vectors are (outwards from the back, up), in units of the weight of a strip
+            let mut pull = Vec2::ZERO;
+            let mut settled = angles;
+
+            for i in (1..Self::STRIP_COUNT).rev() {
+                let angle = angles[i].to_radians();
+
+                // A strip hanging straight down faces the wind, while one blown flat lets it through
+                let drag = Vec2::new(wind * Self::DRAG * angle.cos().max(0.0), 0.0);
+                pull += gravity + drag;
+
+                settled[i] = pull.x.atan2(-pull.y).to_degrees();
+            }
+
+            for i in 1..Self::STRIP_COUNT {
+                let neighbours = (settled[i - 1] + settled[(i + 1).min(Self::STRIP_COUNT - 1)]) / 2.0;
+                let target = settled[i] + (neighbours - settled[i]) * Self::STIFFNESS;
+
+                // The cape can't go through the back of the player
+                angles[i] += (target.max(0.0) - angles[i]) * Self::RELAXATION_RATE;
+            }
+        }
+
+        let flutter = (wind * Self::FLUTTER).min(Self::MAX_FLUTTER);
+
+        for (i, angle) in angles.iter_mut().enumerate().skip(1) {
+            let progress = i as f32 / (Self::STRIP_COUNT - 1) as f32;
+            let ripple = (std::f32::consts::TAU * i as f32 / Self::FLUTTER_WAVELENGTH).sin();
+
+            *angle = (*angle + ripple * flutter * progress).max(0.0);
+        }
+
+        angles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CapeState;
+
+    fn simulate(angle: f32, wind: f32, body_lean: f32) -> [f32; CapeState::STRIP_COUNT] {
+        CapeState {
+            angle,
+            wind: Some(wind),
+        }
+        .simulate_strip_angles(body_lean)
+    }
+
+    fn average(angles: &[f32]) -> f32 {
+        angles.iter().sum::<f32>() / angles.len() as f32
+    }
+
+    #[test]
+    fn test_cape_hangs_down_without_wind() {
+        let angles = simulate(CapeState::DEFAULT_ANGLE, 0.0, 0.0);
+
+        assert_eq!(CapeState::DEFAULT_ANGLE, angles[0].round());
+        assert!(angles.iter().all(|angle| angle.is_finite() && angle.abs() < 10.0), "{angles:?}");
+    }
+
+    #[test]
+    fn test_cape_is_blown_back_by_the_wind() {
+        let calm = simulate(CapeState::DEFAULT_ANGLE, 0.0, 0.0);
+        let windy = simulate(CapeState::DEFAULT_ANGLE, 8.0, 0.0);
+
+        assert!(average(&windy[1..]) > average(&calm[1..]) + 20.0, "{windy:?}");
+        assert!(windy.iter().all(|angle| angle.is_finite() && *angle < 180.0), "{windy:?}");
+    }
+
+    #[test]
+    fn test_cape_hangs_away_when_leaning_forward() {
+        let upright = simulate(CapeState::DEFAULT_ANGLE, 0.0, 0.0);
+        let leaning = simulate(CapeState::DEFAULT_ANGLE, 0.0, 30.0);
+
+        // Hanging straight down in the world means hanging 30 degrees away from the leaning back
+        assert!((average(&leaning[4..]) - 30.0).abs() < 5.0, "{leaning:?}");
+        assert!(average(&leaning[4..]) > average(&upright[4..]));
+    }
+}
@@ -1,7 +1,11 @@
 #![allow(dead_code, unused_variables)]
 
+pub mod animation;
+pub mod cape;
 pub mod model;
 pub mod parts;
+pub mod pose;
+pub mod skeleton;
 pub mod types;
 
 pub use strum::IntoEnumIterator;
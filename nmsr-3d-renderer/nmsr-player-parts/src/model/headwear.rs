@@ -0,0 +1,137 @@
+use glam::{Affine3A, Vec3};
+use strum::{Display, EnumIter, EnumString, IntoStaticStr};
+
+use crate::parts::part::Part;
+use crate::parts::uv::box_uv;
+use crate::types::PlayerPartTextureType;
+
+/// Block-style headwear worn in the helmet slot instead of a piece of armor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, EnumIter, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum Headwear {
+    CarvedPumpkin,
+    SkeletonSkull,
+    WitherSkeletonSkull,
+    ZombieHead,
+    CreeperHead,
+    /// The head of a player, textured with their skin.
+    #[strum(to_string = "head", serialize = "player_head")]
+    PlayerHead,
+}
+
+impl Headwear {
+    /// How much bigger than a block skulls are when worn, taken from the game.
+    const SKULL_SCALE: f32 = 1.1875;
+
+    /// The texture the headwear is laid out on.
+    ///
+    /// Skulls use the layout of the head of their mob (or of a player skin), and the carved pumpkin uses the layout of
+    /// a 16×16×16 box, with the carved face on the front.
+    pub fn get_texture(&self) -> PlayerPartTextureType {
+        let size = match self {
+            Self::ZombieHead | Self::PlayerHead => (64, 64),
+            _ => (64, 32),
+        };
+
+        PlayerPartTextureType::Custom {
+            key: self.into(),
+            size,
+        }
+    }
+
+    /// The path of the texture of the headwear in the vanilla assets, relative to `assets/minecraft`.
+    ///
+    /// There's none for the carved pumpkin, which is made out of the block textures, nor for player heads,
+    /// which use the skin of the player.
+    pub fn get_texture_path(&self) -> Option<&'static str> {
+        match self {
+            Self::SkeletonSkull => Some("textures/entity/skeleton/skeleton.png"),
+            Self::WitherSkeletonSkull => Some("textures/entity/skeleton/wither_skeleton.png"),
+            Self::ZombieHead => Some("textures/entity/zombie/zombie.png"),
+            Self::CreeperHead => Some("textures/entity/creeper/creeper.png"),
+            Self::CarvedPumpkin | Self::PlayerHead => None,
+        }
+    }
+
+    /// Whether the head has a hat layer over it, like the ones of players and zombies.
+    pub fn has_hat_layer(&self) -> bool {
+        matches!(self, Self::ZombieHead | Self::PlayerHead)
+    }
+
+    /// The parts of the headwear, placed over the head of the player.
+    pub fn get_parts(&self) -> Vec<Part> {
+        let texture = self.get_texture();
+
+        if *self == Self::CarvedPumpkin {
+            // Blocks are worn at 10/16 of their size, centered on the head
+            let pumpkin = Part::new_cube(
+                texture,
+                [-5, 23, -5],
+                [10, 10, 10],
+                box_uv(16, 16, [16, 16, 16]),
+                #[cfg(feature = "part_tracker")]
+                Some(self.to_string()),
+            );
+
+            return vec![pumpkin];
+        }
+
+        let head = Part::new_cube(
+            texture,
+            [-4, 24, -4],
+            [8, 8, 8],
+            box_uv(8, 8, [8, 8, 8]),
+            #[cfg(feature = "part_tracker")]
+            Some(self.to_string()),
+        );
+
+        let mut parts = vec![head.clone()];
+
+        if self.has_hat_layer() {
+            let mut hat = head.expand_splat(0.25);
+            hat.set_face_uvs(box_uv(40, 8, [8, 8, 8]));
+
+            parts.push(hat);
+        }
+
+        // Skulls are scaled up from the bottom of the head, so they sit on the shoulders
+        let pivot = Vec3::new(0.0, 24.0, 0.0);
+        let transform = Affine3A::from_translation(pivot)
+            * Affine3A::from_scale(Vec3::splat(Self::SKULL_SCALE))
+            * Affine3A::from_translation(-pivot);
+
+        for part in &mut parts {
+            part.transform_affine(transform);
+        }
+
+        parts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+    use strum::IntoEnumIterator;
+
+    use super::Headwear;
+    use crate::parts::provider::minecraft::compute_base_part;
+    use crate::types::PlayerBodyPartType;
+
+    #[test]
+    fn test_headwear_covers_the_head() {
+        let head = compute_base_part(PlayerBodyPartType::Head, false);
+        let (head_min, head_max) = (head.get_position(), head.get_position() + head.get_size());
+
+        for headwear in Headwear::iter() {
+            for part in headwear.get_parts() {
+                let transformation = part.get_transformation();
+                let min = transformation.transform_point3(Vec3::ZERO);
+                let max = transformation.transform_point3(Vec3::ONE);
+
+                assert!(min.x <= head_min.x && min.z <= head_min.z, "{headwear} doesn't cover the head");
+                assert!(max.cmpge(head_max).all(), "{headwear} doesn't cover the head");
+                assert!(min.y <= head_min.y + 0.01, "{headwear} floats above the shoulders");
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use glam::Vec3;
+use strum::{Display, EnumIter, EnumString, IntoStaticStr};
+
+use crate::parts::java_model::{JavaModel, JavaModelElement, JavaModelTransform};
+use crate::types::{PlayerBodyPartType, PlayerPartTextureType};
+
+/// A hand of the player, which can hold an item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumIter, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum PlayerHand {
+    MainHand,
+    OffHand,
+}
+
+impl PlayerHand {
+    /// The arm holding the items of the hand. Players are right-handed by default, so the main hand is the right one.
+    pub fn get_arm(&self) -> PlayerBodyPartType {
+        match self {
+            Self::MainHand => PlayerBodyPartType::RightArm,
+            Self::OffHand => PlayerBodyPartType::LeftArm,
+        }
+    }
+
+    /// The texture of the item held in the hand, which is the size of the one its model is laid out on.
+    pub fn get_texture(&self, size: (u32, u32)) -> PlayerPartTextureType {
+        let key = match self {
+            Self::MainHand => "main_hand_item",
+            Self::OffHand => "off_hand_item",
+        };
+
+        PlayerPartTextureType::Custom { key, size }
+    }
+}
+
+/// An item the game renders with an entity model instead of its item model (`builtin/entity`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, EnumIter, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum BuiltInItemModel {
+    Shield,
+    Trident,
+}
+
+impl BuiltInItemModel {
+    /// The path of the texture of the entity model in the vanilla assets, relative to `assets/minecraft`.
+    pub fn get_texture_path(&self) -> &'static str {
+        match self {
+            Self::Shield => "textures/entity/shield_base_nopattern.png",
+            Self::Trident => "textures/entity/trident.png",
+        }
+    }
+
+    /// The model of the item, with the display transforms of its vanilla item model.
+    pub fn get_model(&self) -> JavaModel {
+        let cube = |uv: [f32; 2], origin: [f32; 3], size: [f32; 3], mirror| {
+            JavaModelElement::from_entity_cube(uv.into(), origin.into(), size.into(), mirror)
+        };
+
+        let (texture_size, elements, rotation, translation) = match self {
+            Self::Shield => (
+                (64, 64),
+                vec![
+                    cube([0.0, 0.0], [-6.0, -11.0, -2.0], [12.0, 22.0, 1.0], false),
+                    cube([26.0, 0.0], [-1.0, -3.0, -1.0], [2.0, 6.0, 6.0], false),
+                ],
+                [0.0, 90.0, 0.0],
+                [10.0, 6.0, -4.0],
+            ),
+            Self::Trident => (
+                (32, 32),
+                vec![
+                    cube([0.0, 6.0], [-0.5, 2.0, -0.5], [1.0, 25.0, 1.0], false),
+                    cube([4.0, 0.0], [-1.5, 0.0, -0.5], [3.0, 2.0, 1.0], false),
+                    cube([4.0, 3.0], [-2.5, -3.0, -0.5], [1.0, 4.0, 1.0], false),
+                    cube([0.0, 0.0], [-0.5, -4.0, -0.5], [1.0, 4.0, 1.0], false),
+                    cube([4.0, 3.0], [1.5, -3.0, -0.5], [1.0, 4.0, 1.0], true),
+                ],
+                [0.0, 60.0, 0.0],
+                [11.0, 17.0, -2.0],
+            ),
+        };
+
+        let transform = JavaModelTransform {
+            rotation: Vec3::from(rotation),
+            translation: Vec3::from(translation),
+            scale: Vec3::ONE,
+        };
+
+        JavaModel {
+            texture_size,
+            elements,
+            right_hand_transform: transform,
+            left_hand_transform: transform,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::BuiltInItemModel;
+
+    #[test]
+    fn test_built_in_models_fit_their_texture() {
+        for item in BuiltInItemModel::iter() {
+            let model = item.get_model();
+            let (width, height) = model.texture_size;
+
+            for face in model.elements.iter().flat_map(|element| element.faces).flatten() {
+                for point in [face.top_left, face.bottom_right] {
+                    assert!(u32::from(point.x) <= width && u32::from(point.y) <= height, "{item} goes off its texture");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use glam::{Vec2, Vec3};
+use strum::{Display, EnumIter, EnumString, IntoStaticStr};
+
+use crate::parts::geometry::{BedrockBone, BedrockCube, BedrockCubeUv, BedrockGeometry};
+use crate::pose::{PartRotation, PlayerPose};
+use crate::types::PlayerPartTextureType;
+
+/// A mob shaped like a player (or an armor stand) that can be rendered instead of the player, wearing the same armor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, EnumIter, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum HumanoidMob {
+    ArmorStand,
+    Zombie,
+    Husk,
+    Drowned,
+    Skeleton,
+    Stray,
+}
+
+/// A cube of a mob model, given the way the game defines it: relative to the pivot of its part, with `y` pointing down.
+struct ModelCube {
+    texture_offset: (f32, f32),
+    pivot: [f32; 3],
+    from: [f32; 3],
+    size: [f32; 3],
+    mirror: bool,
+    inflate: f32,
+}
+
+const fn cube(texture_offset: (f32, f32), pivot: [f32; 3], from: [f32; 3], size: [f32; 3]) -> ModelCube {
+    ModelCube {
+        texture_offset,
+        pivot,
+        from,
+        size,
+        mirror: false,
+        inflate: 0.0,
+    }
+}
+
+impl ModelCube {
+    const fn mirrored(self) -> Self {
+        Self { mirror: true, ..self }
+    }
+
+    const fn inflated(self, inflate: f32) -> Self {
+        Self { inflate, ..self }
+    }
+
+    /// Move the cube to the way Bedrock Edition lays out models, with `y` pointing up from the feet.
+    fn into_cube(self) -> BedrockCube {
+        let [pivot_x, pivot_y, pivot_z] = self.pivot;
+        let [x, y, z] = self.from;
+        let size = Vec3::from(self.size);
+
+        let origin = Vec3::new(pivot_x + x, 24.0 - (pivot_y + y + size.y), pivot_z + z);
+
+        BedrockCube {
+            origin,
+            size,
+            uv: BedrockCubeUv::Box(Vec2::new(self.texture_offset.0, self.texture_offset.1)),
+            inflate: self.inflate,
+            mirror: self.mirror,
+            pivot: origin + size / 2.0,
+            rotation: Vec3::ZERO,
+        }
+    }
+}
+
+impl HumanoidMob {
+    /// The texture the model of the mob is laid out on.
+    pub fn get_texture(&self) -> PlayerPartTextureType {
+        let size = match self {
+            Self::Skeleton | Self::Stray => (64, 32),
+            _ => (64, 64),
+        };
+
+        PlayerPartTextureType::Custom {
+            key: self.into(),
+            size,
+        }
+    }
+
+    /// The path of the texture of the mob in the vanilla assets, relative to `assets/minecraft`.
+    pub fn get_texture_path(&self) -> &'static str {
+        match self {
+            Self::ArmorStand => "textures/entity/armorstand/wood.png",
+            Self::Zombie => "textures/entity/zombie/zombie.png",
+            Self::Husk => "textures/entity/zombie/husk.png",
+            Self::Drowned => "textures/entity/zombie/drowned.png",
+            Self::Skeleton => "textures/entity/skeleton/skeleton.png",
+            Self::Stray => "textures/entity/skeleton/stray.png",
+        }
+    }
+
+    /// The pose the mob is in when it's standing still.
+    pub fn get_default_pose(&self) -> PlayerPose {
+        match self {
+            Self::ArmorStand => PlayerPose {
+                left_arm: PartRotation::new(10.0, 0.0, 10.0),
+                right_arm: PartRotation::new(15.0, 0.0, 10.0),
+                left_leg: PartRotation::new(0.0, 0.0, 1.0),
+                right_leg: PartRotation::new(0.0, 0.0, 1.0),
+                ..Default::default()
+            },
+            // Zombies walk towards their target with their arms held out
+            Self::Zombie | Self::Husk | Self::Drowned => PlayerPose {
+                left_arm: PartRotation::pitch(90.0),
+                right_arm: PartRotation::pitch(90.0),
+                ..Default::default()
+            },
+            Self::Skeleton | Self::Stray => PlayerPose::default(),
+        }
+    }
+
+    /// The parts that stay in place whatever the pose is, like the base plate of armor stands.
+    pub fn get_static_geometry(&self) -> Option<BedrockGeometry> {
+        match self {
+            Self::ArmorStand => Some(Self::geometry_of(
+                self.get_texture(),
+                vec![("basePlate", vec![cube((0.0, 32.0), [0.0, 12.0, 0.0], [-6.0, 11.0, -6.0], [12.0, 1.0, 12.0])])],
+            )),
+            _ => None,
+        }
+    }
+
+    /// The model of the mob, with its bones named like the ones of the Bedrock Edition player model.
+    pub fn get_geometry(&self) -> BedrockGeometry {
+        let head = [0.0, 0.0, 0.0];
+        let right_arm = [-5.0, 2.0, 0.0];
+        let left_arm = [5.0, 2.0, 0.0];
+        let right_leg = [-1.9, 12.0, 0.0];
+        let left_leg = [1.9, 12.0, 0.0];
+
+        let humanoid_head = || {
+            vec![
+                ("head", vec![cube((0.0, 0.0), head, [-4.0, -8.0, -4.0], [8.0, 8.0, 8.0])]),
+                ("hat", vec![cube((32.0, 0.0), head, [-4.0, -8.0, -4.0], [8.0, 8.0, 8.0]).inflated(0.5)]),
+                ("body", vec![cube((16.0, 16.0), head, [-4.0, 0.0, -2.0], [8.0, 12.0, 4.0])]),
+            ]
+        };
+
+        let bones = match self {
+            Self::ArmorStand => vec![
+                ("head", vec![cube((0.0, 0.0), [0.0, 1.0, 0.0], [-1.0, -7.0, -1.0], [2.0, 7.0, 2.0])]),
+                (
+                    "body",
+                    vec![
+                        cube((0.0, 26.0), head, [-6.0, 0.0, -1.5], [12.0, 3.0, 3.0]),
+                        cube((16.0, 0.0), head, [-3.0, 3.0, -1.0], [2.0, 7.0, 2.0]),
+                        cube((48.0, 16.0), head, [1.0, 3.0, -1.0], [2.0, 7.0, 2.0]),
+                        cube((0.0, 48.0), head, [-4.0, 10.0, -1.0], [8.0, 2.0, 2.0]),
+                    ],
+                ),
+                ("rightArm", vec![cube((24.0, 0.0), right_arm, [-2.0, -2.0, -1.0], [2.0, 12.0, 2.0])]),
+                ("leftArm", vec![cube((32.0, 16.0), left_arm, [0.0, -2.0, -1.0], [2.0, 12.0, 2.0]).mirrored()]),
+                ("rightLeg", vec![cube((8.0, 0.0), right_leg, [-1.0, 0.0, -1.0], [2.0, 11.0, 2.0])]),
+                ("leftLeg", vec![cube((40.0, 16.0), left_leg, [-1.0, 0.0, -1.0], [2.0, 11.0, 2.0]).mirrored()]),
+            ],
+            Self::Zombie | Self::Husk => {
+                let mut bones = humanoid_head();
+                bones.extend([
+                    ("rightArm", vec![cube((40.0, 16.0), right_arm, [-3.0, -2.0, -2.0], [4.0, 12.0, 4.0])]),
+                    ("leftArm", vec![cube((40.0, 16.0), left_arm, [-1.0, -2.0, -2.0], [4.0, 12.0, 4.0]).mirrored()]),
+                    ("rightLeg", vec![cube((0.0, 16.0), right_leg, [-2.0, 0.0, -2.0], [4.0, 12.0, 4.0])]),
+                    ("leftLeg", vec![cube((0.0, 16.0), left_leg, [-2.0, 0.0, -2.0], [4.0, 12.0, 4.0]).mirrored()]),
+                ]);
+                bones
+            }
+            // Drowned have their own left arm and leg on the texture, like modern player skins
+            Self::Drowned => {
+                let mut bones = humanoid_head();
+                bones.extend([
+                    ("rightArm", vec![cube((40.0, 16.0), right_arm, [-3.0, -2.0, -2.0], [4.0, 12.0, 4.0])]),
+                    ("leftArm", vec![cube((32.0, 48.0), left_arm, [-1.0, -2.0, -2.0], [4.0, 12.0, 4.0])]),
+                    ("rightLeg", vec![cube((0.0, 16.0), right_leg, [-2.0, 0.0, -2.0], [4.0, 12.0, 4.0])]),
+                    ("leftLeg", vec![cube((16.0, 48.0), left_leg, [-2.0, 0.0, -2.0], [4.0, 12.0, 4.0])]),
+                ]);
+                bones
+            }
+            Self::Skeleton | Self::Stray => {
+                let mut bones = humanoid_head();
+                bones.extend([
+                    ("rightArm", vec![cube((40.0, 16.0), right_arm, [-1.0, -2.0, -1.0], [2.0, 12.0, 2.0])]),
+                    ("leftArm", vec![cube((40.0, 16.0), left_arm, [-1.0, -2.0, -1.0], [2.0, 12.0, 2.0]).mirrored()]),
+                    ("rightLeg", vec![cube((0.0, 16.0), [-2.0, 12.0, 0.0], [-1.0, 0.0, -1.0], [2.0, 12.0, 2.0])]),
+                    (
+                        "leftLeg",
+                        vec![cube((0.0, 16.0), [2.0, 12.0, 0.0], [-1.0, 0.0, -1.0], [2.0, 12.0, 2.0]).mirrored()],
+                    ),
+                ]);
+                bones
+            }
+        };
+
+        Self::geometry_of(self.get_texture(), bones)
+    }
+
+    fn geometry_of(texture: PlayerPartTextureType, bones: Vec<(&str, Vec<ModelCube>)>) -> BedrockGeometry {
+        BedrockGeometry {
+            texture_size: texture.get_texture_size(),
+            bones: bones
+                .into_iter()
+                .map(|(name, cubes)| BedrockBone {
+                    name: name.to_owned(),
+                    cubes: cubes.into_iter().map(ModelCube::into_cube).collect(),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::HumanoidMob;
+    use crate::parts::geometry::compute_geometry_parts;
+    use crate::parts::provider::minecraft::compute_base_part;
+    use crate::types::PlayerBodyPartType;
+
+    #[test]
+    fn test_humanoids_stand_like_players() {
+        let zombie = HumanoidMob::Zombie.get_geometry();
+
+        for part in [PlayerBodyPartType::Head, PlayerBodyPartType::RightArm, PlayerBodyPartType::LeftLeg] {
+            let player_part = compute_base_part(part, false);
+            let player_center = player_part.get_position() + player_part.get_size() / 2.0;
+
+            let bone = zombie
+                .bones
+                .iter()
+                .find(|bone| zombie.get_body_part(bone) == Some(part))
+                .expect("Every body part should have a bone");
+            // The mob model is laid out like Bedrock Edition ones, which are mirrored compared to ours
+            let cube = &bone.cubes[0];
+            let mut center = cube.origin + cube.size / 2.0;
+            center.x = -center.x;
+
+            assert!(center.abs_diff_eq(player_center, 0.15), "{part}: {center} != {player_center}");
+        }
+    }
+
+    #[test]
+    fn test_every_mob_has_every_body_part() {
+        for mob in HumanoidMob::iter() {
+            let geometry = mob.get_geometry();
+
+            for part in [
+                PlayerBodyPartType::Head,
+                PlayerBodyPartType::Body,
+                PlayerBodyPartType::LeftArm,
+                PlayerBodyPartType::RightArm,
+                PlayerBodyPartType::LeftLeg,
+                PlayerBodyPartType::RightLeg,
+            ] {
+                assert!(
+                    !compute_geometry_parts(&geometry, part, mob.get_texture()).is_empty(),
+                    "{mob} has no {part}"
+                );
+            }
+        }
+    }
+}
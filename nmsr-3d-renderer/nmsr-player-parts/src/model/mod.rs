@@ -1,4 +1,9 @@
-use strum::Display;
+use strum::{Display, EnumString};
+
+pub mod headwear;
+pub mod item;
+pub mod mob;
+pub mod nametag;
 
 use crate::types::{PlayerBodyPartType, PlayerPartTextureType};
 
@@ -9,6 +14,18 @@ pub enum PlayerModel {
     Alex,
 }
 
+/// How the elytra worn by a player is posed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum ElytraState {
+    /// The elytra is folded on the back of the player, as when standing.
+    #[default]
+    #[strum(to_string = "folded", serialize = "closed", serialize = "")]
+    Folded,
+    /// The elytra is spread open, as when gliding.
+    Open,
+}
+
 impl PlayerModel {
     pub fn is_slim_arms(&self) -> bool {
         match self {
@@ -22,6 +39,11 @@ pub trait ArmorMaterial {
     fn get_texture_type(slot: PlayerArmorSlot) -> Option<PlayerPartTextureType> {
         None
     }
+
+    /// Whether the armor is enchanted, which lays the enchantment glint over it.
+    fn is_enchanted(&self) -> bool {
+        false
+    }
 }
 
 impl ArmorMaterial for () {}
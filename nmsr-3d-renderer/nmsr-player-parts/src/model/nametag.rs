@@ -0,0 +1,152 @@
+use glam::{Affine3A, Vec3};
+
+use crate::parts::part::Part;
+use crate::parts::uv::uv_from_pos_and_size;
+use crate::types::PlayerPartTextureType;
+
+/// The name tag shown above the head of the player, which always faces the camera.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Nametag {
+    /// The size of the texture of the name tag (its text and background), in pixels.
+    pub texture_size: (u32, u32),
+    /// The yaw of the camera the name tag faces, in degrees.
+    pub yaw: f32,
+    /// The pitch of the camera the name tag faces, in degrees.
+    pub pitch: f32,
+}
+
+impl Nametag {
+    /// The size of a pixel of the name tag, as the game draws text at 1/40 of a block.
+    const PIXEL_SIZE: f32 = 0.4;
+    /// The space between the top of the head and the bottom of the name tag, taken from the game.
+    const GAP: f32 = 1.2;
+
+    pub fn get_texture(&self) -> PlayerPartTextureType {
+        PlayerPartTextureType::Nametag {
+            size: self.texture_size,
+        }
+    }
+
+    /// The name tag, centered above the head, with its bottom just above `head_top` (the highest point of everything
+    /// worn on the head).
+    ///
+    /// The quad is facing the camera the way billboards do, with its text going right and up along the screen.
+    pub fn get_part(&self, head_top: f32) -> Part {
+        let (width, height) = self.texture_size;
+        let (width, height) = (width as f32 * Self::PIXEL_SIZE, height as f32 * Self::PIXEL_SIZE);
+
+        let (yaw_sin, yaw_cos) = self.yaw.to_radians().sin_cos();
+        let (pitch_sin, pitch_cos) = self.pitch.to_radians().sin_cos();
+
+        let forward = Vec3::new(-yaw_sin * pitch_cos, -pitch_sin, yaw_cos * pitch_cos);
+        let right = Vec3::new(-yaw_cos, 0.0, -yaw_sin);
+        let up = right.cross(forward);
+
+        let center = Vec3::new(0.0, head_top + Self::GAP + height / 2.0, 0.0);
+
+        let mut quad = Part::new_quad(
+            self.get_texture(),
+            [0.0, 0.0, 0.0],
+            [1, 1, 0],
+            uv_from_pos_and_size(0, 0, self.texture_size.0 as u16, self.texture_size.1 as u16),
+            Vec3::Z,
+            #[cfg(feature = "part_tracker")]
+            Some("Nametag".to_string()),
+        );
+
+        quad.transform_affine(Affine3A::from_cols(
+            (right * width).into(),
+            (up * height).into(),
+            (-forward).into(),
+            (center - right * width / 2.0 - up * height / 2.0).into(),
+        ));
+
+        quad
+    }
+}
+
+/// The highest point of `parts`, in the space they were built in.
+pub(crate) fn compute_top(parts: &[Part]) -> f32 {
+    fn top_of(part: &Part, parent: Affine3A) -> f32 {
+        let transformation = parent * part.get_transformation();
+
+        let corners_of = |size: Vec3| {
+            (0..8).map(move |corner| {
+                let corner = Vec3::new((corner & 1) as f32, ((corner >> 1) & 1) as f32, ((corner >> 2) & 1) as f32);
+                transformation.transform_point3(corner * size).y
+            })
+        };
+
+        match part {
+            Part::Cube { .. } => corners_of(Vec3::ONE).fold(f32::MIN, f32::max),
+            Part::Quad { size, .. } => corners_of(*size).fold(f32::MIN, f32::max),
+            Part::Group { parts, .. } => parts.iter().map(|part| top_of(part, transformation)).fold(f32::MIN, f32::max),
+        }
+    }
+
+    parts.iter().map(|part| top_of(part, Affine3A::IDENTITY)).fold(f32::MIN, f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::Nametag;
+    use crate::model::headwear::Headwear;
+    use crate::model::PlayerModel;
+    use crate::parts::part::Part;
+    use crate::parts::provider::{PartsProvider, PlayerPartProviderContext, PlayerPartsProvider};
+    use crate::types::PlayerBodyPartType;
+
+    fn nametag_of(context: &PlayerPartProviderContext<()>) -> Part {
+        let parts = PlayerPartsProvider::Minecraft.get_parts(context, PlayerBodyPartType::Head);
+
+        parts
+            .into_iter()
+            .find(|part| part.get_texture().is_nametag())
+            .expect("The head should have a name tag")
+    }
+
+    fn bottom_of(part: &Part) -> f32 {
+        let transformation = part.get_transformation();
+
+        [Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::new(1.0, 1.0, 0.0)]
+            .map(|corner| transformation.transform_point3(corner).y)
+            .into_iter()
+            .fold(f32::MAX, f32::min)
+    }
+
+    #[test]
+    fn test_nametag_is_above_what_is_worn_on_the_head() {
+        let nametag = Nametag {
+            texture_size: (33, 10),
+            yaw: 20.0,
+            pitch: 10.0,
+        };
+
+        let mut context = PlayerPartProviderContext::<()> {
+            model: PlayerModel::Steve,
+            nametag: Some(nametag),
+            ..Default::default()
+        };
+
+        let bare_head = bottom_of(&nametag_of(&context));
+        assert!((bare_head - 33.2).abs() < 0.5, "The name tag is at {bare_head} over a bare head");
+
+        context.headwear = Some(Headwear::CarvedPumpkin);
+        let pumpkin = bottom_of(&nametag_of(&context));
+        assert!(pumpkin > bare_head, "The name tag doesn't move up over a carved pumpkin");
+
+        // The name tag faces the camera, so it isn't attached to the head
+        let quad = nametag_of(&context);
+        assert_eq!(None, quad.get_bone());
+
+        let Part::Quad { normal, .. } = quad else {
+            unreachable!("Name tags are quads");
+        };
+
+        let (yaw, pitch) = (nametag.yaw.to_radians(), nametag.pitch.to_radians());
+        let towards_camera = -Vec3::new(-yaw.sin() * pitch.cos(), -pitch.sin(), yaw.cos() * pitch.cos());
+        assert!(normal.normalize().abs_diff_eq(towards_camera, 1e-4));
+    }
+}
@@ -0,0 +1,403 @@
+use glam::{Affine3A, EulerRot, Quat, Vec2, Vec3};
+
+use crate::parts::part::Part;
+use crate::parts::uv::{box_uv, CubeFaceLayout, CubeFaceUvs, FaceUv, FaceUvPoint};
+use crate::types::{PlayerBodyPartType, PlayerPartTextureType};
+
+/// A custom model for the player, like the geometry Bedrock Edition skins can have (`geometry.humanoid.custom` and
+/// persona models).
+///
+/// Positions, pivots and rotations are kept the way Bedrock Edition defines them, with the player facing `+Z`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BedrockGeometry {
+    /// The size of the texture the UVs of the cubes are laid out on, in pixels.
+    pub texture_size: (u32, u32),
+    pub bones: Vec<BedrockBone>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BedrockBone {
+    pub name: String,
+    pub parent: Option<String>,
+    /// The point the bone (and all of its children) rotates around.
+    pub pivot: Vec3,
+    /// The rotation of the bone around its pivot, in degrees.
+    pub rotation: Vec3,
+    pub cubes: Vec<BedrockCube>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BedrockCube {
+    /// The corner of the cube with the lowest coordinates.
+    pub origin: Vec3,
+    pub size: Vec3,
+    pub uv: BedrockCubeUv,
+    /// How far the cube grows out in every direction, without changing its UVs.
+    pub inflate: f32,
+    /// Whether the cube is textured mirrored, like the left limbs of legacy skins. Only used with box UVs.
+    pub mirror: bool,
+    /// The point the cube rotates around.
+    pub pivot: Vec3,
+    /// The rotation of the cube around its pivot, in degrees.
+    pub rotation: Vec3,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BedrockCubeUv {
+    /// The cube is textured like a vanilla cuboid, with its faces laid out around the given point.
+    Box(Vec2),
+    /// The UVs of each face, in the order of [`CubeFaceUvs`]. Faces without UVs aren't rendered.
+    PerFace([Option<BedrockFaceUv>; 6]),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BedrockFaceUv {
+    pub uv: Vec2,
+    /// The size of the face on the texture, which is negative when the face is flipped.
+    pub size: Vec2,
+}
+
+impl BedrockGeometry {
+    fn get_bone(&self, name: &str) -> Option<&BedrockBone> {
+        self.bones
+            .iter()
+            .find(|bone| bone.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The parents of a bone, from the bone itself up to its root. Stops at bones that are their own ancestors.
+    fn get_ancestors<'a>(&'a self, bone: &'a BedrockBone) -> impl Iterator<Item = &'a BedrockBone> {
+        std::iter::successors(Some(bone), |bone| {
+            bone.parent.as_deref().and_then(|parent| self.get_bone(parent))
+        })
+        .take(self.bones.len())
+    }
+
+    /// The body part a bone moves with, which is the one of the closest of its ancestors that's part of the
+    /// vanilla player model. Bones that aren't attached to one move with the body.
+    ///
+    /// Returns [`None`] for the cape, which is rendered from the cape texture instead.
+    pub fn get_body_part(&self, bone: &BedrockBone) -> Option<PlayerBodyPartType> {
+        for ancestor in self.get_ancestors(bone) {
+            let body_part = match ancestor.name.to_ascii_lowercase().as_str() {
+                "cape" => return None,
+                "head" => PlayerBodyPartType::Head,
+                "hat" => PlayerBodyPartType::HeadLayer,
+                "body" => PlayerBodyPartType::Body,
+                "jacket" => PlayerBodyPartType::BodyLayer,
+                "leftarm" => PlayerBodyPartType::LeftArm,
+                "leftsleeve" => PlayerBodyPartType::LeftArmLayer,
+                "rightarm" => PlayerBodyPartType::RightArm,
+                "rightsleeve" => PlayerBodyPartType::RightArmLayer,
+                "leftleg" => PlayerBodyPartType::LeftLeg,
+                "leftpants" => PlayerBodyPartType::LeftLegLayer,
+                "rightleg" => PlayerBodyPartType::RightLeg,
+                "rightpants" => PlayerBodyPartType::RightLegLayer,
+                _ => continue,
+            };
+
+            return Some(body_part);
+        }
+
+        Some(PlayerBodyPartType::Body)
+    }
+
+    /// The transformation of a bone at rest, taking into account all of its parents.
+    fn get_bone_transform(&self, bone: &BedrockBone) -> Affine3A {
+        self.get_ancestors(bone)
+            .fold(Affine3A::IDENTITY, |transform, ancestor| {
+                compute_rotation_around(ancestor.pivot, ancestor.rotation) * transform
+            })
+    }
+}
+
+/// Compute the parts of the bones of `geometry` that move with `body_part`, one group for each bone.
+///
+/// The geometry is mirrored along the X axis to go from the way Bedrock Edition lays models out to ours, like
+/// Blockbench does when importing them, and its UVs are scaled to the size of `skin_texture`.
+pub fn compute_geometry_parts(
+    geometry: &BedrockGeometry,
+    body_part: PlayerBodyPartType,
+    skin_texture: PlayerPartTextureType,
+) -> Vec<Part> {
+    let uv_scale = match geometry.texture_size.0 {
+        0 => 1.0,
+        width => skin_texture.get_texture_size().0 as f32 / width as f32,
+    };
+
+    geometry
+        .bones
+        .iter()
+        .filter(|bone| !bone.cubes.is_empty() && geometry.get_body_part(bone) == Some(body_part))
+        .map(|bone| {
+            let quads = bone
+                .cubes
+                .iter()
+                .flat_map(|cube| compute_cube_quads(cube, uv_scale, skin_texture))
+                .collect();
+
+            let mut group = Part::new_group(
+                skin_texture,
+                quads,
+                #[cfg(feature = "part_tracker")]
+                Some(bone.name.clone()),
+            );
+
+            group.transform_affine(geometry.get_bone_transform(bone));
+
+            group
+        })
+        .collect()
+}
+
+/// Compute the rotation around a pivot, both given the way Bedrock Edition does.
+fn compute_rotation_around(pivot: Vec3, rotation: Vec3) -> Affine3A {
+    let pivot = mirror(pivot);
+    let rotation = Quat::from_euler(
+        EulerRot::ZYX,
+        rotation.z.to_radians(),
+        -rotation.y.to_radians(),
+        -rotation.x.to_radians(),
+    );
+
+    Affine3A::from_translation(pivot) * Affine3A::from_quat(rotation) * Affine3A::from_translation(-pivot)
+}
+
+fn mirror(position: Vec3) -> Vec3 {
+    Vec3::new(-position.x, position.y, position.z)
+}
+
+/// Compute the quads of the faces of a cube that have a texture, since cubes with per-face UVs can leave some out.
+fn compute_cube_quads(cube: &BedrockCube, uv_scale: f32, texture: PlayerPartTextureType) -> Vec<Part> {
+    let min = mirror(cube.origin + Vec3::X * cube.size.x) - cube.inflate;
+    let max = mirror(cube.origin) + Vec3::new(0.0, cube.size.y, cube.size.z) + cube.inflate;
+
+    let uvs = match &cube.uv {
+        BedrockCubeUv::Box(uv) => compute_box_uvs(*uv, cube.size, cube.mirror)
+            .to_array()
+            .map(|uv| Some(scale_face_uv(uv, uv_scale))),
+        BedrockCubeUv::PerFace(faces) => {
+            let mut uvs = faces.map(|face| {
+                face.map(|face| {
+                    let end = face.uv + face.size;
+                    [face.uv.x, face.uv.y, end.x, end.y]
+                })
+            });
+
+            // The bottom is flipped compared to the way box UVs lay it out
+            if let Some([x1, y1, x2, y2]) = uvs[5] {
+                uvs[5] = Some([x1, y2, x2, y1]);
+            }
+
+            uvs.map(|uv| uv.map(|uv| compute_face_uv(uv, uv_scale)))
+        }
+    };
+
+    let rotation = compute_rotation_around(cube.pivot, cube.rotation);
+
+    CubeFaceLayout::of_cube(min, max)
+        .into_iter()
+        .zip(uvs)
+        .filter_map(|(layout, uv)| {
+            let mut quad = compute_face_quad(layout, max - min, uv?, texture)?;
+            quad.transform_affine(rotation);
+
+            Some(quad)
+        })
+        .collect()
+}
+
+pub(crate) fn compute_box_uvs(uv: Vec2, size: Vec3, mirror: bool) -> CubeFaceUvs {
+    let size = size.floor();
+    let uvs = box_uv(
+        (uv.x + size.z) as u16,
+        (uv.y + size.z) as u16,
+        [size.x as u16, size.y as u16, size.z as u16],
+    );
+
+    if !mirror {
+        return uvs;
+    }
+
+    CubeFaceUvs {
+        north: uvs.north.flip_horizontally(),
+        south: uvs.south.flip_horizontally(),
+        east: uvs.west.flip_horizontally(),
+        west: uvs.east.flip_horizontally(),
+        up: uvs.up.flip_horizontally(),
+        down: uvs.down.flip_horizontally(),
+    }
+}
+
+fn compute_face_uv([x1, y1, x2, y2]: [f32; 4], uv_scale: f32) -> FaceUv {
+    let scale = |coordinate: f32| (coordinate * uv_scale).round().max(0.0) as u16;
+
+    FaceUv::new(scale(x1), scale(y1), scale(x2), scale(y2))
+}
+
+fn scale_face_uv(uv: FaceUv, uv_scale: f32) -> FaceUv {
+    let scale = |point: FaceUvPoint| FaceUvPoint {
+        x: (point.x as f32 * uv_scale).round() as u16,
+        y: (point.y as f32 * uv_scale).round() as u16,
+    };
+
+    FaceUv {
+        top_left: scale(uv.top_left),
+        top_right: scale(uv.top_right),
+        bottom_left: scale(uv.bottom_left),
+        bottom_right: scale(uv.bottom_right),
+    }
+}
+
+/// Compute the quad covering a face of a cube of the given size, or [`None`] if the face is flat.
+pub(crate) fn compute_face_quad(layout: CubeFaceLayout, size: Vec3, uv: FaceUv, texture: PlayerPartTextureType) -> Option<Part> {
+    let width = size.dot(layout.column).abs();
+    let height = size.dot(layout.row).abs();
+
+    if width <= 0.0 || height <= 0.0 {
+        return None;
+    }
+
+    let mut quad = Part::new_quad(
+        texture,
+        [0.0, 0.0, 0.0],
+        [1, 1, 0],
+        uv,
+        Vec3::Z,
+        #[cfg(feature = "part_tracker")]
+        None,
+    );
+
+    // Quads go from their bottom left corner, with the texture going right along X and up along Y
+    quad.transform_affine(Affine3A::from_cols(
+        (layout.column * width).into(),
+        (-layout.row * height).into(),
+        layout.normal.into(),
+        (layout.origin + layout.row * height).into(),
+    ));
+
+    Some(quad)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Vec2, Vec3};
+
+    use super::{compute_geometry_parts, BedrockBone, BedrockCube, BedrockCubeUv, BedrockGeometry};
+    use crate::parts::part::Part;
+    use crate::parts::uv::box_uv;
+    use crate::types::{PlayerBodyPartType, PlayerPartTextureType};
+
+    fn bone(name: &str, parent: Option<&str>, cubes: Vec<BedrockCube>) -> BedrockBone {
+        BedrockBone {
+            name: name.to_owned(),
+            parent: parent.map(ToOwned::to_owned),
+            cubes,
+            ..Default::default()
+        }
+    }
+
+    fn cube(origin: [f32; 3], size: [f32; 3], uv: [f32; 2]) -> BedrockCube {
+        BedrockCube {
+            origin: origin.into(),
+            size: size.into(),
+            uv: BedrockCubeUv::Box(Vec2::from(uv)),
+            inflate: 0.0,
+            mirror: false,
+            pivot: Vec3::ZERO,
+            rotation: Vec3::ZERO,
+        }
+    }
+
+    fn humanoid() -> BedrockGeometry {
+        BedrockGeometry {
+            texture_size: (64, 64),
+            bones: vec![
+                bone("root", None, vec![]),
+                bone("body", Some("root"), vec![cube([-4.0, 12.0, -2.0], [8.0, 12.0, 4.0], [16.0, 16.0])]),
+                bone("head", Some("body"), vec![cube([-4.0, 24.0, -4.0], [8.0, 8.0, 8.0], [0.0, 0.0])]),
+                bone("rightArm", Some("body"), vec![cube([-8.0, 12.0, -2.0], [4.0, 12.0, 4.0], [40.0, 16.0])]),
+                bone("rightSleeve", Some("rightArm"), vec![cube([-8.0, 12.0, -2.0], [4.0, 12.0, 4.0], [40.0, 32.0])]),
+                bone("tail", Some("body"), vec![cube([-1.0, 10.0, 2.0], [2.0, 2.0, 6.0], [56.0, 16.0])]),
+                bone("cape", Some("body"), vec![cube([-5.0, 8.0, 2.0], [10.0, 16.0, 1.0], [0.0, 0.0])]),
+            ],
+        }
+    }
+
+    fn quads_of(parts: Vec<Part>) -> Vec<Part> {
+        parts
+            .into_iter()
+            .flat_map(|part| match part {
+                Part::Group { parts, transformation, .. } => parts.into_iter().map(move |mut quad| {
+                    quad.transform_affine(transformation);
+                    quad
+                }),
+                _ => unreachable!("Geometry bones are groups of quads"),
+            })
+            .collect()
+    }
+
+    fn center_of(quad: &Part) -> Vec3 {
+        quad.get_transformation().transform_point3(Vec3::new(0.5, 0.5, 0.0))
+    }
+
+    #[test]
+    fn test_bones_move_with_their_body_part() {
+        let geometry = humanoid();
+        let body_part_of = |name: &str| geometry.get_body_part(geometry.bones.iter().find(|b| b.name == name).unwrap());
+
+        assert_eq!(Some(PlayerBodyPartType::RightArm), body_part_of("rightArm"));
+        assert_eq!(Some(PlayerBodyPartType::RightArmLayer), body_part_of("rightSleeve"));
+        assert_eq!(Some(PlayerBodyPartType::Body), body_part_of("tail"));
+        assert_eq!(Some(PlayerBodyPartType::Body), body_part_of("root"));
+        assert_eq!(None, body_part_of("cape"));
+
+        let body = compute_geometry_parts(&geometry, PlayerBodyPartType::Body, PlayerPartTextureType::VANILLA_SKIN);
+        assert_eq!(2, body.len(), "The body and the tail move with the body, but not the cape");
+    }
+
+    #[test]
+    fn test_humanoid_matches_the_vanilla_model() {
+        let geometry = humanoid();
+        let quads = quads_of(compute_geometry_parts(&geometry, PlayerBodyPartType::Head, PlayerPartTextureType::VANILLA_SKIN));
+
+        assert_eq!(6, quads.len());
+
+        let Part::Quad { face_uv, normal, .. } = &quads[0] else {
+            unreachable!("Cubes are made of quads");
+        };
+
+        // The face of the head is at the front, with the same texture as the vanilla head
+        assert_eq!(box_uv(8, 8, [8, 8, 8]).north, *face_uv);
+        assert!(normal.abs_diff_eq(Vec3::NEG_Z, 1e-4), "{normal}");
+        assert!(center_of(&quads[0]).abs_diff_eq(Vec3::new(0.0, 28.0, -4.0), 1e-4), "{}", center_of(&quads[0]));
+
+        // Bedrock Edition puts the right arm on the negative side of the X axis, while we put it on the positive one
+        let arm = quads_of(compute_geometry_parts(&geometry, PlayerBodyPartType::RightArm, PlayerPartTextureType::VANILLA_SKIN));
+        assert!(center_of(&arm[0]).abs_diff_eq(Vec3::new(6.0, 18.0, -2.0), 1e-4), "{}", center_of(&arm[0]));
+    }
+
+    #[test]
+    fn test_uvs_are_scaled_to_the_skin() {
+        let geometry = humanoid();
+        let quads = quads_of(compute_geometry_parts(&geometry, PlayerBodyPartType::Head, PlayerPartTextureType::Skin { scale: 2 }));
+
+        assert_eq!(box_uv(8, 8, [8, 8, 8]).north.scale(2), quads[0].get_face_uv());
+        assert_eq!(PlayerPartTextureType::Skin { scale: 2 }, quads[0].get_texture());
+    }
+
+    #[test]
+    fn test_bone_rotation_around_pivot() {
+        let mut geometry = humanoid();
+        let head = geometry.bones.iter_mut().find(|b| b.name == "head").unwrap();
+        // Tilt the head forward around the neck, which puts its face at the bottom
+        head.pivot = Vec3::new(0.0, 24.0, 0.0);
+        head.rotation = Vec3::new(90.0, 0.0, 0.0);
+
+        let quads = quads_of(compute_geometry_parts(&geometry, PlayerBodyPartType::Head, PlayerPartTextureType::VANILLA_SKIN));
+        let Part::Quad { normal, .. } = &quads[0] else {
+            unreachable!("Cubes are made of quads");
+        };
+
+        assert!(normal.abs_diff_eq(Vec3::NEG_Y, 1e-4), "{normal}");
+    }
+}
@@ -0,0 +1,262 @@
+use glam::{Affine3A, Quat, Vec2, Vec3};
+
+use crate::parts::geometry::{compute_box_uvs, compute_face_quad};
+use crate::parts::part::Part;
+use crate::parts::uv::{CubeFaceLayout, FaceUv};
+use crate::skeleton::PlayerBone;
+use crate::types::{PlayerBodyPartType, PlayerPartTextureType};
+
+/// A Java Edition block or item model, like the ones of the items held by the player.
+///
+/// Positions are kept the way Java Edition defines them, from 0 to 16 on each axis, and the textures of all of the
+/// faces are stitched together into a single one.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct JavaModel {
+    /// The size of the texture the UVs of the faces are laid out on, in pixels.
+    pub texture_size: (u32, u32),
+    pub elements: Vec<JavaModelElement>,
+    /// How the model is placed in the right hand of a player (`thirdperson_righthand`).
+    pub right_hand_transform: JavaModelTransform,
+    /// How the model is placed in the left hand of a player (`thirdperson_lefthand`), before being mirrored.
+    pub left_hand_transform: JavaModelTransform,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JavaModelElement {
+    /// The corner of the element with the lowest coordinates.
+    pub from: Vec3,
+    /// The corner of the element with the highest coordinates.
+    pub to: Vec3,
+    pub rotation: Option<JavaModelElementRotation>,
+    /// The UVs of each face in pixels, in the order of [`CubeFaceUvs`](crate::parts::uv::CubeFaceUvs) and laid out
+    /// the way Java Edition lays them out. Faces without UVs aren't rendered.
+    pub faces: [Option<FaceUv>; 6],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JavaModelElementRotation {
+    /// The point the element rotates around.
+    pub origin: Vec3,
+    /// The axis the element rotates around, which is one of `X`, `Y` and `Z`.
+    pub axis: Vec3,
+    /// The angle of the rotation, in degrees.
+    pub angle: f32,
+    /// Whether the element is scaled up across the other axes to keep the size it had before the rotation.
+    pub rescale: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JavaModelTransform {
+    /// The rotation of the model, in degrees.
+    pub rotation: Vec3,
+    /// The translation of the model, in pixels.
+    pub translation: Vec3,
+    pub scale: Vec3,
+}
+
+impl Default for JavaModelTransform {
+    fn default() -> Self {
+        Self {
+            rotation: Vec3::ZERO,
+            translation: Vec3::ZERO,
+            scale: Vec3::ONE,
+        }
+    }
+}
+
+impl JavaModelTransform {
+    /// Compute the transformation the way the game applies display transforms, which mirrors the ones of the left hand.
+    fn to_affine(self, is_left_hand: bool) -> Affine3A {
+        let (mut rotation, mut translation) = (self.rotation, self.translation);
+
+        if is_left_hand {
+            rotation.y = -rotation.y;
+            rotation.z = -rotation.z;
+            translation.x = -translation.x;
+        }
+
+        let rotation = Quat::from_rotation_x(rotation.x.to_radians())
+            * Quat::from_rotation_y(rotation.y.to_radians())
+            * Quat::from_rotation_z(rotation.z.to_radians());
+
+        Affine3A::from_translation(translation) * Affine3A::from_quat(rotation) * Affine3A::from_scale(self.scale)
+    }
+}
+
+impl JavaModelElementRotation {
+    fn to_affine(self) -> Affine3A {
+        let mut scale = Vec3::ONE;
+
+        if self.rescale {
+            let factor = 1.0 / self.angle.to_radians().cos();
+            scale += (Vec3::ONE - self.axis.abs()) * (factor - 1.0);
+        }
+
+        Affine3A::from_translation(self.origin)
+            * Affine3A::from_scale(scale)
+            * Affine3A::from_axis_angle(self.axis, self.angle.to_radians())
+            * Affine3A::from_translation(-self.origin)
+    }
+}
+
+impl JavaModelElement {
+    /// Convert a cuboid of an entity model, given the way the game defines them (with `Y` pointing down and its
+    /// texture at `uv`), to the element the game renders when that model is used for an item.
+    pub fn from_entity_cube(uv: Vec2, origin: Vec3, size: Vec3, mirror: bool) -> Self {
+        let uvs = compute_box_uvs(uv, size, mirror);
+        let end = origin + size;
+
+        // Items are rendered from entity models scaled by (1, -1, -1), which turns the cuboid around the Y axis
+        Self {
+            from: Vec3::new(origin.x, -end.y, -end.z),
+            to: Vec3::new(end.x, -origin.y, -origin.z),
+            rotation: None,
+            faces: [
+                uvs.south,
+                uvs.north,
+                uvs.west,
+                uvs.east,
+                uvs.up,
+                uvs.down.flip_vertically(),
+            ]
+            .map(Some),
+        }
+    }
+}
+
+/// Compute the quads of the faces of the elements of `model`, in the space of the model.
+pub fn compute_java_model_parts(model: &JavaModel, texture: PlayerPartTextureType) -> Vec<Part> {
+    model
+        .elements
+        .iter()
+        .flat_map(|element| {
+            let (min, max) = (element.from.min(element.to), element.from.max(element.to));
+            let rotation = element
+                .rotation
+                .map_or(Affine3A::IDENTITY, JavaModelElementRotation::to_affine);
+
+            CubeFaceLayout::of_cube(min, max)
+                .into_iter()
+                .zip(element.faces)
+                .enumerate()
+                .filter_map(move |(index, (layout, uv))| {
+                    // Java Edition lays out the top and the bottom of elements differently than we lay out cubes
+                    let uv = match index {
+                        4 => uv?.rotate_cw().rotate_cw(),
+                        5 => uv?.flip_horizontally(),
+                        _ => uv?,
+                    };
+
+                    let mut quad = compute_face_quad(layout, max - min, uv, texture)?;
+                    quad.transform_affine(rotation);
+
+                    Some(quad)
+                })
+        })
+        .collect()
+}
+
+/// Compute the item held in the hand at the end of `arm`, placed the way the game places the items held by players.
+///
+/// The item is positioned for the arm at rest, so it follows the arm once attached to the bone of `arm`.
+pub fn compute_held_item_part(
+    model: &JavaModel,
+    texture: PlayerPartTextureType,
+    arm: PlayerBodyPartType,
+    is_slim_arms: bool,
+) -> Part {
+    let bone = PlayerBone::from_body_part(arm);
+    let is_left_hand = bone.is_left();
+    let side = if is_left_hand { -1.0 } else { 1.0 };
+
+    // The game holds items one pixel closer to the body than the pivot of the arm
+    let anchor = bone.get_pivot(is_slim_arms) - Vec3::X * side;
+
+    let display = if is_left_hand {
+        model.left_hand_transform
+    } else {
+        model.right_hand_transform
+    };
+
+    // The game places items in entity model space, which is upside down and mirrored along X compared to ours
+    let transform = Affine3A::from_translation(anchor)
+        * Affine3A::from_scale(Vec3::new(-1.0, -1.0, 1.0))
+        * Affine3A::from_rotation_x((-90f32).to_radians())
+        * Affine3A::from_rotation_y(180f32.to_radians())
+        * Affine3A::from_translation(Vec3::new(side, 2.0, -10.0))
+        * display.to_affine(is_left_hand)
+        * Affine3A::from_translation(Vec3::splat(-8.0));
+
+    let mut group = Part::new_group(
+        texture,
+        compute_java_model_parts(model, texture),
+        #[cfg(feature = "part_tracker")]
+        Some(format!("{arm} Item")),
+    );
+
+    group.transform_affine(transform);
+
+    group
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Affine3A, Vec3};
+
+    use super::{compute_held_item_part, compute_java_model_parts, JavaModel, JavaModelElement};
+    use crate::parts::part::Part;
+    use crate::parts::uv::FaceUv;
+    use crate::types::{PlayerBodyPartType, PlayerPartTextureType};
+
+    const TEXTURE: PlayerPartTextureType = PlayerPartTextureType::Custom {
+        key: "item",
+        size: (16, 16),
+    };
+
+    fn cube_model() -> JavaModel {
+        JavaModel {
+            texture_size: (16, 16),
+            elements: vec![JavaModelElement {
+                from: Vec3::splat(6.0),
+                to: Vec3::splat(10.0),
+                rotation: None,
+                faces: [Some(FaceUv::new(0, 0, 4, 4)); 6],
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn center_of(quad: &Part, transform: Affine3A) -> Vec3 {
+        (transform * quad.get_transformation()).transform_point3(Vec3::new(0.5, 0.5, 0.0))
+    }
+
+    #[test]
+    fn test_faces_of_elements() {
+        let quads = compute_java_model_parts(&cube_model(), TEXTURE);
+        assert_eq!(6, quads.len());
+
+        assert!(center_of(&quads[0], Affine3A::IDENTITY).abs_diff_eq(Vec3::new(8.0, 8.0, 6.0), 1e-4));
+
+        // Java Edition lays the top of elements out from their north west corner, which we lay out from the south east
+        let up = &quads[4];
+        assert!(up.get_transformation().transform_point3(Vec3::Y).abs_diff_eq(Vec3::splat(10.0), 1e-4));
+        assert_eq!(FaceUv::new(0, 0, 4, 4).bottom_right, up.get_face_uv().top_left);
+    }
+
+    #[test]
+    fn test_held_items_are_in_the_hands() {
+        let model = cube_model();
+
+        for (arm, hand) in [
+            (PlayerBodyPartType::RightArm, Vec3::new(6.0, 12.0, -2.0)),
+            (PlayerBodyPartType::LeftArm, Vec3::new(-6.0, 12.0, -2.0)),
+        ] {
+            let Part::Group { parts, transformation, .. } = compute_held_item_part(&model, TEXTURE, arm, false) else {
+                unreachable!("Held items are groups of quads");
+            };
+
+            let center = parts.iter().map(|quad| center_of(quad, transformation)).sum::<Vec3>() / parts.len() as f32;
+            assert!(center.abs_diff_eq(hand, 1e-4), "The item in the {arm} is at {center}");
+        }
+    }
+}
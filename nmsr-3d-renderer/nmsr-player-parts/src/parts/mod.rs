@@ -1,5 +1,8 @@
+pub mod geometry;
+pub mod java_model;
 pub mod part;
 pub mod provider;
 pub mod uv;
+pub mod voxels;
 #[cfg(feature = "part_tracker")]
 pub mod tracking;
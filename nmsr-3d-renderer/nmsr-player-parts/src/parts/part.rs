@@ -1,6 +1,7 @@
 use super::provider::minecraft::compute_base_part;
 use crate::parts::part::Part::{Cube, Quad};
 use crate::parts::uv::{CubeFaceUvs, FaceUv};
+use crate::skeleton::PlayerBone;
 use crate::types::{PlayerBodyPartType, PlayerPartTextureType};
 use glam::{Affine3A, Quat, Vec3};
 
@@ -66,6 +67,7 @@ pub enum Part {
         transformation: Affine3A,
         face_uvs: CubeFaceUvs,
         texture: PlayerPartTextureType,
+        bone: Option<PlayerBone>,
         #[cfg(feature = "part_tracker")]
         part_tracking_data: PartTrackingData,
     },
@@ -76,6 +78,7 @@ pub enum Part {
         face_uv: FaceUv,
         normal: Vec3,
         texture: PlayerPartTextureType,
+        bone: Option<PlayerBone>,
         #[cfg(feature = "part_tracker")]
         part_tracking_data: PartTrackingData,
     },
@@ -85,6 +88,7 @@ pub enum Part {
         parts: Vec<Part>,
         transformation: Affine3A,
         texture: PlayerPartTextureType,
+        bone: Option<PlayerBone>,
         #[cfg(feature = "part_tracker")]
         part_tracking_data: PartTrackingData,
     },
@@ -98,8 +102,8 @@ impl Part {
     /// * `pos`: The position of the cube. [x, y, z]
     /// * `size`: The size of the cube. [x, y, z]
     /// * `uvs`: The UVs of the cube.
-    /// UVs are in the following order: [North, South, East, West, Up, Down]
-    /// Each UV is in the following order: [Top left, Bottom right]
+    ///   UVs are in the following order: [North, South, East, West, Up, Down]
+    ///   Each UV is in the following order: [Top left, Bottom right]
     ///
     /// returns: [Part]
     pub fn new_cube(
@@ -119,6 +123,7 @@ impl Part {
             transformation: transform,
             face_uvs: uvs,
             texture,
+            bone: None,
             #[cfg(feature = "part_tracker")]
             part_tracking_data: PartTrackingData::new(name),
         }
@@ -135,7 +140,7 @@ impl Part {
         let transform = Affine3A::from_scale_rotation_translation(
             Vec3::ONE,
             Quat::IDENTITY,
-            Vec3::new(pos[0] as f32, pos[1] as f32, pos[2] as f32),
+            Vec3::new(pos[0], pos[1], pos[2]),
         );
 
         Quad {
@@ -144,6 +149,7 @@ impl Part {
             face_uv: uvs,
             normal,
             texture,
+            bone: None,
             #[cfg(feature = "part_tracker")]
             part_tracking_data: PartTrackingData::new(name),
         }
@@ -158,6 +164,7 @@ impl Part {
             parts,
             transformation: Affine3A::IDENTITY,
             texture,
+            bone: None,
             #[cfg(feature = "part_tracker")]
             part_tracking_data: PartTrackingData::new(name),
         }
@@ -282,6 +289,22 @@ impl Part {
         }
     }
 
+    /// The bone this part is attached to, which moves it from the rest pose to the pose of the player.
+    /// Parts without a bone (like the shadow) always stay where they were built.
+    pub fn get_bone(&self) -> Option<PlayerBone> {
+        match self {
+            Self::Cube { bone, .. } | Self::Quad { bone, .. } | Self::Group { bone, .. } => *bone,
+        }
+    }
+
+    pub fn set_bone(&mut self, new_bone: PlayerBone) {
+        match self {
+            Self::Cube { bone, .. } | Self::Quad { bone, .. } | Self::Group { bone, .. } => {
+                bone.replace(new_bone);
+            }
+        }
+    }
+
     pub fn get_texture(&self) -> PlayerPartTextureType {
         match self {
             Cube { texture, .. } => *texture,
@@ -304,6 +327,45 @@ impl Part {
         }
     }
 
+    /// Move the parts laid out on the vanilla skin over to `skin`, scaling their UVs up to its size.
+    /// Groups are handled part by part, since each part keeps track of its own texture.
+    pub fn use_skin_texture(&mut self, skin: PlayerPartTextureType) {
+        let PlayerPartTextureType::Skin { scale } = skin else {
+            return;
+        };
+
+        if let Self::Group { parts, .. } = self {
+            for part in parts.iter_mut() {
+                part.use_skin_texture(skin);
+            }
+        }
+
+        if self.get_texture() != PlayerPartTextureType::VANILLA_SKIN {
+            return;
+        }
+
+        match self {
+            Cube { face_uvs, .. } => *face_uvs = face_uvs.scale(scale as u16),
+            Quad { face_uv, .. } => *face_uv = face_uv.scale(scale as u16),
+            Self::Group { .. } => {}
+        }
+
+        self.set_texture(skin);
+    }
+
+    /// Move the parts using the `from` texture over to `to`, keeping their UVs as they are.
+    pub fn replace_texture(&mut self, from: PlayerPartTextureType, to: PlayerPartTextureType) {
+        if let Self::Group { parts, .. } = self {
+            for part in parts.iter_mut() {
+                part.replace_texture(from, to);
+            }
+        }
+
+        if self.get_texture() == from {
+            self.set_texture(to);
+        }
+    }
+
     pub fn get_face_uv(&self) -> FaceUv {
         match self {
             Cube { face_uvs, .. } => unimplemented!("Cannot get face UV on a cube"),
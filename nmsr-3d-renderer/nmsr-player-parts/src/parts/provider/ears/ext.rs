@@ -2,10 +2,19 @@ use crate::{model::ArmorMaterial, parts::provider::PlayerPartProviderContext};
 
 pub(crate) trait PlayerPartProviderContextExt<M: ArmorMaterial> {
     fn is_wearing_boots(&self) -> bool;
+
+    /// How far animated wings flap around their rest angle, in degrees, like the Ears mod flaps them while the player
+    /// stands still, with the time of the render in ticks. They're back where they started after every
+    /// [`ANIMATED_PARTS_PERIOD`](crate::parts::provider::ANIMATED_PARTS_PERIOD) ticks.
+    fn get_wing_wiggle(&self) -> f32;
 }
 
 impl<M: ArmorMaterial> PlayerPartProviderContextExt<M> for PlayerPartProviderContext<M> {
     fn is_wearing_boots(&self) -> bool {
         self.armor_slots.as_ref().is_some_and(|s| s.boots.is_some())
     }
-}
\ No newline at end of file
+
+    fn get_wing_wiggle(&self) -> f32 {
+        f32::sin((self.t + 8.0) / 12.0) * 2.0
+    }
+}
@@ -23,13 +23,14 @@ pub(crate) struct EarsModPartBuilder<'a, M: ArmorMaterial> {
     context: &'a PlayerPartProviderContext<M>,
 }
 
+#[allow(clippy::too_many_arguments)] // Mirrors the quad methods of the Ears model builder
 impl<'a, M: ArmorMaterial> EarsModPartBuilder<'a, M> {
     pub(crate) fn new(
         target: &'a mut Vec<Part>,
         context: &'a PlayerPartProviderContext<M>,
     ) -> Self {
         Self {
-            texture_stack: vec![PlayerPartTextureType::Skin],
+            texture_stack: vec![PlayerPartTextureType::VANILLA_SKIN],
             transformation_stack: vec![Affine3A::IDENTITY],
             group_stack: vec![],
             mesh_stack: vec![],
@@ -76,7 +77,7 @@ impl<'a, M: ArmorMaterial> EarsModPartBuilder<'a, M> {
             .expect("Expected group stack to not be empty");
     }
 
-    pub(crate) fn stack_group<F: FnOnce(&mut Self) -> ()>(
+    pub(crate) fn stack_group<F: FnOnce(&mut Self)>(
         &mut self,
         name: impl Into<String>,
         action: F,
@@ -101,13 +102,13 @@ impl<'a, M: ArmorMaterial> EarsModPartBuilder<'a, M> {
         self.translate(x, y, z);
     }
 
-    pub(crate) fn stack<F: FnOnce(&mut Self) -> ()>(&mut self, action: F) {
+    pub(crate) fn stack<F: FnOnce(&mut Self)>(&mut self, action: F) {
         self.push();
         action(self);
         self.pop();
     }
 
-    pub(crate) fn stack_texture<F: FnOnce(&mut Self) -> ()>(
+    pub(crate) fn stack_texture<F: FnOnce(&mut Self)>(
         &mut self,
         texture: PlayerPartTextureType,
         action: F,
@@ -117,7 +118,7 @@ impl<'a, M: ArmorMaterial> EarsModPartBuilder<'a, M> {
         self.texture_stack.pop();
     }
 
-    pub(crate) fn stack_mesh<F: FnOnce(&mut Self) -> ()>(
+    pub(crate) fn stack_mesh<F: FnOnce(&mut Self)>(
         &mut self,
         name: impl Into<String>,
         action: F,
@@ -261,7 +262,7 @@ impl<'a, M: ArmorMaterial> EarsModPartBuilder<'a, M> {
         let mut name: String = name.into();
         name.push_str(" (Front)");
 
-        return self.quad(u, v, width, height, rot, flip, true, name);
+        self.quad(u, v, width, height, rot, flip, true, name)
     }
 
     pub(crate) fn quad_back(
@@ -277,7 +278,7 @@ impl<'a, M: ArmorMaterial> EarsModPartBuilder<'a, M> {
         let mut name: String = name.into();
         name.push_str(" (Back)");
 
-        return self.quad(u, v, width, height, rot, flip, false, name);
+        self.quad(u, v, width, height, rot, flip, false, name)
     }
 
     pub(crate) fn quad(
@@ -291,7 +292,7 @@ impl<'a, M: ArmorMaterial> EarsModPartBuilder<'a, M> {
         front_facing: bool,
         name: impl Into<String>,
     ) {
-        return self.textured_quad(
+        self.textured_quad(
             u,
             v,
             width,
@@ -301,7 +302,7 @@ impl<'a, M: ArmorMaterial> EarsModPartBuilder<'a, M> {
             flip,
             front_facing,
             name,
-        );
+        )
     }
 
     pub(crate) fn textured_quad(
@@ -98,8 +98,8 @@ impl<M: ArmorMaterial> EarsModPartProvider<M> for EarsModTailsPartProvider<M> {
 
             let seg_height = 12 / segments;
             
-            for i in 0..segments {
-                b.rotate(angles[i], 0., 0.);
+            for (i, &angle) in angles.iter().enumerate().take(segments) {
+                b.rotate(angle, 0., 0.);
                 b.quad_front(
                     56,
                     (16 + (i * seg_height)) as u16,
@@ -7,7 +7,8 @@ use crate::{
     model::ArmorMaterial,
     parts::provider::{
         ears::{
-            providers::builder::EarsModPartBuilder, EarsModPartProvider, PlayerPartEarsTextureType,
+            ext::PlayerPartProviderContextExt, providers::builder::EarsModPartBuilder, EarsModPartProvider,
+            PlayerPartEarsTextureType,
         },
         PlayerPartProviderContext,
     },
@@ -54,7 +55,7 @@ impl<M: ArmorMaterial> EarsModPartProvider<M> for EarsModWingsPartProvider<M> {
 
             b.stack(|b| {
                 let wiggle = if wing.animated {
-                    f32::sin(8.0 / 12.0) * 2.0
+                    context.get_wing_wiggle()
                 } else {
                     0.0
                 };
@@ -1,11 +1,20 @@
 use std::marker::PhantomData;
 
-use glam::Vec3;
-
-use crate::model::{ArmorMaterial, PlayerArmorSlot, PlayerArmorSlots};
+use glam::{Affine3A, EulerRot, Quat, Vec3};
+
+use crate::model::item::PlayerHand;
+use crate::model::mob::HumanoidMob;
+use crate::model::nametag::compute_top;
+use crate::model::{ArmorMaterial, ElytraState, PlayerArmorSlot, PlayerArmorSlots};
+use crate::parts::geometry::compute_geometry_parts;
+use crate::parts::java_model::compute_held_item_part;
 use crate::parts::part::{Part, PartAnchorInfo};
 use crate::parts::provider::{PartsProvider, PlayerPartProviderContext};
-use crate::parts::uv::uv_from_pos_and_size;
+#[cfg(feature = "ears")]
+use crate::parts::provider::PlayerPartsProvider;
+use crate::parts::uv::{box_uv, uv_from_pos_and_size, CubeFaceUvs};
+use crate::parts::voxels::compute_voxel_layer_part;
+use crate::skeleton::PlayerBone;
 use crate::types::PlayerBodyPartType::*;
 use crate::types::{PlayerBodyPartType, PlayerPartTextureType};
 
@@ -24,7 +33,7 @@ macro_rules! body_part {
             pos: $pos,
             size: $size,
             box_uv_start: ($uv_x, $uv_y),
-            texture_type: Skin,
+            texture_type: VANILLA_SKIN,
             name: $name
         }
     };
@@ -65,24 +74,32 @@ impl<M: ArmorMaterial> PartsProvider<M> for MinecraftPlayerPartsProvider<M> {
 
         let non_layer_body_part_type = body_part.get_non_layer_part();
 
-        let part = compute_base_part(non_layer_body_part_type, context.model.is_slim_arms());
+        let mut result = if let Some(mob) = context.mob {
+            compute_mob_parts(mob, body_part)
+        } else if let Some(geometry) = &context.geometry {
+            compute_geometry_parts(geometry, body_part, context.skin_texture)
+        } else if body_part.is_layer() || body_part.is_hat_layer() {
+            vec![compute_layer_part(context, non_layer_body_part_type)]
+        } else {
+            vec![compute_base_part(non_layer_body_part_type, context.model.is_slim_arms())]
+        };
 
         if body_part.is_layer() || body_part.is_hat_layer() {
-            let expand_offset = get_layer_expand_offset(non_layer_body_part_type);
-            let box_uv_offset: (i32, i32) = get_body_part_layer_uv_offset(non_layer_body_part_type);
-
-            return vec![expand_player_body_part(
-                non_layer_body_part_type,
-                part,
-                expand_offset,
-                box_uv_offset,
-            )];
+            return result;
         }
 
-        let mut result = vec![part];
-
-        if body_part == Body && context.has_cape {
-            append_cape_part(&mut result);
+        if body_part == Body {
+            if let Some(elytra) = context.elytra {
+                let texture = if context.has_cape {
+                    PlayerPartTextureType::Cape
+                } else {
+                    PlayerPartTextureType::VANILLA_ELYTRA
+                };
+
+                append_elytra_parts(&mut result, elytra, texture);
+            } else if context.has_cape {
+                append_cape_parts(&mut result, context);
+            }
         }
 
         if body_part == Head {
@@ -100,6 +117,21 @@ impl<M: ArmorMaterial> PartsProvider<M> for MinecraftPlayerPartsProvider<M> {
 
                 result.push(shadow);
             }
+
+            if let Some(headwear) = context.headwear {
+                result.extend(headwear.get_parts());
+            }
+        }
+
+        let held_item = match body_part {
+            RightArm => context.main_hand.as_ref().map(|model| (PlayerHand::MainHand, model)),
+            LeftArm => context.off_hand.as_ref().map(|model| (PlayerHand::OffHand, model)),
+            _ => None,
+        };
+
+        if let Some((hand, model)) = held_item {
+            let texture = hand.get_texture(model.texture_size);
+            result.push(compute_held_item_part(model, texture, body_part, context.model.is_slim_arms()));
         }
 
         if let Some(armor_slots) = &context.armor_slots {
@@ -129,16 +161,68 @@ impl<M: ArmorMaterial> PartsProvider<M> for MinecraftPlayerPartsProvider<M> {
                         }
 
                         armor_part.set_texture(texture);
+
+                        if armor_slot.is_enchanted() {
+                            let mut glint_part = armor_part.clone();
+                            glint_part.set_texture(PlayerPartTextureType::Glint);
+                            result.push(glint_part);
+                        }
+
                         result.push(armor_part);
                     }
                 }
             }
         }
 
+        if let Some(nametag) = context.nametag.filter(|_| body_part == Head) {
+            // Ears (and horns) can stick out of the top of the head
+            #[cfg(feature = "ears")]
+            let ears = PlayerPartsProvider::Ears.get_parts(context, Head);
+            #[cfg(not(feature = "ears"))]
+            let ears = vec![];
+
+            let head_top = compute_top(&result).max(compute_top(&ears));
+            result.push(nametag.get_part(head_top));
+        }
+
         result
     }
 }
 
+/// Compute the layer covering a body part, either as an inflated box or out of voxels.
+fn compute_layer_part<M: ArmorMaterial>(
+    context: &PlayerPartProviderContext<M>,
+    non_layer_body_part_type: PlayerBodyPartType,
+) -> Part {
+    let part = compute_base_part(non_layer_body_part_type, context.model.is_slim_arms());
+
+    let expand_offset = get_layer_expand_offset(non_layer_body_part_type);
+    let box_uv_offset: (i32, i32) = get_body_part_layer_uv_offset(non_layer_body_part_type);
+
+    if let Some(mask) = &context.voxel_layers {
+        // The voxels stick out as far as the inflated layer would
+        return compute_voxel_layer_part(&part, box_uv_offset, expand_offset * 2.0, mask);
+    }
+
+    expand_player_body_part(non_layer_body_part_type, part, expand_offset, box_uv_offset)
+}
+
+/// Compute the parts of a mob that move with a body part. The parts that never move come along with the body, but
+/// stay in place whatever the pose is.
+fn compute_mob_parts(mob: HumanoidMob, body_part: PlayerBodyPartType) -> Vec<Part> {
+    let texture = mob.get_texture();
+    let mut parts = compute_geometry_parts(&mob.get_geometry(), body_part, texture);
+
+    if let Some(static_geometry) = mob.get_static_geometry().filter(|_| body_part == Body) {
+        for mut part in compute_geometry_parts(&static_geometry, Body, texture) {
+            part.set_bone(PlayerBone::Root);
+            parts.push(part);
+        }
+    }
+
+    parts
+}
+
 #[cfg(feature = "part_tracker")]
 pub fn get_part_group_name(non_layer_body_part_type: PlayerBodyPartType) -> &'static str {
     match non_layer_body_part_type {
@@ -219,49 +303,135 @@ pub fn compute_base_part(non_layer_body_part_type: PlayerBodyPartType, is_slim_a
     }
 }
 
-pub(crate) fn perform_arm_part_rotation(
-    non_layer_body_part_type: PlayerBodyPartType,
-    part: &mut Part,
-    is_slim_arms: bool,
-    arm_rotation_angle: f32,
-) {
-    let normal_part = compute_base_part(non_layer_body_part_type, is_slim_arms);
-    let normal_part_size = normal_part.get_size();
-
-    if non_layer_body_part_type == LeftArm {
-        let anchor = normal_part.get_position() + normal_part_size * Vec3::new(1.0, 1.0, 0.5);
-
-        part.rotate(
-            [0.0, 0.0, -arm_rotation_angle].into(),
-            Some(PartAnchorInfo::new_rotation_anchor_position(anchor)),
-        );
-    } else if non_layer_body_part_type == RightArm {
-        let anchor = normal_part.get_position() + normal_part_size * Vec3::new(0.0, 1.0, 0.5);
-
-        part.rotate(
-            [0.0, 0.0, arm_rotation_angle].into(),
-            Some(PartAnchorInfo::new_rotation_anchor_position(anchor)),
-        );
-    }
-}
-
-fn append_cape_part(result: &mut Vec<Part>) {
-    let mut cape = body_part! {
+/// Compute the cape cuboid as it hangs behind the body, before it's flipped to face backwards.
+/// Its front (north) face is the outside of the cape.
+pub fn compute_cape_part() -> Part {
+    body_part! {
         pos: [-5, 8, 1],
         size: [10, 16, 1],
         box_uv_start: (1, 1),
         texture_type: Cape,
         name: "Cape"
-    };
+    }
+}
+
+/// Compute the parts used to show a cape on its own, with the outside of the cape on the left and
+/// the inside on the right, both facing the front.
+pub fn compute_cape_showcase_parts() -> Vec<Part> {
+    let mut outside = compute_cape_part();
+    outside.translate([-6.0, 0.0, -1.5].into());
 
-    cape.rotate(
-        [5.0, 180.0, 0.0].into(),
+    let mut inside = compute_cape_part();
+    inside.rotate(
+        [0.0, 180.0, 0.0].into(),
         Some(PartAnchorInfo::new_rotation_anchor_position(
-            [0.0, 24.0, 2.0].into(),
+            [0.0, 16.0, 1.5].into(),
         )),
     );
+    inside.translate([6.0, 0.0, -1.5].into());
 
-    result.push(cape);
+    vec![outside, inside]
+}
+
+fn append_cape_parts<M: ArmorMaterial>(result: &mut Vec<Part>, context: &PlayerPartProviderContext<M>) {
+    let state = context.cape_state;
+    // The cape hangs from the top of the back, and is flipped to face backwards
+    let mut hinge = Vec3::new(0.0, 24.0, 2.0);
+
+    if !state.is_wavy() {
+        let mut cape = compute_cape_part();
+
+        cape.rotate(
+            [state.angle, 180.0, 0.0].into(),
+            Some(PartAnchorInfo::new_rotation_anchor_position(hinge)),
+        );
+
+        result.push(cape);
+        return;
+    }
+
+    let body_lean = context.pose.map(|pose| pose.body_lean).unwrap_or_default();
+
+    // Each strip is a row of the cape hanging from the bottom edge of the strip above it
+    for (row, angle) in state.simulate_strip_angles(body_lean).into_iter().enumerate() {
+        let mut strip = body_part! {
+            pos: [-5, -1, -1],
+            size: [10, 1, 1],
+            box_uv_start: (1, 1 + row as u16),
+            texture_type: Cape,
+            name: "Cape"
+        };
+
+        strip.rotate([angle, 180.0, 0.0].into(), None);
+        strip.translate(hinge);
+
+        let angle = angle.to_radians();
+        hinge += Vec3::new(0.0, -angle.cos(), angle.sin());
+
+        result.push(strip);
+    }
+}
+
+fn append_elytra_parts(result: &mut Vec<Part>, state: ElytraState, texture: PlayerPartTextureType) {
+    // Rotations of the left wing in degrees (pitch, yaw, roll), taken from the game
+    let (pitch, yaw, roll) = match state {
+        ElytraState::Folded => (15.0f32, 0.0f32, -15.0f32),
+        ElytraState::Open => (20.0, 0.0, -90.0),
+    };
+
+    let uvs = box_uv(24, 2, [10, 20, 2]);
+
+    // Both wings cover the whole back, hinged on opposite sides
+    for (is_left, pivot_x) in [(true, -5.0), (false, 5.0)] {
+        // The right wing is a mirror image of the left one
+        let (uvs, yaw, roll) = if is_left {
+            (uvs, yaw, roll)
+        } else {
+            (mirror_box_uvs(uvs), -yaw, -roll)
+        };
+
+        let mut wing = Part::new_cube(
+            texture,
+            [-5, 4, 2],
+            [10, 20, 2],
+            uvs,
+            #[cfg(feature = "part_tracker")]
+            Some(if is_left { "Left Elytra Wing" } else { "Right Elytra Wing" }.to_string()),
+        )
+        .expand_splat(1.0);
+
+        // The game applies the rotations in Z, Y, X order, with the pitch and yaw inverted in our coordinate space
+        let rotation = Quat::from_euler(
+            EulerRot::ZYX,
+            roll.to_radians(),
+            (-yaw).to_radians(),
+            (-pitch).to_radians(),
+        );
+
+        let pivot = Vec3::new(pivot_x, 24.0, 2.0);
+
+        wing.transform_affine(
+            Affine3A::from_translation(pivot)
+                * Affine3A::from_quat(rotation)
+                * Affine3A::from_translation(-pivot),
+        );
+
+        #[cfg(feature = "part_tracker")]
+        wing.part_tracking_data_mut().set_last_rotation_origin(pivot);
+
+        result.push(wing);
+    }
+}
+
+fn mirror_box_uvs(uvs: CubeFaceUvs) -> CubeFaceUvs {
+    CubeFaceUvs {
+        north: uvs.north.flip_horizontally(),
+        south: uvs.south.flip_horizontally(),
+        east: uvs.west.flip_horizontally(),
+        west: uvs.east.flip_horizontally(),
+        up: uvs.up.flip_horizontally(),
+        down: uvs.down.flip_horizontally(),
+    }
 }
 
 fn expand_player_body_part(
@@ -322,3 +492,125 @@ fn get_layer_expand_offset(body_part: PlayerBodyPartType) -> f32 {
         _ => 0.25,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::{compute_cape_showcase_parts, MinecraftPlayerPartsProvider};
+    use crate::cape::CapeState;
+    use crate::model::ElytraState;
+    use crate::parts::provider::{PartsProvider, PlayerPartProviderContext, PlayerPartsProvider};
+    use crate::types::{PlayerBodyPartType, PlayerPartTextureType};
+
+    fn body_textures(has_cape: bool, elytra: Option<ElytraState>) -> Vec<PlayerPartTextureType> {
+        let context = PlayerPartProviderContext::<()> {
+            has_cape,
+            elytra,
+            ..Default::default()
+        };
+
+        MinecraftPlayerPartsProvider::default()
+            .get_parts(&context, PlayerBodyPartType::Body)
+            .iter()
+            .map(|part| part.get_texture())
+            .filter(|texture| !texture.is_skin())
+            .collect()
+    }
+
+    #[test]
+    fn test_elytra_replaces_cape() {
+        use PlayerPartTextureType::Cape;
+
+        assert_eq!(vec![Cape], body_textures(true, None), "A cape is a single part");
+        assert!(body_textures(false, None).is_empty());
+
+        for state in [ElytraState::Folded, ElytraState::Open] {
+            assert_eq!(
+                vec![Cape, Cape],
+                body_textures(true, Some(state)),
+                "The elytra should replace the cape and use its texture"
+            );
+            assert_eq!(
+                vec![PlayerPartTextureType::VANILLA_ELYTRA; 2],
+                body_textures(false, Some(state)),
+                "The elytra should use the vanilla texture without a cape"
+            );
+        }
+    }
+
+    #[test]
+    fn test_cape_showcase_shows_both_sides() {
+        let parts = compute_cape_showcase_parts();
+        assert_eq!(2, parts.len());
+
+        let [outside, inside] = [&parts[0], &parts[1]].map(|part| {
+            assert_eq!(PlayerPartTextureType::Cape, part.get_texture());
+
+            let transformation = part.get_transformation();
+            let center = transformation.transform_point3(Vec3::splat(0.5));
+            let front = transformation.transform_vector3(Vec3::NEG_Z).normalize();
+
+            (center, front)
+        });
+
+        assert!(outside.0.abs_diff_eq(Vec3::new(-6.0, 16.0, 0.0), 1e-4));
+        assert!(inside.0.abs_diff_eq(Vec3::new(6.0, 16.0, 0.0), 1e-4));
+
+        // The outside of the cape is its front face, so it should face the camera on the left and away from it on the right
+        assert!(outside.1.abs_diff_eq(Vec3::NEG_Z, 1e-4));
+        assert!(inside.1.abs_diff_eq(Vec3::Z, 1e-4));
+    }
+
+    #[test]
+    fn test_wavy_cape_is_split_into_strips() {
+        let context = PlayerPartProviderContext::<()> {
+            has_cape: true,
+            cape_state: CapeState {
+                wind: Some(4.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let strips: Vec<_> = MinecraftPlayerPartsProvider::default()
+            .get_parts(&context, PlayerBodyPartType::Body)
+            .into_iter()
+            .filter(|part| part.get_texture() == PlayerPartTextureType::Cape)
+            .collect();
+
+        assert_eq!(CapeState::STRIP_COUNT, strips.len());
+
+        // Each strip should hang from the bottom edge of the one above it
+        for pair in strips.windows(2) {
+            let bottom_of_upper = pair[0].get_transformation().transform_point3(Vec3::new(0.5, 0.0, 1.0));
+            let top_of_lower = pair[1].get_transformation().transform_point3(Vec3::new(0.5, 1.0, 1.0));
+
+            assert!(bottom_of_upper.abs_diff_eq(top_of_lower, 1e-4), "{bottom_of_upper} != {top_of_lower}");
+        }
+    }
+
+    #[test]
+    fn test_hd_skin_scales_uvs() {
+        assert_eq!(Some(PlayerPartTextureType::VANILLA_SKIN), PlayerPartTextureType::skin_of_size(64, 32));
+        assert_eq!(Some(PlayerPartTextureType::Skin { scale: 4 }), PlayerPartTextureType::skin_of_size(256, 256));
+        assert_eq!(None, PlayerPartTextureType::skin_of_size(192, 192), "Only power of two scales are valid");
+        assert_eq!(None, PlayerPartTextureType::skin_of_size(128, 96));
+
+        let skin_texture = PlayerPartTextureType::Skin { scale: 2 };
+        let context = PlayerPartProviderContext::<()> {
+            skin_texture,
+            ..Default::default()
+        };
+
+        let parts = PlayerPartsProvider::Minecraft.get_parts(&context, PlayerBodyPartType::Head);
+        let head = &parts[0];
+
+        assert_eq!(skin_texture, head.get_texture());
+        assert_eq!((128, 128), head.get_texture().get_texture_size());
+
+        let front = head.get_face_uvs().north;
+        assert_eq!((16, 16), (front.top_left.x, front.top_left.y));
+        assert_eq!((32, 32), (front.bottom_right.x, front.bottom_right.y));
+    }
+}
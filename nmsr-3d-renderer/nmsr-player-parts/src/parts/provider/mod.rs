@@ -1,19 +1,33 @@
 
-use self::minecraft::{perform_arm_part_rotation, MinecraftPlayerPartsProvider};
-use crate::model::{ArmorMaterial, PlayerArmorSlots, PlayerModel};
+use self::minecraft::MinecraftPlayerPartsProvider;
+use crate::cape::CapeState;
+use crate::model::headwear::Headwear;
+use crate::model::mob::HumanoidMob;
+use crate::model::nametag::Nametag;
+use crate::model::{ArmorMaterial, ElytraState, PlayerArmorSlots, PlayerModel};
+use crate::parts::geometry::BedrockGeometry;
+use crate::parts::java_model::JavaModel;
 use crate::parts::part::Part;
-use crate::types::PlayerBodyPartType;
+use crate::parts::voxels::SkinLayerMask;
+use crate::pose::PlayerPose;
+use crate::skeleton::{PlayerBone, PlayerSkeleton};
+use crate::types::{PlayerBodyPartType, PlayerPartTextureType};
 #[cfg(feature = "ears")]
 use ears_rs::features::EarsFeatures;
+use std::sync::Arc;
 
 #[cfg(feature = "ears")]
 pub mod ears;
 
-#[cfg(all(feature = "ears"))]
+#[cfg(feature = "ears")]
 use self::ears::EarsPlayerPartsProvider;
 
 pub mod minecraft;
 
+/// The time after which the animated parts of the player, like the flapping wings of Ears skins, are back where they
+/// started, in ticks.
+pub const ANIMATED_PARTS_PERIOD: f32 = 24.0 * std::f32::consts::PI;
+
 #[derive(Copy, Clone)]
 pub enum PlayerPartsProvider {
     Minecraft,
@@ -22,24 +36,67 @@ pub enum PlayerPartsProvider {
 }
 
 /// Context for player parts.
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct PlayerPartProviderContext<M = ()>
 where
     M: ArmorMaterial,
 {
     pub model: PlayerModel,
+    /// The mob rendered instead of the player, wearing the armor of the player.
+    pub mob: Option<HumanoidMob>,
+    /// The skin texture of the player, which can be larger than the vanilla one for HD skins.
+    pub skin_texture: PlayerPartTextureType,
+    /// Custom geometry replacing the cuboids of the player (and their layers), like Bedrock Edition skins can have.
+    pub geometry: Option<Arc<BedrockGeometry>>,
     pub has_hat_layer: bool,
     pub has_layers: bool,
+    /// The opaque pixels of the skin, used to build the layers out of voxels (like the 3D Skin Layers mod does)
+    /// instead of inflated boxes.
+    pub voxel_layers: Option<SkinLayerMask>,
     pub has_cape: bool,
     pub is_optifine_cape: bool,
+    /// How the cape hangs from the shoulders, and whether it bends like cloth.
+    pub cape_state: CapeState,
+    /// The elytra worn by the player, which replaces the cape and uses its texture (or the vanilla one without a cape).
+    pub elytra: Option<ElytraState>,
     pub arm_rotation: f32,
+    /// The pose of the player, applied to the skeleton after the arm rotation.
+    pub pose: Option<PlayerPose>,
+    /// The time of the render, in ticks (twentieths of a second), which moves the animated parts of the player.
+    pub t: f32,
+    /// The distance to move each part away from the body, for exploded view renders.
+    pub exploded_view_offset: Option<f32>,
     pub shadow_y_pos: Option<f32>,
     pub shadow_is_square: bool,
     pub armor_slots: Option<PlayerArmorSlots<M>>,
+    /// The block-style headwear worn instead of a helmet, like a carved pumpkin or a mob skull.
+    pub headwear: Option<Headwear>,
+    /// The model of the item held in the main (right) hand.
+    pub main_hand: Option<Arc<JavaModel>>,
+    /// The model of the item held in the off (left) hand.
+    pub off_hand: Option<Arc<JavaModel>>,
+    /// The name tag shown above the head of the player.
+    pub nametag: Option<Nametag>,
     #[cfg(feature = "ears")]
     pub ears_features: Option<EarsFeatures>,
 }
 
+/// Move the parts collected from the providers from the rest pose to the arm rotation and pose of the context,
+/// and away from the body for exploded views.
+///
+/// Providers only attach their parts to a bone, so this has to be called once all of the parts have been collected.
+pub fn pose_player_parts<M: ArmorMaterial>(context: &PlayerPartProviderContext<M>, parts: &mut [Part]) {
+    PlayerSkeleton::from_context(context).attach_parts(parts);
+
+    if let Some(offset) = context.exploded_view_offset {
+        for part in parts.iter_mut() {
+            if let Some(body_part) = part.get_bone().and_then(|bone| bone.get_body_part()) {
+                part.translate(body_part.get_exploded_view_direction() * offset);
+            }
+        }
+    }
+}
+
 pub trait PartsProvider<M: ArmorMaterial> {
     fn get_parts(
         &self,
@@ -74,17 +131,141 @@ impl<M: ArmorMaterial> PartsProvider<M> for PlayerPartsProvider {
                 .get_parts(context, body_part),
         };
 
-        if body_part.is_arm() {
-            for part in &mut parts {
-                perform_arm_part_rotation(
-                    body_part.get_non_layer_part(),
-                    part,
-                    context.model.is_slim_arms(),
-                    context.arm_rotation,
-                );
+        let bone = PlayerBone::from_body_part(body_part);
+
+        for part in parts
+            .iter_mut()
+            .filter(|p| !p.get_texture().is_shadow() && !p.get_texture().is_nametag() && p.get_bone().is_none())
+        {
+            part.set_bone(bone);
+        }
+
+        #[cfg(feature = "ears")]
+        if context.ears_features.as_ref().is_some_and(|features| features.emissive) {
+            // The emissive pixels of the skin are drawn again over it, from a texture that only has those pixels
+            let emissive_parts: Vec<_> = parts
+                .iter()
+                .filter(|p| p.get_texture() == PlayerPartTextureType::VANILLA_SKIN)
+                .map(|part| {
+                    let mut part = part.clone();
+                    part.replace_texture(
+                        PlayerPartTextureType::VANILLA_SKIN,
+                        ears::PlayerPartEarsTextureType::Emissive.into(),
+                    );
+                    part
+                })
+                .collect();
+
+            parts.extend(emissive_parts);
+        }
+
+        if context.skin_texture != PlayerPartTextureType::VANILLA_SKIN {
+            // Providers lay their parts out on the vanilla skin, so move them over to the HD one
+            for part in parts.iter_mut() {
+                part.use_skin_texture(context.skin_texture);
             }
         }
 
         parts
     }
 }
+
+#[cfg(all(test, feature = "ears"))]
+mod tests {
+    use ears_rs::features::{
+        data::{
+            ear::{EarAnchor, EarMode},
+            tail::{TailData, TailMode},
+            wing::{WingData, WingMode},
+        },
+        EarsFeatures,
+    };
+
+    use super::ears::PlayerPartEarsTextureType;
+    use super::{PartsProvider, PlayerPartProviderContext, PlayerPartsProvider, ANIMATED_PARTS_PERIOD};
+    use crate::types::{PlayerBodyPartType, PlayerPartTextureType};
+
+    fn features(emissive: bool) -> EarsFeatures {
+        EarsFeatures {
+            ear_mode: EarMode::None,
+            ear_anchor: EarAnchor::Center,
+            tail: None,
+            snout: None,
+            wing: None,
+            claws: false,
+            horn: false,
+            chest_size: 0.0,
+            cape_enabled: false,
+            emissive,
+        }
+    }
+
+    #[test]
+    fn test_emissive_parts_are_drawn_over_the_skin() {
+        let mut context = PlayerPartProviderContext::<()> {
+            ears_features: Some(features(false)),
+            ..Default::default()
+        };
+
+        let emissive: PlayerPartTextureType = PlayerPartEarsTextureType::Emissive.into();
+        let head = PlayerPartsProvider::Minecraft.get_parts(&context, PlayerBodyPartType::Head);
+        assert!(!head.iter().any(|part| part.get_texture() == emissive));
+
+        context.ears_features = Some(features(true));
+
+        let emissive_head = PlayerPartsProvider::Minecraft.get_parts(&context, PlayerBodyPartType::Head);
+        let emissive_parts: Vec<_> = emissive_head.iter().filter(|part| part.get_texture() == emissive).collect();
+
+        assert_eq!(head.len() * 2, emissive_head.len());
+        assert!(emissive.is_unlit());
+        assert!(emissive_parts.iter().all(|part| part.get_bone().is_some()));
+    }
+
+    #[test]
+    fn test_animated_wings_flap_over_time() {
+        let mut context = PlayerPartProviderContext::<()> {
+            ears_features: Some(EarsFeatures {
+                ear_mode: EarMode::Tall,
+                tail: Some(TailData {
+                    mode: TailMode::Down,
+                    segments: 1,
+                    ..Default::default()
+                }),
+                wing: Some(WingData {
+                    mode: WingMode::SymmetricDual,
+                    animated: true,
+                }),
+                ..features(false)
+            }),
+            ..Default::default()
+        };
+
+        let transformations_of = |context: &PlayerPartProviderContext<()>, body_part| {
+            PlayerPartsProvider::Ears
+                .get_parts(context, body_part)
+                .iter()
+                .map(|part| part.get_transformation())
+                .collect::<Vec<_>>()
+        };
+
+        let (body, head) = (
+            transformations_of(&context, PlayerBodyPartType::Body),
+            transformations_of(&context, PlayerBodyPartType::Head),
+        );
+
+        context.t = 30.0;
+
+        assert_ne!(body, transformations_of(&context, PlayerBodyPartType::Body));
+        // Only the wings are animated, while the tail and the ears stay where they are
+        assert_eq!(head, transformations_of(&context, PlayerBodyPartType::Head));
+
+        context.t += ANIMATED_PARTS_PERIOD;
+
+        let looped = transformations_of(&context, PlayerBodyPartType::Body);
+        context.t -= ANIMATED_PARTS_PERIOD;
+
+        for (looped, expected) in looped.iter().zip(transformations_of(&context, PlayerBodyPartType::Body)) {
+            assert!(looped.abs_diff_eq(expected, 1e-4), "The wings should be back where they were after a period");
+        }
+    }
+}
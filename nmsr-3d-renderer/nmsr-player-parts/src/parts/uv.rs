@@ -1,4 +1,4 @@
-use glam::Vec2;
+use glam::{Vec2, Vec3};
 
 /// Represents a point on a face of a part.
 /// The values are in the range 0-255 since Minecraft skin textures are small.
@@ -72,6 +72,21 @@ impl FaceUv {
         }
     }
 
+    /// Scale the face up for a texture that is `factor` times as large as the one it was laid out on.
+    pub fn scale(self, factor: UvCoordinate) -> Self {
+        let scale = |point: FaceUvPoint| FaceUvPoint {
+            x: point.x * factor,
+            y: point.y * factor,
+        };
+
+        Self {
+            top_left: scale(self.top_left),
+            top_right: scale(self.top_right),
+            bottom_left: scale(self.bottom_left),
+            bottom_right: scale(self.bottom_right),
+        }
+    }
+
     pub fn rotate_cw(self) -> Self {
         Self {
             top_left: self.top_right,
@@ -92,6 +107,20 @@ pub struct CubeFaceUvs {
     pub down: FaceUv,
 }
 
+impl CubeFaceUvs {
+    /// Scale the faces up for a texture that is `factor` times as large as the one they were laid out on.
+    pub fn scale(self, factor: UvCoordinate) -> Self {
+        Self {
+            north: self.north.scale(factor),
+            south: self.south.scale(factor),
+            east: self.east.scale(factor),
+            west: self.west.scale(factor),
+            up: self.up.scale(factor),
+            down: self.down.scale(factor),
+        }
+    }
+}
+
 pub fn uv_from_pos_and_size(x: u16, y: u16, size_x: u16, size_y: u16) -> FaceUv {
     FaceUv::new(x, y, x + size_x, y + size_y)
 }
@@ -120,6 +149,49 @@ pub fn box_uv(x: u16, y: u16, size: [u16; 3]) -> CubeFaceUvs {
     }
 }
 
+/// Where the texture of a face of a cube goes, seen as a grid of pixels.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CubeFaceLayout {
+    pub normal: Vec3,
+    /// The corner of the top left pixel of the face.
+    pub origin: Vec3,
+    /// The direction the columns of the face go in, from left to right.
+    pub column: Vec3,
+    /// The direction the rows of the face go in, from top to bottom.
+    pub row: Vec3,
+}
+
+impl CubeFaceLayout {
+    /// The layouts of the faces of the cube spanning from `min` to `max`, in the order of [`CubeFaceUvs`].
+    ///
+    /// These follow the way the faces of a cube are textured, so a face laid out this way with its UVs from
+    /// [`CubeFaceUvs`] looks the same as it would on the cube.
+    pub fn of_cube(min: Vec3, max: Vec3) -> [Self; 6] {
+        let layout = |normal, origin, column, row| Self {
+            normal,
+            origin,
+            column,
+            row,
+        };
+
+        [
+            layout(Vec3::NEG_Z, Vec3::new(max.x, max.y, min.z), Vec3::NEG_X, Vec3::NEG_Y),
+            layout(Vec3::Z, Vec3::new(min.x, max.y, max.z), Vec3::X, Vec3::NEG_Y),
+            layout(Vec3::X, Vec3::new(max.x, max.y, max.z), Vec3::NEG_Z, Vec3::NEG_Y),
+            layout(Vec3::NEG_X, Vec3::new(min.x, max.y, min.z), Vec3::Z, Vec3::NEG_Y),
+            layout(Vec3::Y, Vec3::new(max.x, max.y, max.z), Vec3::NEG_X, Vec3::NEG_Z),
+            layout(Vec3::NEG_Y, Vec3::new(max.x, min.y, max.z), Vec3::NEG_X, Vec3::NEG_Z),
+        ]
+    }
+}
+
+impl CubeFaceUvs {
+    /// The faces in the order of [`CubeFaceLayout::of_cube`].
+    pub fn to_array(self) -> [FaceUv; 6] {
+        [self.north, self.south, self.east, self.west, self.up, self.down]
+    }
+}
+
 impl From<[[UvCoordinate; 4]; 6]> for CubeFaceUvs {
     fn from(uvs: [[UvCoordinate; 4]; 6]) -> Self {
         Self {
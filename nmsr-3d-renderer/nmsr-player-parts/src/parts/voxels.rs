@@ -0,0 +1,278 @@
+use glam::{Affine3A, Vec3};
+
+use crate::parts::part::Part;
+use crate::parts::uv::{box_uv, uv_from_pos_and_size, CubeFaceLayout, FaceUv};
+use crate::types::PlayerPartTextureType;
+
+/// The opaque pixels of a skin, used to build its layers out of voxels instead of inflated boxes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SkinLayerMask([u64; SkinLayerMask::SIZE as usize]);
+
+impl SkinLayerMask {
+    /// The width and height of the skin texture the mask covers, in pixels.
+    pub const SIZE: u32 = 64;
+
+    /// Create a mask from a function telling whether the pixel of the skin at the given position is opaque.
+    pub fn from_fn(is_opaque: impl Fn(u32, u32) -> bool) -> Self {
+        let mut rows = [0u64; Self::SIZE as usize];
+
+        for (y, row) in rows.iter_mut().enumerate() {
+            for x in 0..Self::SIZE {
+                if is_opaque(x, y as u32) {
+                    *row |= 1 << x;
+                }
+            }
+        }
+
+        Self(rows)
+    }
+
+    pub fn is_opaque(&self, x: u32, y: u32) -> bool {
+        x < Self::SIZE && y < Self::SIZE && (self.0[y as usize] >> x) & 1 == 1
+    }
+}
+
+/// A face of the part covered by a layer, seen as a grid of pixels.
+struct LayerFace {
+    uv: FaceUv,
+    layout: CubeFaceLayout,
+    /// Whether the voxels on the edges of the face grow over the corners, along the columns and the rows.
+    covers_corners: (bool, bool),
+}
+
+impl LayerFace {
+    fn size(&self) -> (u32, u32) {
+        (
+            (self.uv.bottom_right.x - self.uv.top_left.x) as u32,
+            (self.uv.bottom_right.y - self.uv.top_left.y) as u32,
+        )
+    }
+
+    fn texture_position(&self, column: u32, row: u32) -> (u32, u32) {
+        (self.uv.top_left.x as u32 + column, self.uv.top_left.y as u32 + row)
+    }
+
+    fn is_opaque(&self, mask: &SkinLayerMask, column: i64, row: i64) -> bool {
+        let (width, height) = self.size();
+
+        if column < 0 || row < 0 || column >= width as i64 || row >= height as i64 {
+            return false;
+        }
+
+        let (x, y) = self.texture_position(column as u32, row as u32);
+        mask.is_opaque(x, y)
+    }
+}
+
+/// Build a layer out of voxels, one for each opaque pixel of the layer, like the 3D Skin Layers mod does.
+///
+/// Each voxel sticks out of the face of `part` it covers by `thickness`, and is textured with its pixel on every side.
+/// Only the outside of a voxel and the sides next to transparent pixels are kept. The voxels on the edges of the
+/// front and the back (and the top and bottom edges of the sides) grow over the corners of the part, so that the
+/// faces meeting at an edge don't leave a gap between them.
+pub fn compute_voxel_layer_part(
+    part: &Part,
+    box_uv_offset: (i32, i32),
+    thickness: f32,
+    mask: &SkinLayerMask,
+) -> Part {
+    let min = part.get_position();
+    let max = min + part.get_size();
+    let size = part.get_size();
+
+    let base_uv = part.get_face_uvs().north.top_left;
+    let uvs = box_uv(
+        (base_uv.x as i32 + box_uv_offset.0) as u16,
+        (base_uv.y as i32 + box_uv_offset.1) as u16,
+        [size.x as u16, size.y as u16, size.z as u16],
+    );
+
+    // The front and the back cover every corner, while the sides only cover the top and bottom ones
+    let covers_corners = [(true, true), (true, true), (false, true), (false, true), (false, false), (false, false)];
+
+    let faces = uvs
+        .to_array()
+        .into_iter()
+        .zip(CubeFaceLayout::of_cube(min, max))
+        .zip(covers_corners)
+        .map(|((uv, layout), covers_corners)| LayerFace {
+            uv,
+            layout,
+            covers_corners,
+        });
+
+    let mut quads = Vec::new();
+
+    for face in faces {
+        let CubeFaceLayout {
+            normal,
+            origin,
+            column: column_direction,
+            row: row_direction,
+        } = face.layout;
+
+        let (width, height) = face.size();
+
+        for row in 0..height {
+            for column in 0..width {
+                if !face.is_opaque(mask, column as i64, row as i64) {
+                    continue;
+                }
+
+                let mut start = origin + column_direction * column as f32 + row_direction * row as f32;
+                let mut end = start + column_direction + row_direction;
+
+                if face.covers_corners.0 && column == 0 {
+                    start -= column_direction * thickness;
+                }
+                if face.covers_corners.0 && column == width - 1 {
+                    end += column_direction * thickness;
+                }
+                if face.covers_corners.1 && row == 0 {
+                    start -= row_direction * thickness;
+                }
+                if face.covers_corners.1 && row == height - 1 {
+                    end += row_direction * thickness;
+                }
+
+                end += normal * thickness;
+
+                let (x, y) = face.texture_position(column, row);
+                let uv = uv_from_pos_and_size(x as u16, y as u16, 1, 1);
+
+                let neighbours = [
+                    (column_direction, (1, 0)),
+                    (-column_direction, (-1, 0)),
+                    (row_direction, (0, 1)),
+                    (-row_direction, (0, -1)),
+                ];
+
+                quads.push(compute_voxel_side(start, end, normal, uv));
+
+                for (direction, (column_offset, row_offset)) in neighbours {
+                    if !face.is_opaque(mask, column as i64 + column_offset, row as i64 + row_offset) {
+                        quads.push(compute_voxel_side(start, end, direction, uv));
+                    }
+                }
+            }
+        }
+    }
+
+    let group = Part::new_group(
+        PlayerPartTextureType::VANILLA_SKIN,
+        quads,
+        #[cfg(feature = "part_tracker")]
+        part.get_name().map(|name| format!("{} Layer", name)),
+    );
+
+    #[cfg(feature = "part_tracker")]
+    {
+        let mut group = group;
+        group.push_groups(part.get_group());
+        group
+    }
+    #[cfg(not(feature = "part_tracker"))]
+    {
+        group
+    }
+}
+
+/// Compute the side of the voxel spanning from `start` to `end` that faces towards `direction`.
+fn compute_voxel_side(start: Vec3, end: Vec3, direction: Vec3, uv: FaceUv) -> Part {
+    let (min, max) = (start.min(end), start.max(end));
+
+    // Pick the two axes of the side so that they turn towards the direction it faces
+    let axes = [Vec3::X, Vec3::Y, Vec3::Z];
+    let axis = (0..3).find(|&axis| direction[axis] != 0.0).unwrap_or_default();
+    let (first, second) = (axes[(axis + 1) % 3], axes[(axis + 2) % 3]);
+    let (right, up) = if direction[axis] > 0.0 {
+        (first, second)
+    } else {
+        (second, first)
+    };
+
+    let mut origin = min;
+    if direction[axis] > 0.0 {
+        origin[axis] = max[axis];
+    }
+
+    let size = max - min;
+
+    let mut quad = Part::new_quad(
+        PlayerPartTextureType::VANILLA_SKIN,
+        [0.0, 0.0, 0.0],
+        [1, 1, 0],
+        uv,
+        Vec3::Z,
+        #[cfg(feature = "part_tracker")]
+        None,
+    );
+
+    quad.transform_affine(Affine3A::from_cols(
+        (right * size.dot(right)).into(),
+        (up * size.dot(up)).into(),
+        direction.into(),
+        origin.into(),
+    ));
+
+    quad
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec3;
+
+    use super::{compute_voxel_layer_part, SkinLayerMask};
+    use crate::parts::part::Part;
+    use crate::parts::provider::minecraft::compute_base_part;
+    use crate::types::PlayerBodyPartType;
+
+    fn head_layer(mask: &SkinLayerMask) -> Vec<Part> {
+        let head = compute_base_part(PlayerBodyPartType::Head, false);
+
+        match compute_voxel_layer_part(&head, (32, 0), 1.0, mask) {
+            Part::Group { parts, .. } => parts,
+            _ => unreachable!("Voxel layers are groups of quads"),
+        }
+    }
+
+    fn center_of(quad: &Part) -> Vec3 {
+        quad.get_transformation().transform_point3(Vec3::new(0.5, 0.5, 0.0))
+    }
+
+    #[test]
+    fn test_transparent_layer_has_no_voxels() {
+        assert!(head_layer(&SkinLayerMask::from_fn(|_, _| false)).is_empty());
+    }
+
+    #[test]
+    fn test_single_pixel_becomes_a_voxel() {
+        // The top left pixel of the front of the hat, which covers the top right corner of the face
+        let quads = head_layer(&SkinLayerMask::from_fn(|x, y| (x, y) == (40, 8)));
+
+        assert_eq!(5, quads.len(), "Every side of the voxel but the one against the head should be kept");
+
+        // The voxel grows over the corners of the head, so it spans from x = 3 to 5 and from y = 31 to 33
+        let outside = center_of(&quads[0]);
+        assert!(outside.abs_diff_eq(Vec3::new(4.0, 32.0, -5.0), 1e-4), "{outside}");
+
+        for quad in &quads {
+            let Part::Quad { face_uv, normal, .. } = quad else {
+                unreachable!("Voxels are made of quads");
+            };
+
+            assert_eq!((40, 8), (face_uv.top_left.x, face_uv.top_left.y));
+
+            // Each side should face away from the center of the voxel
+            let center = Vec3::new(4.0, 32.0, -4.5);
+            assert!((center_of(quad) - center).dot(*normal) > 0.0, "{normal} at {}", center_of(quad));
+        }
+    }
+
+    #[test]
+    fn test_sides_between_opaque_pixels_are_hidden() {
+        let quads = head_layer(&SkinLayerMask::from_fn(|x, y| y == 12 && (x == 43 || x == 44)));
+
+        assert_eq!(8, quads.len());
+    }
+}
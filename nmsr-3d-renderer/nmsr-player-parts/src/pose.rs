@@ -0,0 +1,171 @@
+use strum::{Display, EnumIter, EnumMessage, EnumString};
+
+use crate::types::PlayerBodyPartType;
+
+/// The rotation of a single body part, in degrees.
+///
+/// Positive pitch swings the part forward. For the arms and legs, yaw and roll are mirrored on the
+/// left side so that the same values produce symmetric poses (positive roll raises the limb sideways
+/// and positive yaw turns it towards the body).
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct PartRotation {
+    pub pitch: f32,
+    pub yaw: f32,
+    pub roll: f32,
+}
+
+impl PartRotation {
+    pub const ZERO: Self = Self::new(0.0, 0.0, 0.0);
+
+    pub const fn new(pitch: f32, yaw: f32, roll: f32) -> Self {
+        Self { pitch, yaw, roll }
+    }
+
+    pub const fn pitch(pitch: f32) -> Self {
+        Self::new(pitch, 0.0, 0.0)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self::new(
+            lerp(self.pitch, other.pitch, t),
+            lerp(self.yaw, other.yaw, t),
+            lerp(self.roll, other.roll, t),
+        )
+    }
+}
+
+impl From<[f32; 3]> for PartRotation {
+    fn from([pitch, yaw, roll]: [f32; 3]) -> Self {
+        Self::new(pitch, yaw, roll)
+    }
+}
+
+/// The pose of a player, made out of the rotations of each body part.
+///
+/// Poses are applied to the bones of a [`PlayerSkeleton`](crate::skeleton::PlayerSkeleton), so every part
+/// attached to a body part (including its layer, armor and Ears features) follows the rotation of that body part.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct PlayerPose {
+    pub head: PartRotation,
+    pub left_arm: PartRotation,
+    pub right_arm: PartRotation,
+    pub left_leg: PartRotation,
+    pub right_leg: PartRotation,
+    /// How much the upper body (body, head and arms) leans forward around the waist, in degrees.
+    pub body_lean: f32,
+}
+
+impl PlayerPose {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            head: self.head.lerp(&other.head, t),
+            left_arm: self.left_arm.lerp(&other.left_arm, t),
+            right_arm: self.right_arm.lerp(&other.right_arm, t),
+            left_leg: self.left_leg.lerp(&other.left_leg, t),
+            right_leg: self.right_leg.lerp(&other.right_leg, t),
+            body_lean: lerp(self.body_lean, other.body_lean, t),
+        }
+    }
+
+    pub fn get_rotation(&self, part: PlayerBodyPartType) -> PartRotation {
+        match part.get_non_layer_part() {
+            PlayerBodyPartType::Head => self.head,
+            PlayerBodyPartType::LeftArm => self.left_arm,
+            PlayerBodyPartType::RightArm => self.right_arm,
+            PlayerBodyPartType::LeftLeg => self.left_leg,
+            PlayerBodyPartType::RightLeg => self.right_leg,
+            _ => PartRotation::ZERO,
+        }
+    }
+
+    pub fn get_rotation_mut(&mut self, part: PlayerBodyPartType) -> Option<&mut PartRotation> {
+        match part.get_non_layer_part() {
+            PlayerBodyPartType::Head => Some(&mut self.head),
+            PlayerBodyPartType::LeftArm => Some(&mut self.left_arm),
+            PlayerBodyPartType::RightArm => Some(&mut self.right_arm),
+            PlayerBodyPartType::LeftLeg => Some(&mut self.left_leg),
+            PlayerBodyPartType::RightLeg => Some(&mut self.right_leg),
+            _ => None,
+        }
+    }
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+
+/// Named poses that can be used instead of specifying the rotation of each body part.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumIter, EnumString, EnumMessage, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum PlayerPosePreset {
+    #[strum(serialize = "default", serialize = "standing")]
+    Default,
+    Walking,
+    Running,
+    Waving,
+    Sitting,
+    Sneaking,
+    CrossedArms,
+}
+
+impl PlayerPosePreset {
+    pub fn get_pose(&self) -> PlayerPose {
+        match self {
+            Self::Default => PlayerPose::default(),
+            Self::Walking => PlayerPose {
+                left_arm: PartRotation::pitch(-25.0),
+                right_arm: PartRotation::pitch(25.0),
+                left_leg: PartRotation::pitch(25.0),
+                right_leg: PartRotation::pitch(-25.0),
+                ..Default::default()
+            },
+            Self::Running => PlayerPose {
+                head: PartRotation::pitch(-10.0),
+                left_arm: PartRotation::new(-55.0, 0.0, 5.0),
+                right_arm: PartRotation::new(55.0, 0.0, 5.0),
+                left_leg: PartRotation::pitch(45.0),
+                right_leg: PartRotation::pitch(-45.0),
+                body_lean: 10.0,
+            },
+            Self::Waving => PlayerPose {
+                head: PartRotation::new(0.0, 0.0, -5.0),
+                right_arm: PartRotation::new(0.0, 0.0, 150.0),
+                ..Default::default()
+            },
+            Self::Sitting => PlayerPose {
+                left_arm: PartRotation::pitch(35.0),
+                right_arm: PartRotation::pitch(35.0),
+                left_leg: PartRotation::new(80.0, -10.0, 0.0),
+                right_leg: PartRotation::new(80.0, -10.0, 0.0),
+                ..Default::default()
+            },
+            Self::Sneaking => PlayerPose {
+                head: PartRotation::pitch(-28.0),
+                left_arm: PartRotation::pitch(-5.0),
+                right_arm: PartRotation::pitch(-5.0),
+                body_lean: 28.0,
+                ..Default::default()
+            },
+            Self::CrossedArms => PlayerPose {
+                left_arm: PartRotation::new(70.0, 40.0, 0.0),
+                right_arm: PartRotation::new(65.0, 40.0, 0.0),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl From<PlayerPosePreset> for PlayerPose {
+    fn from(preset: PlayerPosePreset) -> Self {
+        preset.get_pose()
+    }
+}
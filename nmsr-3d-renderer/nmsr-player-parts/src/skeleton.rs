@@ -0,0 +1,345 @@
+use glam::{Affine3A, EulerRot, Quat, Vec3};
+use strum::{EnumCount, EnumIter, IntoEnumIterator};
+
+use crate::model::ArmorMaterial;
+use crate::parts::part::Part;
+use crate::parts::provider::minecraft::compute_base_part;
+use crate::parts::provider::PlayerPartProviderContext;
+use crate::pose::PlayerPose;
+use crate::types::PlayerBodyPartType;
+
+/// A bone of the player skeleton.
+///
+/// Parts are parented to the bone of the body part they belong to (including their layers, armor and Ears features),
+/// and bones are parented as `Root -> Body -> Head / Arms` and `Root -> Legs`, so that leaning the body doesn't move the legs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumIter, EnumCount)]
+pub enum PlayerBone {
+    Root,
+    Body,
+    Head,
+    LeftArm,
+    RightArm,
+    LeftLeg,
+    RightLeg,
+}
+
+impl PlayerBone {
+    pub fn get_parent(&self) -> Option<Self> {
+        match self {
+            Self::Root => None,
+            Self::Body | Self::LeftLeg | Self::RightLeg => Some(Self::Root),
+            _ => Some(Self::Body),
+        }
+    }
+
+    pub fn from_body_part(body_part: PlayerBodyPartType) -> Self {
+        match body_part.get_non_layer_part() {
+            PlayerBodyPartType::Head => Self::Head,
+            PlayerBodyPartType::LeftArm => Self::LeftArm,
+            PlayerBodyPartType::RightArm => Self::RightArm,
+            PlayerBodyPartType::LeftLeg => Self::LeftLeg,
+            PlayerBodyPartType::RightLeg => Self::RightLeg,
+            _ => Self::Body,
+        }
+    }
+
+    pub fn get_body_part(&self) -> Option<PlayerBodyPartType> {
+        match self {
+            Self::Root => None,
+            Self::Body => Some(PlayerBodyPartType::Body),
+            Self::Head => Some(PlayerBodyPartType::Head),
+            Self::LeftArm => Some(PlayerBodyPartType::LeftArm),
+            Self::RightArm => Some(PlayerBodyPartType::RightArm),
+            Self::LeftLeg => Some(PlayerBodyPartType::LeftLeg),
+            Self::RightLeg => Some(PlayerBodyPartType::RightLeg),
+        }
+    }
+
+    pub fn is_left(&self) -> bool {
+        matches!(self, Self::LeftArm | Self::LeftLeg)
+    }
+
+    /// The point around which this bone rotates, in model space.
+    /// That is the neck for the head, the shoulders for the arms, the hips for the legs and the waist for the body.
+    pub fn get_pivot(&self, is_slim_arms: bool) -> Vec3 {
+        let Some(body_part) = self.get_body_part() else {
+            return Vec3::ZERO;
+        };
+
+        let base = compute_base_part(body_part, is_slim_arms);
+        let (position, size) = (base.get_position(), base.get_size());
+
+        match self {
+            Self::Head | Self::Body => position + size * Vec3::new(0.5, 0.0, 0.5),
+            Self::LeftArm | Self::RightArm => {
+                position + size * Vec3::new(0.5, 1.0, 0.5) - Vec3::new(0.0, 2.0, 0.0)
+            }
+            _ => position + size * Vec3::new(0.5, 1.0, 0.5),
+        }
+    }
+}
+
+/// The skeleton of a player, made out of the transformations of each bone relative to its parent.
+///
+/// Parts are built in model space at rest, and only get their final transformation once they're attached to a bone.
+#[derive(Debug, Copy, Clone)]
+pub struct PlayerSkeleton {
+    is_slim_arms: bool,
+    local_transforms: [Affine3A; PlayerBone::COUNT],
+}
+
+impl PlayerSkeleton {
+    pub fn new(is_slim_arms: bool) -> Self {
+        Self {
+            is_slim_arms,
+            local_transforms: [Affine3A::IDENTITY; PlayerBone::COUNT],
+        }
+    }
+
+    /// Create the skeleton for the arm rotation and pose of the given context.
+    pub fn from_context<M: ArmorMaterial>(context: &PlayerPartProviderContext<M>) -> Self {
+        let mut skeleton = Self::new(context.model.is_slim_arms());
+
+        skeleton.apply_arm_rotation(context.arm_rotation);
+
+        if let Some(pose) = context.pose.as_ref() {
+            skeleton.apply_pose(pose);
+        }
+
+        skeleton
+    }
+
+    pub fn is_slim_arms(&self) -> bool {
+        self.is_slim_arms
+    }
+
+    /// Rotate a bone (and all of its children) around the given anchor, in the space of its parent.
+    pub fn rotate_bone(&mut self, bone: PlayerBone, rotation: Vec3, anchor: Vec3) {
+        let rotation = Quat::from_euler(
+            EulerRot::YXZ,
+            rotation.y.to_radians(),
+            rotation.x.to_radians(),
+            rotation.z.to_radians(),
+        );
+
+        let transform = Affine3A::from_translation(anchor)
+            * Affine3A::from_quat(rotation)
+            * Affine3A::from_translation(-anchor);
+
+        self.transform_bone(bone, transform);
+    }
+
+    /// Move a bone (and all of its children), in the space of its parent.
+    pub fn translate_bone(&mut self, bone: PlayerBone, translation: Vec3) {
+        self.transform_bone(bone, Affine3A::from_translation(translation));
+    }
+
+    pub fn transform_bone(&mut self, bone: PlayerBone, transform: Affine3A) {
+        let local = &mut self.local_transforms[bone as usize];
+        *local = transform * *local;
+    }
+
+    pub fn get_local_transform(&self, bone: PlayerBone) -> Affine3A {
+        self.local_transforms[bone as usize]
+    }
+
+    /// The transformation from the rest pose to the current pose of a bone, taking into account all of its parents.
+    pub fn get_world_transform(&self, bone: PlayerBone) -> Affine3A {
+        let local = self.get_local_transform(bone);
+
+        bone.get_parent()
+            .map_or(local, |parent| self.get_world_transform(parent) * local)
+    }
+
+    /// Where a point attached to a bone ends up, given its position in the rest pose.
+    pub fn get_attachment_point(&self, bone: PlayerBone, rest_position: Vec3) -> Vec3 {
+        self.get_world_transform(bone).transform_point3(rest_position)
+    }
+
+    /// The world transformation of every bone, indexed by [`PlayerBone`].
+    pub fn get_world_transforms(&self) -> [Affine3A; PlayerBone::COUNT] {
+        let mut transforms = [Affine3A::IDENTITY; PlayerBone::COUNT];
+
+        // Bones are declared after their parents, so the parent transformations are always resolved first.
+        for bone in PlayerBone::iter() {
+            let local = self.get_local_transform(bone);
+
+            transforms[bone as usize] = bone
+                .get_parent()
+                .map_or(local, |parent| transforms[parent as usize] * local);
+        }
+
+        transforms
+    }
+
+    /// Move parts built in the rest pose to where the bones they're attached to are.
+    /// The transformation of each bone is only resolved once, and parts without a bone are left untouched.
+    pub fn attach_parts(&self, parts: &mut [Part]) {
+        let transforms = self.get_world_transforms();
+
+        for part in parts {
+            let Some(bone) = part.get_bone() else {
+                continue;
+            };
+
+            let transform = transforms[bone as usize];
+
+            if transform == Affine3A::IDENTITY {
+                continue;
+            }
+
+            part.transform_affine(transform);
+
+            #[cfg(feature = "part_tracker")]
+            {
+                let pivot = transform.transform_point3(bone.get_pivot(self.is_slim_arms));
+                part.part_tracking_data_mut().set_last_rotation_origin(pivot);
+            }
+        }
+    }
+
+    /// Open the arms sideways by the given angle, around the top inner edge of each arm.
+    pub fn apply_arm_rotation(&mut self, arm_rotation: f32) {
+        if arm_rotation == 0.0 {
+            return;
+        }
+
+        for (bone, edge, angle) in [
+            (PlayerBone::LeftArm, 1.0, -arm_rotation),
+            (PlayerBone::RightArm, 0.0, arm_rotation),
+        ] {
+            let Some(body_part) = bone.get_body_part() else {
+                continue;
+            };
+
+            let arm = compute_base_part(body_part, self.is_slim_arms);
+            let anchor = arm.get_position() + arm.get_size() * Vec3::new(edge, 1.0, 0.5);
+
+            self.rotate_bone(bone, [0.0, 0.0, angle].into(), anchor);
+        }
+    }
+
+    /// Rotate the bones according to the given pose.
+    pub fn apply_pose(&mut self, pose: &PlayerPose) {
+        for bone in [
+            PlayerBone::Head,
+            PlayerBone::LeftArm,
+            PlayerBone::RightArm,
+            PlayerBone::LeftLeg,
+            PlayerBone::RightLeg,
+        ] {
+            let Some(rotation) = bone.get_body_part().map(|part| pose.get_rotation(part)) else {
+                continue;
+            };
+
+            if rotation.is_zero() {
+                continue;
+            }
+
+            // The head sticks up from its pivot while the limbs hang down from theirs,
+            // so swinging it forward means rotating the other way around.
+            let pitch = if bone == PlayerBone::Head {
+                -rotation.pitch
+            } else {
+                rotation.pitch
+            };
+
+            let (yaw, roll) = if bone.is_left() {
+                (-rotation.yaw, -rotation.roll)
+            } else {
+                (rotation.yaw, rotation.roll)
+            };
+
+            self.rotate_bone(bone, [pitch, yaw, roll].into(), bone.get_pivot(self.is_slim_arms));
+        }
+
+        if pose.body_lean != 0.0 {
+            let waist = PlayerBone::Body.get_pivot(self.is_slim_arms);
+            self.rotate_bone(PlayerBone::Body, [-pose.body_lean, 0.0, 0.0].into(), waist);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Affine3A, Vec3};
+    use strum::IntoEnumIterator;
+
+    use super::{PlayerBone, PlayerSkeleton};
+    use crate::parts::provider::minecraft::compute_base_part;
+    use crate::pose::{PartRotation, PlayerPose};
+    use crate::types::PlayerBodyPartType;
+
+    fn posed_skeleton() -> PlayerSkeleton {
+        let mut skeleton = PlayerSkeleton::new(false);
+        skeleton.apply_arm_rotation(10.0);
+        skeleton.apply_pose(&PlayerPose {
+            head: PartRotation::new(10.0, 20.0, 0.0),
+            right_arm: PartRotation::new(30.0, 0.0, 15.0),
+            left_leg: PartRotation::pitch(25.0),
+            body_lean: 15.0,
+            ..Default::default()
+        });
+        skeleton.translate_bone(PlayerBone::Root, Vec3::new(1.0, 2.0, 3.0));
+
+        skeleton
+    }
+
+    #[test]
+    fn test_world_transform_is_parent_times_local() {
+        let skeleton = posed_skeleton();
+        let transforms = skeleton.get_world_transforms();
+
+        for bone in PlayerBone::iter() {
+            let expected = bone.get_parent().map_or(skeleton.get_local_transform(bone), |parent| {
+                skeleton.get_world_transform(parent) * skeleton.get_local_transform(bone)
+            });
+
+            assert!(
+                skeleton.get_world_transform(bone).abs_diff_eq(expected, 1e-5),
+                "World transform of {bone:?} should be its parent's times its own"
+            );
+            assert!(transforms[bone as usize].abs_diff_eq(expected, 1e-5));
+        }
+    }
+
+    #[test]
+    fn test_lean_only_moves_upper_body() {
+        let mut skeleton = PlayerSkeleton::new(false);
+        skeleton.apply_pose(&PlayerPose {
+            body_lean: 30.0,
+            ..Default::default()
+        });
+
+        for bone in [PlayerBone::LeftLeg, PlayerBone::RightLeg] {
+            assert_eq!(Affine3A::IDENTITY, skeleton.get_world_transform(bone));
+        }
+
+        for bone in [PlayerBone::Head, PlayerBone::LeftArm, PlayerBone::RightArm] {
+            assert_ne!(Affine3A::IDENTITY, skeleton.get_world_transform(bone));
+        }
+    }
+
+    #[test]
+    fn test_zero_pose_leaves_parts_unchanged() {
+        let mut skeleton = PlayerSkeleton::new(false);
+        skeleton.apply_arm_rotation(0.0);
+        skeleton.apply_pose(&PlayerPose::default());
+
+        let mut parts: Vec<_> = PlayerBodyPartType::iter()
+            .filter(|part| !part.is_layer() && !part.is_hat_layer())
+            .map(|part| {
+                let mut base = compute_base_part(part, false);
+                base.set_bone(PlayerBone::from_body_part(part));
+                base
+            })
+            .collect();
+        let rest: Vec<_> = parts.iter().map(|part| part.get_transformation()).collect();
+
+        skeleton.attach_parts(&mut parts);
+
+        for (part, rest) in parts.iter().zip(rest) {
+            assert_eq!(rest, part.get_transformation());
+        }
+    }
+}
@@ -1,6 +1,10 @@
-use strum::{EnumIter, IntoStaticStr};
+use glam::Vec3;
+use strum::{Display, EnumIter, EnumString, IntoStaticStr};
 
-#[derive(Debug, Copy, Clone, EnumIter, Eq, PartialEq, Hash, PartialOrd, Ord)]
+use crate::parts::provider::minecraft::compute_base_part;
+
+#[derive(Debug, Copy, Clone, EnumIter, EnumString, Display, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[strum(serialize_all = "snake_case")]
 pub enum PlayerBodyPartType {
     // Normal body parts
     Head,
@@ -67,14 +71,32 @@ impl PlayerBodyPartType {
     pub fn is_leg(&self) -> bool {
         matches!(self.get_non_layer_part(), Self::LeftLeg | Self::RightLeg)
     }
+
+    /// The direction in which this part moves away from the body in an exploded view.
+    /// It points from the centre of the body to the centre of the part, and layers move together with the part they cover.
+    pub fn get_exploded_view_direction(&self) -> Vec3 {
+        let center_of = |part: PlayerBodyPartType| {
+            let part = compute_base_part(part, false);
+            part.get_position() + part.get_size() / 2.0
+        };
+
+        (center_of(self.get_non_layer_part()) - center_of(Self::Body)).normalize_or_zero()
+    }
 }
 
 #[derive(Debug, Copy, Clone, IntoStaticStr, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum PlayerPartTextureType {
     Shadow,
     Cape,
-    Skin,
+    /// The skin of the player, laid out like the vanilla 64×64 skin scaled up `scale` times (for HD skins).
+    Skin { scale: u32 },
     Custom { key: &'static str, size: (u32, u32) },
+    /// The enchantment glint, laid over enchanted armor using the UVs of the armor textures.
+    /// It's rendered after the other textures, so that it's laid on top of them.
+    Glint,
+    /// The name tag shown above the head of the player, with its semi-transparent background.
+    /// It's the last texture so that everything behind it has been rendered when it's blended in.
+    Nametag { size: (u32, u32) },
 }
 
 impl std::fmt::Display for PlayerPartTextureType {
@@ -82,23 +104,90 @@ impl std::fmt::Display for PlayerPartTextureType {
         match *self {
             PlayerPartTextureType::Shadow => f.pad("Shadow"),
             PlayerPartTextureType::Cape => f.pad("Cape"),
-            PlayerPartTextureType::Skin => f.pad("Skin"),
-            PlayerPartTextureType::Custom { key, .. } => f.pad(key)
+            PlayerPartTextureType::Skin { .. } => f.pad("Skin"),
+            PlayerPartTextureType::Custom { key, .. } => f.pad(key),
+            PlayerPartTextureType::Glint => f.pad("Glint"),
+            PlayerPartTextureType::Nametag { .. } => f.pad("Nametag"),
         }
     }
 }
 
+impl Default for PlayerPartTextureType {
+    fn default() -> Self {
+        Self::VANILLA_SKIN
+    }
+}
+
 impl PlayerPartTextureType {
+    /// The width and height of the vanilla skin texture, in pixels.
+    pub const VANILLA_SKIN_SIZE: u32 = 64;
+
+    /// The vanilla 64×64 skin texture, which the parts of the player are laid out on.
+    pub const VANILLA_SKIN: Self = Self::Skin { scale: 1 };
+
+    /// The vanilla elytra texture, used for players that wear an elytra but don't have a cape.
+    pub const VANILLA_ELYTRA: Self = Self::Custom {
+        key: "vanilla_elytra",
+        size: (64, 32),
+    };
+
     pub fn get_texture_size(&self) -> (u32, u32) {
         match self {
-            Self::Skin => (64, 64),
+            Self::Skin { scale } => (
+                Self::VANILLA_SKIN_SIZE * scale,
+                Self::VANILLA_SKIN_SIZE * scale,
+            ),
             Self::Cape => (64, 32),
             Self::Custom { size, .. } => *size,
             Self::Shadow => (128, 128),
+            Self::Glint => (64, 64),
+            Self::Nametag { size } => *size,
         }
     }
 
+    /// The skin texture for a skin image of the given size.
+    ///
+    /// Skins have to use the vanilla 64×64 layout (or the legacy 64×32 one), optionally scaled up by a power of two
+    /// like the HD skins from Bedrock Edition or HD skin mods.
+    pub fn skin_of_size(width: u32, height: u32) -> Option<Self> {
+        let scale = width / Self::VANILLA_SKIN_SIZE;
+
+        let is_vanilla_layout = scale * Self::VANILLA_SKIN_SIZE == width
+            && scale.is_power_of_two()
+            && (height == width || height * 2 == width);
+
+        is_vanilla_layout.then_some(Self::Skin { scale })
+    }
+
     pub fn is_shadow(&self) -> bool {
         matches!(self, Self::Shadow)
     }
+
+    pub fn is_skin(&self) -> bool {
+        matches!(self, Self::Skin { .. })
+    }
+
+    pub fn is_glint(&self) -> bool {
+        matches!(self, Self::Glint)
+    }
+
+    pub fn is_nametag(&self) -> bool {
+        matches!(self, Self::Nametag { .. })
+    }
+
+    /// Whether the texture only has the emissive pixels of an Ears skin, which give off their own light.
+    #[cfg(feature = "ears")]
+    pub fn is_emissive(&self) -> bool {
+        *self == crate::parts::provider::ears::PlayerPartEarsTextureType::Emissive.into()
+    }
+
+    /// Whether the texture is rendered as is, without being shaded by the sun.
+    pub fn is_unlit(&self) -> bool {
+        #[cfg(feature = "ears")]
+        if self.is_emissive() {
+            return true;
+        }
+
+        self.is_nametag()
+    }
 }
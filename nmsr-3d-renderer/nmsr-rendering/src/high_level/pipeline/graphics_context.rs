@@ -3,7 +3,7 @@ use std::{borrow::Cow, env, mem, sync::Arc};
 use deadpool::managed::{Object, Pool};
 use smaa::SmaaMode;
 use wgpu::{
-    vertex_attr_array, BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
+    vertex_attr_array, BindGroupLayout, BlendComponent, BlendFactor, BlendOperation, BindGroupLayoutDescriptor, BindGroupLayoutEntry,
     BindingType, BufferAddress, BufferBindingType, BufferSize, ColorTargetState, ColorWrites,
     CompareFunction, DepthStencilState, FragmentState, FrontFace, MultisampleState,
     PipelineLayoutDescriptor, PresentMode, PrimitiveState, RenderPipeline,
@@ -22,7 +22,7 @@ use crate::{
 
 use super::{
     pools::SceneContextPoolManager,
-    scene::{Size, SunInformation},
+    scene::{GlintInformation, Size, SunInformation},
 };
 
 #[derive(Debug)]
@@ -36,6 +36,10 @@ pub struct GraphicsContext<'a> {
     pub adapter: Adapter,
 
     pub pipeline: RenderPipeline,
+    /// The pipeline the enchantment glint is rendered with, on top of the armor it's laid over.
+    pub glint_pipeline: RenderPipeline,
+    /// The pipeline textures that aren't shaded by the sun (like the name tag) are rendered with.
+    pub unlit_pipeline: RenderPipeline,
     pub layouts: GraphicsContextLayouts,
     pub multisampling_strategy: MultiSamplingStrategy,
 }
@@ -69,6 +73,8 @@ pub struct GraphicsContextLayouts {
     pub skin_sampler_bind_group_layout: BindGroupLayout,
     pub pipeline_layout: wgpu::PipelineLayout,
     pub sun_bind_group_layout: BindGroupLayout,
+    pub glint_bind_group_layout: BindGroupLayout,
+    pub glint_pipeline_layout: wgpu::PipelineLayout,
 }
 
 #[derive(Debug)]
@@ -149,6 +155,20 @@ impl<'a> GraphicsContext<'a> {
     pub const DEFAULT_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
     pub const DEPTH_TEXTURE_FORMAT: TextureFormat = TextureFormat::Depth32Float;
 
+    /// Add the color of the glint (weighted by itself, like the game does) to the armor, leaving its alpha as is.
+    const GLINT_BLEND_STATE: BlendState = BlendState {
+        color: BlendComponent {
+            src_factor: BlendFactor::Src,
+            dst_factor: BlendFactor::One,
+            operation: BlendOperation::Add,
+        },
+        alpha: BlendComponent {
+            src_factor: BlendFactor::Zero,
+            dst_factor: BlendFactor::One,
+            operation: BlendOperation::Add,
+        },
+    };
+
     pub async fn new(descriptor: GraphicsContextDescriptor<'a>) -> Result<Self> {
         Self::new_with_shader(
             descriptor,
@@ -186,7 +206,7 @@ impl<'a> GraphicsContext<'a> {
                 &wgpu::DeviceDescriptor {
                     label: None,
                     required_features: descriptor.features,
-                    required_limits: descriptor.limits.unwrap_or_else(|| wgpu::Limits::default())
+                    required_limits: descriptor.limits.unwrap_or_default()
                 },
                 None,
             )
@@ -280,6 +300,20 @@ impl<'a> GraphicsContext<'a> {
             }],
         });
 
+        let glint_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Glint Bind Group"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: BufferSize::new(mem::size_of::<GlintInformation>() as u64),
+                },
+                count: None,
+            }],
+        });
+
         // Create the pipeline layout
         let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: Some("Scene Pipeline Layout"),
@@ -291,11 +325,28 @@ impl<'a> GraphicsContext<'a> {
             push_constant_ranges: &[],
         });
 
+        let glint_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Glint Pipeline Layout"),
+            bind_group_layouts: &[
+                &transform_bind_group_layout,
+                &skin_bind_group_layout,
+                &sun_bind_group_layout,
+                &glint_bind_group_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+
         let shader = device.create_shader_module(ShaderModuleDescriptor {
             label: None,
             source: shader,
         });
 
+        // Custom shaders don't know about the glint nor about unlit textures, so those are always rendered with ours
+        let builtin_shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Built-in Shader"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
+        });
+
         let vertex_buffer_layout = VertexBufferLayout {
             array_stride: mem::size_of::<Vertex>() as BufferAddress,
             step_mode: wgpu::VertexStepMode::Vertex,
@@ -320,7 +371,7 @@ impl<'a> GraphicsContext<'a> {
             vertex: VertexState {
                 module: &shader,
                 entry_point: "vs_main",
-                buffers: &[vertex_buffer_layout],
+                buffers: std::slice::from_ref(&vertex_buffer_layout),
             },
             primitive: PrimitiveState {
                 cull_mode: None,
@@ -351,6 +402,81 @@ impl<'a> GraphicsContext<'a> {
             multiview: None,
         });
 
+        let glint_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Glint Pipeline"),
+            layout: Some(&glint_pipeline_layout),
+            vertex: VertexState {
+                module: &builtin_shader,
+                entry_point: "vs_main",
+                buffers: std::slice::from_ref(&vertex_buffer_layout),
+            },
+            primitive: PrimitiveState {
+                cull_mode: None,
+                front_face: FrontFace::Cw,
+                ..Default::default()
+            },
+            // Only draw the glint where the armor it's laid over was drawn
+            depth_stencil: Some(DepthStencilState {
+                format: Self::DEPTH_TEXTURE_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: CompareFunction::Equal,
+                stencil: Default::default(),
+                bias: Default::default(),
+            }),
+            multisample: MultisampleState {
+                count: sample_count,
+                alpha_to_coverage_enabled: false,
+                ..Default::default()
+            },
+            fragment: Some(FragmentState {
+                module: &builtin_shader,
+                entry_point: "fs_glint",
+                targets: &[Some(ColorTargetState {
+                    format: texture_format,
+                    blend: Some(Self::GLINT_BLEND_STATE),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+
+        let unlit_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Unlit Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &builtin_shader,
+                entry_point: "vs_main",
+                buffers: &[vertex_buffer_layout],
+            },
+            primitive: PrimitiveState {
+                cull_mode: None,
+                front_face: FrontFace::Cw,
+                ..Default::default()
+            },
+            depth_stencil: Some(DepthStencilState {
+                format: Self::DEPTH_TEXTURE_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: CompareFunction::LessEqual,
+                stencil: Default::default(),
+                bias: Default::default(),
+            }),
+            multisample: MultisampleState {
+                count: sample_count,
+                alpha_to_coverage_enabled: false,
+                ..Default::default()
+            },
+            fragment: Some(FragmentState {
+                module: &builtin_shader,
+                entry_point: "fs_unlit",
+                targets: &[Some(ColorTargetState {
+                    format: texture_format,
+                    blend,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+        });
+
         Ok(GraphicsContext {
             instance,
             device,
@@ -360,12 +486,16 @@ impl<'a> GraphicsContext<'a> {
             texture_format,
             adapter,
             pipeline,
+            glint_pipeline,
+            unlit_pipeline,
             multisampling_strategy,
             layouts: GraphicsContextLayouts {
                 pipeline_layout,
                 transform_bind_group_layout,
                 skin_sampler_bind_group_layout: skin_bind_group_layout,
                 sun_bind_group_layout,
+                glint_bind_group_layout,
+                glint_pipeline_layout,
             },
         })
     }
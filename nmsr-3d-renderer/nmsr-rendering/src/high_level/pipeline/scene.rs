@@ -5,14 +5,16 @@ use crate::{
     low_level::primitives::{mesh::Mesh, part_primitive::PartPrimitive},
 };
 use bytemuck::{Pod, Zeroable};
-use glam::Vec3;
+use glam::{Vec2, Vec3};
 use image::RgbaImage;
 use itertools::Itertools;
 use nmsr_player_parts::{
     model::ArmorMaterial,
     parts::{
         part::Part,
-        provider::{PartsProvider, PlayerPartProviderContext, PlayerPartsProvider},
+        provider::{
+            pose_player_parts, PartsProvider, PlayerPartProviderContext, PlayerPartsProvider,
+        },
     },
     types::{PlayerBodyPartType, PlayerPartTextureType},
 };
@@ -45,6 +47,7 @@ where
     textures: HashMap<PlayerPartTextureType, SceneTexture>,
     computed_body_parts: Vec<Part>,
     sun_information: SunInformation,
+    glint_information: GlintInformation,
 }
 
 #[derive(Copy, Clone, Pod, Zeroable, Debug)]
@@ -82,6 +85,34 @@ impl SunInformation {
     }
 }
 
+/// Where the enchantment glint texture is over the armor, like the texture matrix the game scrolls it with.
+#[derive(Copy, Clone, Pod, Zeroable, Debug, Default)]
+#[repr(C)]
+pub struct GlintInformation {
+    pub offset: Vec2,
+    pub rotation: f32,
+    pub scale: f32,
+}
+
+impl GlintInformation {
+    /// How long the glint takes to scroll over the armor horizontally and vertically, in seconds.
+    const SCROLL_PERIODS: Vec2 = Vec2::new(110.0 / 8.0, 30.0 / 8.0);
+
+    const ROTATION: f32 = 10.0 * std::f32::consts::PI / 180.0;
+    const ARMOR_SCALE: f32 = 0.16;
+
+    /// The glint the given amount of seconds after it started scrolling.
+    pub fn at_time(seconds: f32) -> Self {
+        let progress = (Vec2::splat(seconds) / Self::SCROLL_PERIODS).fract();
+
+        Self {
+            offset: Vec2::new(-progress.x, progress.y),
+            rotation: Self::ROTATION,
+            scale: Self::ARMOR_SCALE,
+        }
+    }
+}
+
 type ExtraRenderFunc<'a> =
     Box<dyn FnOnce(&TextureView, &mut CommandEncoder, &mut Camera, &mut SunInformation) + 'a>;
 
@@ -128,6 +159,7 @@ where
             textures: HashMap::new(),
             computed_body_parts,
             sun_information: sun,
+            glint_information: GlintInformation::at_time(0.0),
         };
 
         if part_context.shadow_y_pos.is_some() {
@@ -160,6 +192,10 @@ where
         &mut self.sun_information
     }
 
+    pub fn glint_information_mut(&mut self) -> &mut GlintInformation {
+        &mut self.glint_information
+    }
+
     pub fn viewport_size_mut(&mut self) -> &mut Size {
         &mut self.viewport_size
     }
@@ -203,6 +239,8 @@ where
             })
             .collect::<Vec<Part>>();
 
+        pose_player_parts(part_provider_context, &mut parts);
+
         // Sort the parts by texture. This allows us to render all parts with the same texture in one go.
         parts.sort_by_key(|p| p.get_texture());
 
@@ -229,8 +267,12 @@ where
             _ => unreachable!("SMAA target is always initialized"),
         };
 
+        self.scene_context
+            .set_glint_information(graphics_context, &self.glint_information);
+
         let transform_bind_group = &self.scene_context.transform_bind_group;
         let sun_bind_group = &self.scene_context.sun_information_bind_group;
+        let glint_bind_group = &self.scene_context.glint_information_bind_group;
 
         let textures = self
             .scene_context
@@ -284,17 +326,24 @@ where
                 .ok_or(NMSRRenderingError::SceneContextTextureNotSet(texture))?
                 .view;
 
-            let filter = if texture.is_shadow() {
+            let filter = if texture.is_shadow() || texture.is_glint() {
                 FilterMode::Linear
             } else {
                 FilterMode::Nearest
             };
 
+            // The glint is scrolled over the armor, so it has to repeat
+            let address_mode = if texture.is_glint() {
+                AddressMode::Repeat
+            } else {
+                AddressMode::ClampToEdge
+            };
+
             let texture_sampler = device.create_sampler(&SamplerDescriptor {
                 label: Some(texture.into()),
-                address_mode_u: AddressMode::ClampToEdge,
-                address_mode_v: AddressMode::ClampToEdge,
-                address_mode_w: AddressMode::ClampToEdge,
+                address_mode_u: address_mode,
+                address_mode_v: address_mode,
+                address_mode_w: address_mode,
                 mag_filter: filter,
                 min_filter: filter,
                 mipmap_filter: filter,
@@ -373,10 +422,19 @@ where
                 occlusion_query_set: None,
             });
 
-            rpass.set_pipeline(pipeline);
             rpass.set_bind_group(0, transform_bind_group, &[]);
             rpass.set_bind_group(1, &texture_sampler_bind_group, &[]);
             rpass.set_bind_group(2, sun_bind_group, &[]);
+
+            if texture.is_glint() {
+                rpass.set_pipeline(&graphics_context.glint_pipeline);
+                rpass.set_bind_group(3, glint_bind_group, &[]);
+            } else if texture.is_unlit() {
+                rpass.set_pipeline(&graphics_context.unlit_pipeline);
+            } else {
+                rpass.set_pipeline(pipeline);
+            }
+
             rpass.set_index_buffer(index_buf.slice(..), IndexFormat::Uint16);
             rpass.set_vertex_buffer(0, vertex_buf.slice(..));
             rpass.draw_indexed(0..(index_data.len() as u32), 0, 0..1);
@@ -480,13 +538,21 @@ where
         );
     }
 
-    pub fn rebuild_parts(
+    pub fn rebuild_parts<M: ArmorMaterial>(
         &mut self,
-        part_context: &PlayerPartProviderContext,
+        part_context: &PlayerPartProviderContext<M>,
         body_parts: Vec<PlayerBodyPartType>,
     ) -> &[Part] {
         self.computed_body_parts = Self::collect_player_parts(part_context, &body_parts);
 
         self.parts()
     }
+
+    /// Replace the parts to render with the given ones, for renders that aren't made of player body parts.
+    pub fn set_parts(&mut self, mut parts: Vec<Part>) -> &[Part] {
+        parts.sort_by_key(|p| p.get_texture());
+        self.computed_body_parts = parts;
+
+        self.parts()
+    }
 }
@@ -1,5 +1,5 @@
 use super::{
-    scene::{Size, SunInformation},
+    scene::{GlintInformation, Size, SunInformation},
     textures::{
         create_texture, premultiply_alpha, BufferDimensions, SceneContextTextures, SceneTexture,
     },
@@ -29,6 +29,8 @@ pub struct SceneContext {
     pub transform_bind_group: BindGroup,
     pub sun_information_buffer: Buffer,
     pub sun_information_bind_group: BindGroup,
+    pub glint_information_buffer: Buffer,
+    pub glint_information_bind_group: BindGroup,
     pub(crate) textures: Option<SceneContextTextures>,
     #[debug(skip)]
     pub(crate) smaa_target: Option<SmaaTarget>,
@@ -55,11 +57,20 @@ impl SceneContext {
             &[SunInformation::default()],
         );
 
+        let (glint_information_buffer, glint_information_bind_group) = create_buffer_and_bind_group(
+            device,
+            "Glint",
+            &context.layouts.glint_bind_group_layout,
+            &[GlintInformation::default()],
+        );
+
         Self {
             transform_bind_group,
             transform_matrix_buffer,
             sun_information_buffer,
             sun_information_bind_group,
+            glint_information_buffer,
+            glint_information_bind_group,
             textures: None,
             smaa_target: None,
         }
@@ -82,6 +93,18 @@ impl SceneContext {
             .write_buffer(&self.sun_information_buffer, 0, data);
     }
 
+    pub(crate) fn set_glint_information(
+        &self,
+        context: &GraphicsContext,
+        glint_information: &GlintInformation,
+    ) {
+        let binding = [*glint_information];
+        let data = bytemuck::cast_slice(&binding);
+        context
+            .queue
+            .write_buffer(&self.glint_information_buffer, 0, data);
+    }
+
     #[instrument(skip(self, graphics_context, camera, sun, viewport_size))]
     pub(crate) fn init(
         &mut self,
@@ -105,12 +128,12 @@ impl SceneContext {
         let needs_texture_resize = self
             .textures
             .as_ref()
-            .map_or(true, |textures| textures.camera_size != camera_size);
+            .is_none_or(|textures| textures.camera_size != camera_size);
 
         let needs_output_buffer_resize = self
             .textures
             .as_ref()
-            .map_or(true, |textures| textures.viewport_size != viewport_size);
+            .is_none_or(|textures| textures.viewport_size != viewport_size);
 
         let output = if needs_output_buffer_resize {
             let output_buffer_dimensions = BufferDimensions::new(
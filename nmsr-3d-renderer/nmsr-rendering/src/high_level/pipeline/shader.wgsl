@@ -32,6 +32,16 @@ var texture_sampler: sampler;
 @binding(0)
 var<uniform> sun: SunInformation;
 
+struct GlintInformation {
+    offset: vec2<f32>,
+    rotation: f32,
+    scale: f32,
+}
+
+@group(3)
+@binding(0)
+var<uniform> glint: GlintInformation;
+
 @vertex
 fn vs_main(
     vertex: VertexInput,
@@ -69,4 +79,40 @@ fn fs_main(vertex: VertexOutput) -> @location(0) vec4<f32> {
     }
     
     return compute_sun_lighting(color, vertex.normal);
-}
\ No newline at end of file
+}
+
+// Textures that give off their own light (like the name tag) aren't shaded by the sun.
+@fragment
+fn fs_unlit(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    var color: vec4<f32> = textureSample(
+        texture,
+        texture_sampler,
+        vec2<f32>(vertex.tex_coord)
+    );
+    
+    if (color.a == 0.0) {
+        discard;
+    }
+    
+    return color;
+}
+
+// The enchantment glint is scaled, rotated and scrolled over the armor like the game does with its texture matrix,
+// and added on top of the armor by the blend state of the glint pipeline.
+@fragment
+fn fs_glint(vertex: VertexOutput) -> @location(0) vec4<f32> {
+    var scaled: vec2<f32> = vertex.tex_coord * glint.scale;
+    
+    var rotated: vec2<f32> = vec2<f32>(
+        scaled.x * cos(glint.rotation) - scaled.y * sin(glint.rotation),
+        scaled.x * sin(glint.rotation) + scaled.y * cos(glint.rotation),
+    );
+    
+    var color: vec4<f32> = textureSample(
+        texture,
+        texture_sampler,
+        rotated + glint.offset
+    );
+    
+    return vec4<f32>(color.rgb, 0.0);
+}
@@ -19,12 +19,13 @@ mod routes;
 mod utils;
 
 use crate::{
-    routes::{render, render_get_warning, render_post_warning, NMSRState},
-    utils::tracing::NmsrTracing,
+    routes::{info, openapi, render, render_get_warning, render_post_warning, render_preset, NMSRState},
+    utils::tracing::{NmsrTraceLayer, NmsrTracing},
 };
 
 use crate::utils::config::NmsrConfiguration;
 use anyhow::Context;
+use axum::middleware;
 use axum::routing::post;
 use axum::{routing::get, Router};
 use http::HeaderName;
@@ -52,10 +53,11 @@ use utils::config::TracingConfiguration;
 pub use utils::{caching, config, error};
 
 #[main]
+#[allow(clippy::significant_drop_tightening)] // The state lives on in the router
 async fn main() -> anyhow::Result<()> {
     let init_guard = info_span!("NMSRaaS init").entered();
     let toml_path: PathBuf = "config.toml".into();
-    let toml_layer = Some(Layer::Toml(toml_path.clone())).filter(|_| toml_path.exists());
+    let toml_layer = toml_path.exists().then(|| Layer::Toml(toml_path.clone()));
 
     let layers: Vec<_> = vec![
         Some(Layer::DefaultTrait),
@@ -85,11 +87,20 @@ async fn main() -> anyhow::Result<()> {
     );
 
     // build our application with a route
-    let router = Router::new()
+    let router = Router::new();
+
+    #[cfg(feature = "ears")]
+    let router = router.route("/ears", post(routes::encode_ears));
+
+    let router = router
+        .route("/openapi.json", get(openapi))
+        .route("/info/:entry", get(info))
+        .route("/preset/:preset/:texture", get(render_preset))
         .route("/:mode/:texture", get(render))
         .route("/:mode/:texture", post(render_post_warning))
         .route("/:mode", get(render_get_warning))
         .route("/:mode", post(render))
+        .layer(middleware::from_fn(error::problem_details_middleware))
         .with_state(state);
 
     let router = if let Some(path) = config.server.static_files_directory {
@@ -104,15 +115,8 @@ async fn main() -> anyhow::Result<()> {
         router.route("/", get(root))
     };
 
-    let trace_layer: tower_http::trace::TraceLayer<
-        tower_http::classify::SharedClassifier<tower_http::classify::ServerErrorsAsFailures>,
-        NmsrTracing<axum::body::Body>,
-        NmsrTracing<axum::body::Body>,
-        NmsrTracing<axum::body::Body>,
-        tower_http::trace::DefaultOnBodyChunk,
-        (),
-        NmsrTracing<axum::body::Body>,
-    > = NmsrTracing::new_trace_layer();
+    let trace_layer: NmsrTraceLayer<axum::body::Body, axum::body::Body> =
+        NmsrTracing::new_trace_layer();
 
     let app = router
         .layer(SetRequestIdLayer::new(
@@ -212,8 +216,8 @@ async fn shutdown_signal() {
     let terminate = std::future::pending::<()>();
 
     tokio::select! {
-        _ = ctrl_c => {},
-        _ = terminate => {},
+        () = ctrl_c => {},
+        () = terminate => {},
     }
 
     info!("Received shutdown signal... Shutting down.");
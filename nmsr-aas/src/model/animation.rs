@@ -0,0 +1,158 @@
+use std::path::Path;
+
+use nmsr_rendering::high_level::{
+    animation::{PlayerAnimation, PoseKeyframe},
+    pose::{PartRotation, PlayerPose, PlayerPosePreset},
+};
+use serde::{de, Deserialize};
+use serde_with::{serde_as, DisplayFromStr};
+
+use crate::error::{ExplainableExt, RenderRequestError, Result};
+
+/// A custom animation, as defined in a JSON file.
+///
+/// ```json
+/// {
+///     "duration": 1.5,
+///     "looping": true,
+///     "keyframes": [
+///         { "time": 0.0, "pose": "walking" },
+///         { "time": 0.5, "right_arm": [0, 0, 150], "lean": 5 }
+///     ]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnimationDefinition {
+    /// How long a full cycle of the animation lasts, in seconds.
+    #[serde(default = "default_duration")]
+    pub duration: f32,
+    #[serde(default = "default_looping")]
+    pub looping: bool,
+    pub keyframes: Vec<KeyframeDefinition>,
+}
+
+/// A keyframe of an [`AnimationDefinition`].
+///
+/// The rotations are given as `[pitch, yaw, roll]` in degrees, like the limb query parameters,
+/// and override the rotations of the `pose` the keyframe is based on.
+#[serde_as]
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeyframeDefinition {
+    /// The point of the animation this keyframe is at, from `0.0` (start) to `1.0` (end).
+    pub time: f32,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub pose: Option<PlayerPosePreset>,
+    pub head: Option<[f32; 3]>,
+    pub left_arm: Option<[f32; 3]>,
+    pub right_arm: Option<[f32; 3]>,
+    pub left_leg: Option<[f32; 3]>,
+    pub right_leg: Option<[f32; 3]>,
+    pub lean: Option<f32>,
+}
+
+const fn default_duration() -> f32 {
+    1.0
+}
+
+const fn default_looping() -> bool {
+    true
+}
+
+impl From<KeyframeDefinition> for PoseKeyframe {
+    fn from(keyframe: KeyframeDefinition) -> Self {
+        let mut pose = keyframe.pose.map(PlayerPose::from).unwrap_or_default();
+
+        let limbs = [
+            (&mut pose.head, keyframe.head),
+            (&mut pose.left_arm, keyframe.left_arm),
+            (&mut pose.right_arm, keyframe.right_arm),
+            (&mut pose.left_leg, keyframe.left_leg),
+            (&mut pose.right_leg, keyframe.right_leg),
+        ];
+
+        for (rotation, keyframe_rotation) in limbs {
+            if let Some(keyframe_rotation) = keyframe_rotation {
+                *rotation = PartRotation::from(keyframe_rotation);
+            }
+        }
+
+        if let Some(lean) = keyframe.lean {
+            pose.body_lean = lean;
+        }
+
+        Self::new(keyframe.time.clamp(0.0, 1.0), pose)
+    }
+}
+
+impl From<AnimationDefinition> for PlayerAnimation {
+    fn from(definition: AnimationDefinition) -> Self {
+        let keyframes = definition
+            .keyframes
+            .into_iter()
+            .map(PoseKeyframe::from)
+            .collect();
+
+        Self::new(keyframes, definition.duration, definition.looping)
+    }
+}
+
+impl AnimationDefinition {
+    pub async fn load(path: &Path) -> Result<PlayerAnimation> {
+        let contents = tokio::fs::read(path)
+            .await
+            .explain_closure(|| format!("Unable to read animation file {}", path.display()))?;
+
+        let definition: Self = serde_json::from_slice(&contents).map_err(|e| {
+            RenderRequestError::InvalidAnimationDefinitionError(path.to_path_buf(), e)
+        })?;
+
+        if !definition.duration.is_finite() || definition.duration <= 0.0 {
+            return Err(RenderRequestError::InvalidAnimationDefinitionError(
+                path.to_path_buf(),
+                de::Error::custom(format!(
+                    "duration must be a positive amount of seconds, got {}",
+                    definition.duration
+                )),
+            )
+            .into());
+        }
+
+        Ok(definition.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{NMSRaaSError, RenderRequestError};
+
+    use super::AnimationDefinition;
+
+    #[tokio::test]
+    async fn test_load_animation_duration() {
+        let path = std::env::temp_dir().join(format!("nmsr-animation-{}.json", std::process::id()));
+
+        for (duration, valid) in [("1.5", true), ("0", false), ("-2", false)] {
+            let json = format!(r#"{{ "duration": {duration}, "keyframes": [{{ "time": 0.0, "pose": "walking" }}] }}"#);
+            tokio::fs::write(&path, json).await.expect("Failed to write animation");
+
+            let result = AnimationDefinition::load(&path).await;
+
+            if valid {
+                assert_eq!(Some(1.5), result.ok().map(|animation| animation.duration));
+            } else {
+                assert!(
+                    matches!(
+                        result,
+                        Err(NMSRaaSError::RenderRequestError(
+                            RenderRequestError::InvalidAnimationDefinitionError(_, _)
+                        ))
+                    ),
+                    "Duration {duration} should be rejected"
+                );
+            }
+        }
+
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use image::RgbaImage;
+use zip::ZipArchive;
+
+use crate::error::{ArmorManagerError, ArmorManagerResult};
+
+/// The folder of the vanilla namespace, which every asset path is relative to.
+const NAMESPACE_FOLDER: &str = "assets/minecraft/";
+
+/// The folders of the assets we need relative to the vanilla namespace, along with the extension of their files.
+const ASSET_FOLDERS: [(&str, &str); 7] = [
+    ("textures/models/armor/", "png"),
+    ("textures/trims/", "png"),
+    ("textures/entity/", "png"),
+    ("textures/misc/", "png"),
+    ("textures/block/", "png"),
+    ("textures/item/", "png"),
+    ("models/", "json"),
+];
+
+/// The assets read from the configured asset packs, which are either a vanilla client jar,
+/// or a resource pack (as a directory or a ZIP file).
+///
+/// Packs are read in order, so that the assets of a pack replace the ones of the packs before it.
+#[derive(Default)]
+pub struct AssetPacks {
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl AssetPacks {
+    pub fn load(packs: &[PathBuf]) -> ArmorManagerResult<Self> {
+        let mut assets = Self::default();
+
+        for pack in packs {
+            if pack.is_dir() {
+                assets.load_directory(pack, pack)?;
+            } else {
+                assets.load_archive(pack)?;
+            }
+        }
+
+        Ok(assets)
+    }
+
+    fn load_directory(&mut self, pack: &Path, directory: &Path) -> ArmorManagerResult<()> {
+        let entries = fs::read_dir(directory).map_err(|e| pack_error(pack, &e))?;
+
+        for entry in entries {
+            let path = entry.map_err(|e| pack_error(pack, &e))?.path();
+
+            if path.is_dir() {
+                self.load_directory(pack, &path)?;
+                continue;
+            }
+
+            let Ok(relative_path) = path.strip_prefix(pack) else {
+                continue;
+            };
+            let name = relative_path.to_string_lossy().replace('\\', "/");
+
+            if let Some(asset_path) = Self::get_asset_path(&name) {
+                let bytes = fs::read(&path).map_err(|e| pack_error(pack, &e))?;
+
+                self.files.insert(asset_path, bytes);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn load_archive(&mut self, pack: &Path) -> ArmorManagerResult<()> {
+        let file = File::open(pack).map_err(|e| pack_error(pack, &e))?;
+        let mut archive = ZipArchive::new(file).map_err(|e| pack_error(pack, &e))?;
+
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index).map_err(|e| pack_error(pack, &e))?;
+
+            let Some(asset_path) = Self::get_asset_path(entry.name()) else {
+                continue;
+            };
+
+            let mut bytes = Vec::with_capacity(entry.size() as usize);
+            entry
+                .read_to_end(&mut bytes)
+                .map_err(|e| pack_error(pack, &e))?;
+
+            self.files.insert(asset_path, bytes);
+        }
+
+        Ok(())
+    }
+
+    /// The path of a file of a pack relative to the vanilla namespace, if it's an asset we need.
+    ///
+    /// Resource packs are sometimes zipped along with the folder they're in, so anything before the namespace is ignored.
+    fn get_asset_path(name: &str) -> Option<String> {
+        let (_, asset_path) = name.split_once(NAMESPACE_FOLDER)?;
+        let extension = Path::new(asset_path).extension()?;
+
+        let is_needed = ASSET_FOLDERS
+            .iter()
+            .any(|(folder, folder_extension)| asset_path.starts_with(folder) && extension == *folder_extension);
+
+        is_needed.then(|| asset_path.to_owned())
+    }
+
+    /// The paths of every texture found in the given folder (relative to the vanilla namespace), excluding sub-folders.
+    pub fn get_textures_in<'a>(&'a self, folder: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.files.keys().filter_map(move |path| {
+            path.strip_prefix(folder)
+                .filter(|name| !name.contains('/'))
+                .map(|_| path.as_str())
+        })
+    }
+
+    #[must_use]
+    pub fn contains(&self, path: &str) -> bool {
+        self.files.contains_key(path)
+    }
+
+    /// The contents of a file of the asset packs, like a block or item model.
+    #[must_use]
+    pub fn get_file(&self, path: &str) -> Option<&[u8]> {
+        self.files.get(path).map(Vec::as_slice)
+    }
+
+    pub fn get_texture(&self, path: &str) -> ArmorManagerResult<RgbaImage> {
+        let bytes = self
+            .files
+            .get(path)
+            .ok_or_else(|| ArmorManagerError::MissingArmorTextureError(path.to_owned()))?;
+
+        let image = image::load_from_memory(bytes)
+            .map_err(|e| ArmorManagerError::ArmorTextureLoadError(path.to_owned(), e))?
+            .into_rgba8();
+
+        Ok(image)
+    }
+}
+
+fn pack_error(pack: &Path, error: &impl ToString) -> ArmorManagerError {
+    ArmorManagerError::AssetPackLoadError(pack.to_path_buf(), error.to_string())
+}
+
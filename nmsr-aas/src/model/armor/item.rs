@@ -0,0 +1,597 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use derive_more::Debug;
+use image::{imageops, GenericImageView, RgbaImage};
+use nmsr_rendering::{
+    high_level::{
+        model::item::BuiltInItemModel,
+        parts::{
+            java_model::{
+                JavaModel, JavaModelElement, JavaModelElementRotation, JavaModelTransform,
+            },
+            uv::FaceUv,
+        },
+    },
+    low_level::Vec3,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::{ArmorManagerError, ArmorManagerResult};
+
+use super::assets::AssetPacks;
+
+/// How deep the parents of a model (`"parent": "item/handheld"`) can go.
+const MAX_MODEL_INHERITANCE_DEPTH: usize = 16;
+
+/// The parent of item models that are made out of their sprites, extruded to be one pixel thick.
+const GENERATED_MODEL: &str = "builtin/generated";
+
+/// The parent of item models that are rendered with an entity model, like shields and tridents.
+const ENTITY_MODEL: &str = "builtin/entity";
+
+/// The size of blocks and item sprites in model space, which UVs are also given in.
+const MODEL_SIZE: f32 = 16.0;
+
+/// How far display transforms can move a model, and how much they can scale it, as clamped by the game.
+const MAX_DISPLAY_TRANSLATION: f32 = 80.0;
+const MAX_DISPLAY_SCALE: f32 = 4.0;
+
+/// The faces of an element in the order of [`JavaModelElement::faces`].
+const FACE_NAMES: [&str; 6] = ["north", "south", "east", "west", "up", "down"];
+
+/// An item held in one of the hands of the entry, with its model and the texture its faces are laid out on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeldItem {
+    pub name: String,
+    #[debug(skip)]
+    pub model: Arc<JavaModel>,
+    #[debug(skip)]
+    pub texture: Arc<RgbaImage>,
+}
+
+/// A Java Edition block or item model file (`models/item/diamond_sword.json`).
+///
+/// ```json
+/// {
+///     "parent": "minecraft:item/handheld",
+///     "textures": { "layer0": "minecraft:item/diamond_sword" }
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+struct ModelFile {
+    parent: Option<String>,
+    #[serde(default)]
+    textures: HashMap<String, Value>,
+    elements: Option<Vec<ElementDefinition>>,
+    #[serde(default)]
+    display: HashMap<String, TransformDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ElementDefinition {
+    from: [f32; 3],
+    to: [f32; 3],
+    rotation: Option<RotationDefinition>,
+    #[serde(default)]
+    faces: HashMap<String, FaceDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RotationDefinition {
+    origin: [f32; 3],
+    axis: String,
+    angle: f32,
+    #[serde(default)]
+    rescale: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FaceDefinition {
+    uv: Option<[f32; 4]>,
+    texture: String,
+    #[serde(default)]
+    rotation: u32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct TransformDefinition {
+    #[serde(default)]
+    rotation: [f32; 3],
+    #[serde(default)]
+    translation: [f32; 3],
+    #[serde(default = "TransformDefinition::default_scale")]
+    scale: [f32; 3],
+}
+
+impl TransformDefinition {
+    const fn default_scale() -> [f32; 3] {
+        [1.0; 3]
+    }
+}
+
+impl From<TransformDefinition> for JavaModelTransform {
+    fn from(transform: TransformDefinition) -> Self {
+        Self {
+            rotation: transform.rotation.into(),
+            translation: Vec3::from(transform.translation)
+                .clamp(Vec3::splat(-MAX_DISPLAY_TRANSLATION), Vec3::splat(MAX_DISPLAY_TRANSLATION)),
+            scale: Vec3::from(transform.scale)
+                .clamp(Vec3::splat(-MAX_DISPLAY_SCALE), Vec3::splat(MAX_DISPLAY_SCALE)),
+        }
+    }
+}
+
+/// A model merged with the models it inherits from, where the settings of a child replace the ones of its parents.
+#[derive(Debug, Default)]
+struct MergedModel {
+    /// The path of the model, for errors.
+    path: String,
+    textures: HashMap<String, String>,
+    elements: Option<Vec<ElementDefinition>>,
+    display: HashMap<String, TransformDefinition>,
+    /// The built-in model at the root of the parents, if any.
+    builtin: Option<String>,
+}
+
+/// A texture stitched into the texture of a model, as its horizontal offset and its size.
+#[derive(Debug, Clone, Copy)]
+struct Sprite {
+    index: usize,
+    x: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Sprite {
+    /// Convert a point given in model space (from 0 to 16) to the pixels of the stitched texture.
+    fn to_pixels(self, u: f32, v: f32) -> (u16, u16) {
+        let x = self.x as f32 + u * self.width as f32 / MODEL_SIZE;
+        let y = v * self.height as f32 / MODEL_SIZE;
+
+        (x.round().max(0.0) as u16, y.round().max(0.0) as u16)
+    }
+
+    fn to_face_uv(self, [u1, v1, u2, v2]: [f32; 4]) -> FaceUv {
+        let (x1, y1) = self.to_pixels(u1, v1);
+        let (x2, y2) = self.to_pixels(u2, v2);
+
+        FaceUv::new(x1, y1, x2, y2)
+    }
+}
+
+/// The textures used by a model, stitched side by side into a single one.
+#[derive(Debug, Default)]
+struct TextureAtlas {
+    sprites: HashMap<String, Sprite>,
+    textures: Vec<RgbaImage>,
+}
+
+impl TextureAtlas {
+    fn add(&mut self, assets: &AssetPacks, path: &str) -> ArmorManagerResult<Sprite> {
+        if let Some(sprite) = self.sprites.get(path) {
+            return Ok(*sprite);
+        }
+
+        let mut texture = assets.get_texture(path)?;
+
+        // Animated textures have their frames stacked vertically, so only the first one is used
+        if texture.height() > texture.width() {
+            texture = texture.view(0, 0, texture.width(), texture.width()).to_image();
+        }
+
+        let sprite = Sprite {
+            index: self.textures.len(),
+            x: self.textures.iter().map(RgbaImage::width).sum(),
+            width: texture.width(),
+            height: texture.height(),
+        };
+
+        self.sprites.insert(path.to_owned(), sprite);
+        self.textures.push(texture);
+
+        Ok(sprite)
+    }
+
+    fn into_texture(self) -> RgbaImage {
+        let width = self.textures.iter().map(RgbaImage::width).sum::<u32>().max(1);
+        let height = self.textures.iter().map(RgbaImage::height).max().unwrap_or(1);
+
+        let mut atlas = RgbaImage::new(width, height);
+        let mut x = 0;
+
+        for texture in &self.textures {
+            imageops::replace(&mut atlas, texture, i64::from(x), 0);
+            x += texture.width();
+        }
+
+        atlas
+    }
+}
+
+/// Load the model of an item (or a block) from the asset packs, along with the texture its faces are laid out on.
+///
+/// Items with a dedicated model for when they're held (like `trident_in_hand`) use it, and blocks without an
+/// item model use their block model.
+pub fn load_item_model(assets: &AssetPacks, item: &str) -> ArmorManagerResult<(JavaModel, RgbaImage)> {
+    let name = strip_namespace(item).to_ascii_lowercase();
+
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || "_-./".contains(c)) {
+        return Err(ArmorManagerError::UnknownItemError(item.to_owned()));
+    }
+
+    let path = [
+        format!("models/item/{name}_in_hand.json"),
+        format!("models/item/{name}.json"),
+        format!("models/block/{name}.json"),
+    ]
+    .into_iter()
+    .find(|path| assets.contains(path));
+
+    let model = match &path {
+        Some(path) => merge_model(assets, path)?,
+        None => MergedModel::default(),
+    };
+
+    let builtin = BuiltInItemModel::from_str(&name).ok();
+
+    if let Some(elements) = &model.elements {
+        let mut atlas = TextureAtlas::default();
+        let elements = elements
+            .iter()
+            .map(|element| convert_element(assets, &model, &mut atlas, element))
+            .collect::<ArmorManagerResult<_>>()?;
+
+        return Ok(finish_model(&model, elements, atlas.into_texture()));
+    }
+
+    if model.builtin.as_deref() == Some(GENERATED_MODEL) {
+        let mut atlas = TextureAtlas::default();
+        let mut elements = Vec::new();
+
+        for layer in (0..).map(|index| format!("layer{index}")) {
+            let Some(texture) = resolve_texture(&model, &layer) else {
+                break;
+            };
+
+            let sprite = atlas.add(assets, &texture)?;
+            elements.extend(extrude_sprite(sprite, &atlas.textures[sprite.index]));
+        }
+
+        return Ok(finish_model(&model, elements, atlas.into_texture()));
+    }
+
+    // Items rendered with an entity model (or whose item model doesn't exist in older packs)
+    match builtin {
+        Some(builtin) if path.is_none() || model.builtin.as_deref() == Some(ENTITY_MODEL) => {
+            let texture = assets.get_texture(builtin.get_texture_path())?;
+            let mut builtin_model = builtin.get_model();
+
+            if let Some(right_hand) = model.display.get("thirdperson_righthand") {
+                builtin_model.right_hand_transform = (*right_hand).into();
+                builtin_model.left_hand_transform = builtin_model.right_hand_transform;
+            }
+
+            if let Some(left_hand) = model.display.get("thirdperson_lefthand") {
+                builtin_model.left_hand_transform = (*left_hand).into();
+            }
+
+            Ok((builtin_model, texture))
+        }
+        _ if path.is_none() => Err(ArmorManagerError::UnknownItemError(item.to_owned())),
+        _ => Err(invalid_model(&name, &"the model has no elements")),
+    }
+}
+
+/// Read a model and the models it inherits from, up to a built-in model or one without a parent.
+fn merge_model(assets: &AssetPacks, path: &str) -> ArmorManagerResult<MergedModel> {
+    let mut merged = MergedModel {
+        path: path.to_owned(),
+        ..Default::default()
+    };
+    let mut current = Some(path.to_owned());
+
+    for _ in 0..MAX_MODEL_INHERITANCE_DEPTH {
+        let Some(path) = current.take() else {
+            break;
+        };
+
+        let bytes = assets
+            .get_file(&path)
+            .ok_or_else(|| invalid_model(&path, &"the model doesn't exist"))?;
+        let model: ModelFile = serde_json::from_slice(bytes).map_err(|e| invalid_model(&path, &e))?;
+
+        for (key, value) in model.textures {
+            // Newer versions can give textures as objects, with the sprite as one of their fields
+            let texture = match value {
+                Value::String(texture) => texture,
+                Value::Object(mut object) => match object.remove("sprite") {
+                    Some(Value::String(texture)) => texture,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            merged.textures.entry(key).or_insert(texture);
+        }
+
+        if merged.elements.is_none() {
+            merged.elements = model.elements;
+        }
+
+        for (key, transform) in model.display {
+            merged.display.entry(key).or_insert(transform);
+        }
+
+        if let Some(parent) = model.parent {
+            let parent = strip_namespace(&parent);
+
+            if parent.starts_with("builtin/") {
+                merged.builtin = Some(parent.to_owned());
+            } else {
+                current = Some(format!("models/{parent}.json"));
+            }
+        }
+    }
+
+    if let Some(path) = current {
+        return Err(invalid_model(&path, &"the model has too many parents"));
+    }
+
+    Ok(merged)
+}
+
+/// The path of a texture of a model, following the references to other textures (`"#layer0"`).
+fn resolve_texture(model: &MergedModel, name: &str) -> Option<String> {
+    let mut texture = name;
+
+    for _ in 0..=model.textures.len() {
+        let key = texture.strip_prefix('#').unwrap_or(texture);
+
+        match model.textures.get(key) {
+            Some(value) if value.starts_with('#') => texture = value,
+            Some(value) => return Some(format!("textures/{}.png", strip_namespace(value))),
+            None => return None,
+        }
+    }
+
+    None
+}
+
+fn convert_element(
+    assets: &AssetPacks,
+    model: &MergedModel,
+    atlas: &mut TextureAtlas,
+    element: &ElementDefinition,
+) -> ArmorManagerResult<JavaModelElement> {
+    let (from, to) = (Vec3::from(element.from), Vec3::from(element.to));
+
+    let rotation = element
+        .rotation
+        .as_ref()
+        .map(|rotation| {
+            let axis = match rotation.axis.as_str() {
+                "x" => Vec3::X,
+                "y" => Vec3::Y,
+                "z" => Vec3::Z,
+                axis => return Err(invalid_model(&model.path, &format!("unknown rotation axis {axis:?}"))),
+            };
+
+            Ok(JavaModelElementRotation {
+                origin: rotation.origin.into(),
+                axis,
+                angle: rotation.angle,
+                rescale: rotation.rescale,
+            })
+        })
+        .transpose()?;
+
+    let mut faces = [None; 6];
+
+    for (face_uv, name) in faces.iter_mut().zip(FACE_NAMES) {
+        let Some(face) = element.faces.get(name) else {
+            continue;
+        };
+
+        // Faces with a missing texture are left out, instead of being rendered with the missing texture
+        let Some(texture) = resolve_texture(model, &face.texture) else {
+            continue;
+        };
+
+        let sprite = atlas.add(assets, &texture)?;
+        let uv = sprite.to_face_uv(face.uv.unwrap_or_else(|| get_default_face_uv(name, from, to)));
+
+        // The game turns the texture clockwise, which is the other way around from the way we do
+        *face_uv = Some((0..(4 - face.rotation / 90 % 4) % 4).fold(uv, |uv, _| uv.rotate_cw()));
+    }
+
+    Ok(JavaModelElement {
+        from,
+        to,
+        rotation,
+        faces,
+    })
+}
+
+/// The UVs the game gives to faces without any, which map the face to the part of the texture it would cover on a block.
+fn get_default_face_uv(face: &str, from: Vec3, to: Vec3) -> [f32; 4] {
+    match face {
+        "down" => [from.x, MODEL_SIZE - to.z, to.x, MODEL_SIZE - from.z],
+        "up" => [from.x, from.z, to.x, to.z],
+        "north" => [MODEL_SIZE - to.x, MODEL_SIZE - to.y, MODEL_SIZE - from.x, MODEL_SIZE - from.y],
+        "south" => [from.x, MODEL_SIZE - to.y, to.x, MODEL_SIZE - from.y],
+        "west" => [from.z, MODEL_SIZE - to.y, to.z, MODEL_SIZE - from.y],
+        _ => [MODEL_SIZE - to.z, MODEL_SIZE - to.y, MODEL_SIZE - from.z, MODEL_SIZE - from.y],
+    }
+}
+
+/// Build the elements of a sprite extruded to be one pixel thick, like the game does for `builtin/generated` models.
+///
+/// The front and the back of the sprite are single faces, and the sides are made of a face for each edge of an
+/// opaque pixel next to a transparent one.
+fn extrude_sprite(sprite: Sprite, texture: &RgbaImage) -> Vec<JavaModelElement> {
+    let (front_z, back_z) = (7.5, 8.5);
+    let (width, height) = (sprite.width as u16, sprite.height as u16);
+    let x = sprite.x as u16;
+
+    let mut elements = vec![JavaModelElement {
+        from: Vec3::new(0.0, 0.0, front_z),
+        to: Vec3::new(MODEL_SIZE, MODEL_SIZE, back_z),
+        rotation: None,
+        faces: [
+            Some(FaceUv::new(x + width, 0, x, height)),
+            Some(FaceUv::new(x, 0, x + width, height)),
+            None,
+            None,
+            None,
+            None,
+        ],
+    }];
+
+    let is_opaque = |x: i64, y: i64| {
+        u32::try_from(x)
+            .ok()
+            .zip(u32::try_from(y).ok())
+            .and_then(|(x, y)| texture.get_pixel_checked(x, y))
+            .is_some_and(|pixel| pixel[3] > 0)
+    };
+
+    let (pixel_width, pixel_height) = (MODEL_SIZE / f32::from(width), MODEL_SIZE / f32::from(height));
+
+    for (pixel_x, pixel_y) in (0..height).flat_map(|y| (0..width).map(move |x| (x, y))) {
+        let (px, py) = (i64::from(pixel_x), i64::from(pixel_y));
+
+        if !is_opaque(px, py) {
+            continue;
+        }
+
+        // North, south, east, west, up and down, where only the sides can border another pixel
+        let neighbours = [None, None, Some((1, 0)), Some((-1, 0)), Some((0, -1)), Some((0, 1))];
+        let uv = FaceUv::new(x + pixel_x, pixel_y, x + pixel_x + 1, pixel_y + 1);
+
+        let faces = neighbours.map(|offset| {
+            offset
+                .filter(|(dx, dy)| !is_opaque(px + dx, py + dy))
+                .map(|_| uv)
+        });
+
+        if faces.iter().all(Option::is_none) {
+            continue;
+        }
+
+        let from = Vec3::new(
+            f32::from(pixel_x) * pixel_width,
+            f32::from(height - pixel_y - 1) * pixel_height,
+            front_z,
+        );
+
+        elements.push(JavaModelElement {
+            from,
+            to: from + Vec3::new(pixel_width, pixel_height, back_z - front_z),
+            rotation: None,
+            faces,
+        });
+    }
+
+    elements
+}
+
+fn finish_model(model: &MergedModel, elements: Vec<JavaModelElement>, texture: RgbaImage) -> (JavaModel, RgbaImage) {
+    let right_hand_transform = model
+        .display
+        .get("thirdperson_righthand")
+        .copied()
+        .map(JavaModelTransform::from)
+        .unwrap_or_default();
+
+    let left_hand_transform = model
+        .display
+        .get("thirdperson_lefthand")
+        .copied()
+        .map_or(right_hand_transform, JavaModelTransform::from);
+
+    let model = JavaModel {
+        texture_size: texture.dimensions(),
+        elements,
+        right_hand_transform,
+        left_hand_transform,
+    };
+
+    (model, texture)
+}
+
+fn strip_namespace(name: &str) -> &str {
+    name.strip_prefix("minecraft:").unwrap_or(name)
+}
+
+fn invalid_model(model: &str, error: &impl ToString) -> ArmorManagerError {
+    ArmorManagerError::InvalidItemModelError(model.to_owned(), error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use image::{Rgba, RgbaImage};
+
+    use super::load_item_model;
+    use crate::{error::ArmorManagerError, model::armor::assets::AssetPacks};
+
+    #[test]
+    fn test_item_models_from_resource_pack() {
+        let pack = std::env::temp_dir().join(format!("nmsr-item-test-{}", std::process::id()));
+        let assets = pack.join("assets/minecraft");
+
+        let models = [
+            ("item/generated", r#"{ "parent": "builtin/generated" }"#),
+            (
+                "item/handheld",
+                r#"{ "parent": "item/generated", "display": { "thirdperson_righthand": { "rotation": [0, -90, 55], "translation": [0, 4, 0.5], "scale": [0.85, 0.85, 0.85] } } }"#,
+            ),
+            ("item/stick", r##"{ "parent": "minecraft:item/handheld", "textures": { "layer0": "#stick" , "stick": "item/stick" } }"##),
+            (
+                "block/dirt",
+                r##"{ "textures": { "all": "block/dirt" }, "elements": [{ "from": [0, 0, 0], "to": [16, 16, 16], "faces": { "north": { "texture": "#all" }, "up": { "texture": "#all", "uv": [0, 0, 8, 8], "rotation": 90 } } }] }"##,
+            ),
+        ];
+
+        for (name, model) in models {
+            let path = assets.join(format!("models/{name}.json"));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, model).unwrap();
+        }
+
+        // A diagonal line of pixels, where every pixel only touches the others by its corners
+        let stick = RgbaImage::from_fn(16, 16, |x, y| Rgba([255, 255, 255, if x == y { 255 } else { 0 }]));
+        let dirt = RgbaImage::from_pixel(16, 32, Rgba([100, 60, 20, 255]));
+
+        for (path, image) in [("textures/item/stick.png", &stick), ("textures/block/dirt.png", &dirt)] {
+            let path = assets.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            image.save(path).unwrap();
+        }
+
+        let assets = AssetPacks::load(std::slice::from_ref(&pack)).expect("Pack should load");
+        fs::remove_dir_all(pack).unwrap();
+
+        let (stick_model, stick_texture) = load_item_model(&assets, "minecraft:stick").expect("Stick should load");
+        assert_eq!((16, 16), stick_texture.dimensions());
+        // The front and back, and then one element with four sides for each pixel
+        assert_eq!(17, stick_model.elements.len());
+        assert!(stick_model.elements[1..].iter().all(|element| element.faces.iter().flatten().count() == 4));
+        assert!((stick_model.right_hand_transform.scale.x - 0.85).abs() < 1e-4);
+        assert_eq!(stick_model.right_hand_transform, stick_model.left_hand_transform);
+
+        // Blocks without an item model use their block model, and animated textures only keep their first frame
+        let (dirt_model, dirt_texture) = load_item_model(&assets, "dirt").expect("Dirt should load");
+        assert_eq!((16, 16), dirt_texture.dimensions());
+        let faces = dirt_model.elements[0].faces;
+        assert_eq!(2, faces.iter().flatten().count());
+        assert!(faces[4].is_some_and(|up| up.top_right.x == 0 && up.top_right.y == 0));
+
+        assert!(matches!(
+            load_item_model(&assets, "diamond_sword"),
+            Err(ArmorManagerError::UnknownItemError(_))
+        ));
+    }
+}
@@ -1,199 +1,390 @@
-use std::path::PathBuf;
+use std::{collections::BTreeMap, sync::Arc};
 
 use ears_rs::utils::upgrade_skin_if_needed;
-use hyper::Method;
-use image::{GenericImageView, RgbaImage};
+use image::{imageops, GenericImageView, RgbaImage};
 use nmsr_rendering::high_level::{
-    model::{PlayerArmorSlot, PlayerArmorSlots},
+    model::{headwear::Headwear, mob::HumanoidMob, PlayerArmorSlot, PlayerArmorSlots},
     parts::provider::minecraft::compute_base_part,
 };
-use strum::IntoEnumIterator;
-use tokio::fs;
-use tracing::Span;
+use tracing::warn;
 
 use crate::{
-    error::{ArmorManagerError, ArmorManagerResult, ExplainableExt, Result},
-    utils::http_client::NmsrHttpClient,
+    error::{ArmorManagerError, ArmorManagerResult, Result},
+    model::nametag::NametagFont,
+    utils::config::AssetsConfiguration,
 };
 
 use super::{
-    VanillaMinecraftArmorMaterial, VanillaMinecraftArmorMaterialData, VanillaMinecraftArmorTrim,
-    VanillaMinecraftArmorTrimData, VanillaMinecraftArmorTrimPalette,
+    assets::AssetPacks,
+    item::{load_item_model, HeldItem},
+    resolve_partial_name, UploadedArmorLayers, VanillaMinecraftArmorMaterialData,
+    VanillaMinecraftArmorTrimData,
 };
 
+/// Where the textures of armor materials and trim patterns are, as (folder, suffix) pairs for the main layer and the leggings.
+/// The first layout is the one used until 1.21.1, the second one is the equipment layout that replaced it.
+const MATERIAL_LAYOUTS: [[(&str, &str); 2]; 2] = [
+    [
+        ("textures/models/armor/", "_layer_1.png"),
+        ("textures/models/armor/", "_layer_2.png"),
+    ],
+    [
+        ("textures/entity/equipment/humanoid/", ".png"),
+        ("textures/entity/equipment/humanoid_leggings/", ".png"),
+    ],
+];
+
+const TRIM_LAYOUTS: [[(&str, &str); 2]; 2] = [
+    [
+        ("textures/trims/models/armor/", ".png"),
+        ("textures/trims/models/armor/", "_leggings.png"),
+    ],
+    [
+        ("textures/trims/entity/humanoid/", ".png"),
+        ("textures/trims/entity/humanoid_leggings/", ".png"),
+    ],
+];
+
+const TRIM_PALETTES_FOLDER: &str = "textures/trims/color_palettes/";
+
+/// The palette of greys that trim patterns are painted with, which is replaced by the palette of the trim material.
+const TRIM_PALETTE_KEY: &str = "trim_palette";
+
+const ELYTRA_TEXTURE_PATHS: [&str; 2] = [
+    "textures/entity/equipment/wings/elytra.png",
+    "textures/entity/elytra.png",
+];
+
+/// The folders of the default skins of players since 1.19.3, for each model.
+const DEFAULT_SKIN_FOLDERS: [&str; 2] = ["textures/entity/player/wide/", "textures/entity/player/slim/"];
+
+/// The default skins of players before 1.19.3.
+const LEGACY_DEFAULT_SKIN_PATHS: [&str; 2] = ["textures/entity/steve.png", "textures/entity/alex.png"];
+
+const GLINT_TEXTURE_PATHS: [&str; 2] = [
+    "textures/misc/enchanted_glint_armor.png",
+    "textures/misc/enchanted_item_glint.png",
+];
+
+/// The block textures of the carved pumpkin, as (front, side, top).
+const CARVED_PUMPKIN_TEXTURE_PATHS: (&str, &str, &str) = (
+    "textures/block/carved_pumpkin.png",
+    "textures/block/pumpkin_side.png",
+    "textures/block/pumpkin_top.png",
+);
+
+/// The font of the game, which name tags are written with instead of the bundled one.
+const FONT_TEXTURE_PATH: &str = "textures/font/ascii.png";
+
+/// The size of the faces of blocks, which textures from HD resource packs are scaled down to.
+const BLOCK_FACE_SIZE: u32 = 16;
+
+/// The paths of the textures of an armor material or trim pattern, for the main layer and the leggings.
+type ArmorLayers = [Option<String>; 2];
+
 pub struct VanillaMinecraftArmorManager {
-    client: NmsrHttpClient,
-    material_location: PathBuf,
-    trims_location: PathBuf,
+    assets: AssetPacks,
+    materials: BTreeMap<String, ArmorLayers>,
+    trims: BTreeMap<String, ArmorLayers>,
+    trim_palettes: BTreeMap<String, String>,
+    default_skins: Vec<RgbaImage>,
+    nametag_font: Option<NametagFont>,
 }
 
 enum VanillaArmorApplicable<'a> {
-    Armor(VanillaMinecraftArmorMaterial),
-    Trim(
-        VanillaMinecraftArmorMaterial,
-        &'a VanillaMinecraftArmorTrimData,
-    ),
+    Armor(&'a str),
+    /// A layer uploaded with the request instead of a material of the asset packs.
+    Uploaded(RgbaImage),
+    Trim(&'a str, &'a VanillaMinecraftArmorTrimData),
 }
 
-impl<'a> VanillaArmorApplicable<'a> {
-    fn get_layer_name(&self, slot: PlayerArmorSlot) -> String {
-        match self {
-            Self::Armor(data) => data.get_layer_name(slot.layer_id(), false),
-            Self::Trim(_, trim) => trim.trim.get_layer_name(slot.is_leggings()),
+impl VanillaMinecraftArmorManager {
+    pub fn new(config: &AssetsConfiguration) -> Result<Self> {
+        if config.packs.is_empty() {
+            warn!("No asset packs were configured, so armor, elytras and mobs can't be rendered");
         }
-    }
-
-    fn apply_modifications_if_needed(&self, image: &mut RgbaImage) {
-        if let Self::Trim(armor_material, VanillaMinecraftArmorTrimData { material, .. }) = self {
-            let palette = material
-                .get_palette_for_trim_armor_material(*armor_material)
-                .get_palette_colors();
-            let trim_palette = VanillaMinecraftArmorTrimPalette::get_trim_palette();
 
-            for pixel in image.pixels_mut() {
-                if pixel[3] == 0 {
-                    continue;
-                }
-
-                if let Ok(index) = trim_palette.binary_search(&[pixel[0], pixel[1], pixel[2]]) {
-                    let actual_color = palette[index];
+        let assets = AssetPacks::load(&config.packs)?;
+
+        let materials = Self::discover_layers(&assets, &MATERIAL_LAYOUTS);
+        let trims = Self::discover_layers(&assets, &TRIM_LAYOUTS);
+
+        let trim_palettes = assets
+            .get_textures_in(TRIM_PALETTES_FOLDER)
+            .map(|path| (Self::get_name(TRIM_PALETTES_FOLDER, ".png", path), path))
+            .filter_map(|(name, path)| Some((name?.to_owned(), path.to_owned())))
+            .collect();
+
+        let default_skins = DEFAULT_SKIN_FOLDERS
+            .iter()
+            .flat_map(|folder| assets.get_textures_in(folder))
+            .chain(LEGACY_DEFAULT_SKIN_PATHS.into_iter().filter(|path| assets.contains(path)))
+            .filter_map(|path| assets.get_texture(path).ok())
+            .collect();
+
+        let nametag_font = assets.get_texture(FONT_TEXTURE_PATH).ok().map(NametagFont::new);
+
+        Ok(Self {
+            assets,
+            materials,
+            trims,
+            trim_palettes,
+            default_skins,
+            nametag_font,
+        })
+    }
 
-                    pixel[0] = actual_color[0];
-                    pixel[1] = actual_color[1];
-                    pixel[2] = actual_color[2];
+    /// Find every armor material (or trim pattern) in the asset packs, along with the textures of its layers.
+    fn discover_layers(
+        assets: &AssetPacks,
+        layouts: &[[(&str, &str); 2]],
+    ) -> BTreeMap<String, ArmorLayers> {
+        let mut result = BTreeMap::<String, ArmorLayers>::new();
+
+        for layout in layouts {
+            for (layer, (folder, suffix)) in layout.iter().enumerate() {
+                for path in assets.get_textures_in(folder) {
+                    let Some(name) = Self::get_name(folder, suffix, path) else {
+                        continue;
+                    };
+
+                    // Overlays (like the one of leather armor) and leggings live next to the main textures
+                    if layer == 0 && (name.ends_with("_overlay") || name.ends_with("_leggings")) {
+                        continue;
+                    }
+
+                    let layers = result.entry(name.to_owned()).or_default();
+                    layers[layer].get_or_insert_with(|| path.to_owned());
                 }
             }
         }
-    }
-}
-
-impl VanillaMinecraftArmorManager {
-    pub async fn new(cache_path: PathBuf) -> Result<Self> {
-        let armor_location = cache_path.join("armor");
 
-        let material_location = armor_location.join("material");
-        let trims_location = armor_location.join("trims");
+        result
+    }
 
-        fs::create_dir_all(&material_location)
-            .await
-            .explain("Unable to create armor cache folder".to_string())?;
+    fn get_name<'a>(folder: &str, suffix: &str, path: &'a str) -> Option<&'a str> {
+        path.strip_prefix(folder)?
+            .strip_suffix(suffix)
+            .filter(|name| !name.is_empty())
+    }
 
-        fs::create_dir_all(&trims_location)
-            .await
-            .explain("Unable to create armor cache folder".to_string())?;
+    /// The names of the armor materials found in the asset packs.
+    pub fn get_materials(&self) -> impl Iterator<Item = &String> + Clone {
+        self.materials.keys()
+    }
 
-        let manager = Self {
-            client: NmsrHttpClient::new(20),
-            material_location,
-            trims_location,
-        };
+    /// The names of the trim patterns found in the asset packs.
+    pub fn get_trims(&self) -> impl Iterator<Item = &String> + Clone {
+        self.trims.keys()
+    }
 
-        manager.init().await?;
+    /// The names of the trim materials found in the asset packs.
+    pub fn get_trim_materials(&self) -> impl Iterator<Item = &String> + Clone {
+        self.trim_palettes
+            .keys()
+            .filter(|name| *name != TRIM_PALETTE_KEY && !name.ends_with("_darker"))
+    }
 
-        Ok(manager)
+    /// Whether any default skins of players were found in the asset packs, without which no skin is known to be one.
+    #[must_use]
+    pub const fn has_default_skins(&self) -> bool {
+        !self.default_skins.is_empty()
     }
 
-    fn get_material_file_path(&self, material: VanillaMinecraftArmorMaterial) -> PathBuf {
-        self.material_location.join(material.to_string())
+    /// Whether the skin is one of the default skins of players found in the asset packs.
+    #[must_use]
+    pub fn is_default_skin(&self, skin: &RgbaImage) -> bool {
+        self.default_skins.iter().any(|default_skin| default_skin == skin)
     }
 
-    fn get_trim_file_path(&self, trim: VanillaMinecraftArmorTrim) -> PathBuf {
-        self.trims_location.join(trim.to_string())
+    /// The font name tags are written with, which is the one of the asset packs if they have one.
+    #[must_use]
+    pub fn get_nametag_font(&self) -> &NametagFont {
+        self.nametag_font.as_ref().unwrap_or_else(|| NametagFont::bundled())
     }
 
-    async fn init(&self) -> Result<()> {
-        self.download_materials().await?;
-        self.download_trims().await?;
+    /// The vanilla elytra texture, worn by players that don't have a cape.
+    pub fn get_elytra_texture(&self) -> Result<RgbaImage> {
+        self.get_first_texture(&ELYTRA_TEXTURE_PATHS)
+    }
 
-        Ok(())
+    /// The enchantment glint laid over enchanted armor.
+    pub fn get_glint_texture(&self) -> Result<RgbaImage> {
+        self.get_first_texture(&GLINT_TEXTURE_PATHS)
     }
 
-    async fn download_trims(&self) -> Result<()> {
-        for trim in VanillaMinecraftArmorTrim::iter() {
-            let trim_path = self.get_trim_file_path(trim);
+    /// The first texture of the given ones found in the asset packs, since some of them moved between game versions.
+    fn get_first_texture(&self, paths: &[&str]) -> Result<RgbaImage> {
+        let path = paths
+            .iter()
+            .find(|path| self.assets.contains(path))
+            .unwrap_or(&paths[0]);
 
-            fs::create_dir_all(&trim_path).await.explain(format!(
-                "Unable to create armor cache folder for trim {trim}"
-            ))?;
+        Ok(self.assets.get_texture(path)?)
+    }
 
-            let layers = trim.get_layer_names();
+    /// The texture of a mob that can be rendered instead of a player.
+    pub fn get_mob_texture(&self, mob: HumanoidMob) -> Result<RgbaImage> {
+        Ok(self.assets.get_texture(mob.get_texture_path())?)
+    }
 
-            for layer in layers {
-                let layer_path = trim_path.join(&layer);
+    /// The texture of block-style headwear worn in the helmet slot.
+    ///
+    /// Player heads use the skin of the player they're the head of, so there's none for them in the asset packs.
+    pub fn get_headwear_texture(&self, headwear: Headwear) -> Result<RgbaImage> {
+        if let Some(path) = headwear.get_texture_path() {
+            return Ok(self.assets.get_texture(path)?);
+        }
 
-                if !layer_path.exists() {
-                    let url = format!(
-                        "https://raw.githubusercontent.com/NickAcPT/minecraft-assets/24w11a/assets/minecraft/textures/trims/models/armor/{name}",
-                        name = &layer
-                    );
+        if headwear != Headwear::CarvedPumpkin {
+            return Err(ArmorManagerError::MissingArmorTextureError(headwear.to_string()).into());
+        }
 
-                    let bytes = self
-                        .client
-                        .do_request(&url, Method::GET, &Span::current(), || None)
-                        .await?;
+        let get_face = |path| -> ArmorManagerResult<RgbaImage> {
+            let face = self.assets.get_texture(path)?;
 
-                    fs::write(&layer_path, bytes)
-                        .await
-                        .explain(format!("Unable to write armor cache file for trim {layer}"))?;
-                }
+            if face.dimensions() == (BLOCK_FACE_SIZE, BLOCK_FACE_SIZE) {
+                Ok(face)
+            } else {
+                Ok(imageops::resize(&face, BLOCK_FACE_SIZE, BLOCK_FACE_SIZE, imageops::FilterType::Nearest))
             }
+        };
+
+        let (front, side, top) = CARVED_PUMPKIN_TEXTURE_PATHS;
+        let (front, side, top) = (get_face(front)?, get_face(side)?, get_face(top)?);
+
+        // The faces are laid out like the box UVs of a block, with the carved face on the front
+        let face_size = i64::from(BLOCK_FACE_SIZE);
+        let mut texture = RgbaImage::new(BLOCK_FACE_SIZE * 4, BLOCK_FACE_SIZE * 2);
+
+        for (face, x, y) in [
+            (&top, 1, 0),
+            (&top, 2, 0),
+            (&side, 0, 1),
+            (&front, 1, 1),
+            (&side, 2, 1),
+            (&side, 3, 1),
+        ] {
+            imageops::replace(&mut texture, face, x * face_size, y * face_size);
         }
 
-        Ok(())
+        Ok(texture)
     }
-    async fn download_materials(&self) -> Result<()> {
-        for material in VanillaMinecraftArmorMaterial::iter() {
-            let material_path = self.get_material_file_path(material);
-            let material_name = material.to_string().to_lowercase();
-
-            fs::create_dir_all(&material_path).await.explain(format!(
-                "Unable to create armor cache folder for material {material}"
-            ))?;
-
-            let layers = material.get_layer_names();
-
-            for layer in layers {
-                let file_name = format!("{material_name}_layer_{layer}.png");
-                let layer_path = material_path.join(&file_name);
-
-                if !layer_path.exists() {
-                    let url = format!(
-                        "https://raw.githubusercontent.com/InventivetalentDev/minecraft-assets/1.20.1/assets/minecraft/textures/models/armor/{name}",
-                        name = &file_name
-                    );
-
-                    let bytes = self
-                        .client
-                        .do_request(&url, Method::GET, &Span::current(), || None)
-                        .await?;
-
-                    fs::write(&layer_path, bytes).await.explain(format!(
-                        "Unable to write armor cache file for material {material}"
-                    ))?;
-                }
-            }
-        }
 
-        Ok(())
+    /// The model of an item (or a block) held in one of the hands, along with its texture.
+    pub fn get_held_item(&self, item: &str) -> Result<HeldItem> {
+        let (model, texture) = load_item_model(&self.assets, item)?;
+
+        Ok(HeldItem {
+            name: item.to_owned(),
+            model: Arc::new(model),
+            texture: Arc::new(texture),
+        })
     }
 
-    fn get_image_path(
+    fn load_layer(
         &self,
         applicable: &VanillaArmorApplicable,
         slot: PlayerArmorSlot,
-    ) -> PathBuf {
-        let root = match applicable {
-            VanillaArmorApplicable::Armor(data) => self.get_material_file_path(*data),
-            VanillaArmorApplicable::Trim(_, data) => self.get_trim_file_path(data.trim),
+    ) -> ArmorManagerResult<RgbaImage> {
+        let (layers, layouts, name) = match applicable {
+            VanillaArmorApplicable::Armor(material) => (&self.materials, MATERIAL_LAYOUTS, *material),
+            VanillaArmorApplicable::Uploaded(layer) => return Ok(layer.clone()),
+            VanillaArmorApplicable::Trim(_, data) => (&self.trims, TRIM_LAYOUTS, data.trim.as_str()),
+        };
+
+        let name = resolve_partial_name(layers.keys(), name)?;
+        let layer = usize::from(slot.is_leggings());
+
+        let path = layers[name][layer].clone().unwrap_or_else(|| {
+            // Let the error mention the texture we were looking for
+            let (folder, suffix) = layouts[0][layer];
+            format!("{folder}{name}{suffix}")
+        });
+
+        self.assets.get_texture(&path)
+    }
+
+    /// The colors the trim palette key is replaced with for a trim material.
+    ///
+    /// Trims of the same material as the armor they're on use a darker palette, if the asset packs have one.
+    /// Uploaded armor isn't made of any material, so its trims always use the regular palette.
+    fn get_trim_palette(&self, armor_material: &str, trim_material: &str) -> ArmorManagerResult<Vec<[u8; 3]>> {
+        let armor_material = if armor_material == VanillaMinecraftArmorMaterialData::UPLOADED_MATERIAL {
+            None
+        } else {
+            Some(resolve_partial_name(self.materials.keys(), armor_material)?)
+        };
+        let trim_material = resolve_partial_name(self.get_trim_materials(), trim_material)?;
+
+        let darker_palette = format!("{trim_material}_darker");
+        let palette = if armor_material == Some(trim_material) && self.trim_palettes.contains_key(&darker_palette) {
+            &darker_palette
+        } else {
+            trim_material
         };
 
-        root.join(applicable.get_layer_name(slot))
+        self.load_palette(palette)
     }
 
-    pub async fn create_armor_texture(
+    fn load_palette(&self, name: &str) -> ArmorManagerResult<Vec<[u8; 3]>> {
+        let path = self
+            .trim_palettes
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| format!("{TRIM_PALETTES_FOLDER}{name}.png"));
+
+        let image = self.assets.get_texture(&path)?;
+
+        Ok((0..image.width())
+            .map(|x| {
+                let pixel = image.get_pixel(x, 0);
+                [pixel[0], pixel[1], pixel[2]]
+            })
+            .collect())
+    }
+
+    fn apply_modifications_if_needed(
+        &self,
+        applicable: &VanillaArmorApplicable,
+        image: &mut RgbaImage,
+    ) -> ArmorManagerResult<()> {
+        if let VanillaArmorApplicable::Trim(armor_material, VanillaMinecraftArmorTrimData { material, .. }) = applicable {
+            let palette = self.get_trim_palette(armor_material, material)?;
+            let trim_palette = self.load_palette(TRIM_PALETTE_KEY)?;
+
+            for pixel in image.pixels_mut() {
+                if pixel[3] == 0 {
+                    continue;
+                }
+
+                let index = trim_palette
+                    .iter()
+                    .position(|color| *color == [pixel[0], pixel[1], pixel[2]]);
+
+                if let Some(actual_color) = index.and_then(|index| palette.get(index)) {
+                    pixel[0] = actual_color[0];
+                    pixel[1] = actual_color[1];
+                    pixel[2] = actual_color[2];
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create the textures of the armor worn in the slots, as the main layer and the leggings layer.
+    ///
+    /// Slots made of the uploaded material are textured with the `uploaded` layers, dyed with the dye of the slot, and
+    /// with their trims applied on top.
+    pub fn create_armor_texture(
         &self,
         slots: &PlayerArmorSlots<VanillaMinecraftArmorMaterialData>,
+        uploaded: Option<&UploadedArmorLayers>,
     ) -> Result<(RgbaImage, Option<RgbaImage>)> {
+        let nothing_uploaded = UploadedArmorLayers::default();
+        let uploaded = uploaded.unwrap_or(&nothing_uploaded);
+
         let mut output_armor_image = image::RgbaImage::new(64, 64);
         let mut output_armor_two_image = image::RgbaImage::new(64, 64);
 
@@ -204,44 +395,42 @@ impl VanillaMinecraftArmorManager {
                 &mut output_armor_image
             };
 
-            let mut to_apply = vec![VanillaArmorApplicable::Armor(data.material)];
+            let armor = if data.is_uploaded() {
+                VanillaArmorApplicable::Uploaded(uploaded.get_layer(slot, data.dye)?)
+            } else {
+                VanillaArmorApplicable::Armor(&data.material)
+            };
+
+            let mut to_apply = vec![armor];
 
             to_apply.append(
                 &mut data
                     .trims
                     .iter()
-                    .map(|trim| VanillaArmorApplicable::Trim(data.material, trim))
+                    .map(|trim| VanillaArmorApplicable::Trim(&data.material, trim))
                     .collect(),
             );
 
             for aplicable in to_apply {
-                self.apply_parts(&aplicable, slot, output_image).await?;
+                self.apply_parts(&aplicable, slot, output_image)?;
             }
         }
 
         Ok((
             output_armor_image,
-            Some(output_armor_two_image).filter(|_| slots.leggings.is_some()),
+            slots.leggings.is_some().then_some(output_armor_two_image),
         ))
     }
 
-    async fn apply_parts(
+    fn apply_parts(
         &self,
         applicable: &VanillaArmorApplicable<'_>,
         slot: PlayerArmorSlot,
         output_image: &mut RgbaImage,
     ) -> ArmorManagerResult<()> {
-        let material_path = self.get_image_path(applicable, slot);
-
-        let bytes = fs::read(&material_path)
-            .await
-            .map_err(|_| ArmorManagerError::MissingArmorTextureError(material_path.clone()))?;
+        let mut image = self.load_layer(applicable, slot)?;
 
-        let mut image = image::load_from_memory(&bytes)
-            .map_err(|e| ArmorManagerError::ArmorTextureLoadError(material_path.clone(), e))?
-            .into_rgba8();
-
-        applicable.apply_modifications_if_needed(&mut image);
+        self.apply_modifications_if_needed(applicable, &mut image)?;
 
         let image = upgrade_skin_if_needed(image);
 
@@ -266,3 +455,145 @@ impl VanillaMinecraftArmorManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Cursor};
+
+    use image::{ImageFormat, Rgba, RgbaImage};
+    use nmsr_rendering::high_level::model::PlayerArmorSlots;
+
+    use super::VanillaMinecraftArmorManager;
+    use crate::{
+        model::armor::{UploadedArmorLayers, VanillaMinecraftArmorMaterialData},
+        utils::config::AssetsConfiguration,
+    };
+
+    fn palette(colors: impl Fn(u8) -> [u8; 3]) -> RgbaImage {
+        RgbaImage::from_fn(8, 1, |x, _| {
+            let [r, g, b] = colors(x as u8);
+            Rgba([r, g, b, 255])
+        })
+    }
+
+    #[test]
+    fn test_without_asset_packs() {
+        let manager = VanillaMinecraftArmorManager::new(&AssetsConfiguration::default()).expect("No packs should load");
+
+        assert!(!manager.has_default_skins());
+        assert!(manager.get_materials().next().is_none());
+    }
+
+    #[test]
+    fn test_trims_from_resource_pack() {
+        let pack = std::env::temp_dir().join(format!("nmsr-armor-test-{}", std::process::id()));
+        let textures = pack.join("assets/minecraft/textures");
+
+        let armor = RgbaImage::from_pixel(64, 32, Rgba([0, 0, 255, 255]));
+        let trim = RgbaImage::from_pixel(64, 32, Rgba([0x20, 0x20, 0x20, 255]));
+        let skin = RgbaImage::from_pixel(64, 64, Rgba([0, 255, 0, 255]));
+
+        let files = [
+            ("models/armor/diamond_layer_1.png", &armor),
+            ("models/armor/diamond_layer_2.png", &armor),
+            ("trims/models/armor/sentry.png", &trim),
+            ("trims/models/armor/sentry_leggings.png", &trim),
+            ("trims/color_palettes/trim_palette.png", &palette(|i| [i * 0x20; 3])),
+            ("trims/color_palettes/gold.png", &palette(|i| [i, 100, 0])),
+            ("trims/color_palettes/gold_darker.png", &palette(|i| [i, 50, 0])),
+            ("entity/player/slim/sunny.png", &skin),
+        ];
+
+        for (path, image) in files {
+            let path = textures.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            image.save(path).unwrap();
+        }
+
+        let manager = VanillaMinecraftArmorManager::new(&AssetsConfiguration {
+            packs: vec![pack.clone()],
+        })
+        .expect("Pack should load");
+
+        assert_eq!(manager.get_materials().collect::<Vec<_>>(), ["diamond"]);
+        assert_eq!(manager.get_trims().collect::<Vec<_>>(), ["sentry"]);
+        assert_eq!(manager.get_trim_materials().collect::<Vec<_>>(), ["gold"]);
+        assert!(manager.has_default_skins());
+        assert!(manager.is_default_skin(&skin));
+        assert!(!manager.is_default_skin(&armor));
+
+        let slots = PlayerArmorSlots {
+            chestplate: Some(VanillaMinecraftArmorMaterialData::new("dia").with_trim("sen", "gold")),
+            ..Default::default()
+        };
+        let (layer, leggings) = manager.create_armor_texture(&slots, None).expect("Armor should render");
+
+        fs::remove_dir_all(pack).unwrap();
+
+        assert!(leggings.is_none());
+        // The front of the body, painted with the second color of the gold palette
+        assert_eq!(layer.get_pixel(22, 24), &Rgba([1, 100, 0, 255]));
+
+        let missing = PlayerArmorSlots {
+            helmet: Some(VanillaMinecraftArmorMaterialData::new("netherite")),
+            ..Default::default()
+        };
+        assert!(manager.create_armor_texture(&missing, None).is_err());
+
+        let mut layer = Vec::new();
+        RgbaImage::from_pixel(64, 32, Rgba([255, 0, 0, 255]))
+            .write_to(&mut Cursor::new(&mut layer), ImageFormat::Png)
+            .unwrap();
+        let uploaded = UploadedArmorLayers::load([Some(layer), None, None, None])
+            .expect("Layer should load")
+            .expect("Layer was uploaded");
+
+        let slots = PlayerArmorSlots {
+            helmet: Some(VanillaMinecraftArmorMaterialData::new("uploaded")),
+            chestplate: Some(VanillaMinecraftArmorMaterialData::new("uploaded").with_trim("sen", "gold")),
+            ..Default::default()
+        };
+        let (layer, _) = manager
+            .create_armor_texture(&slots, Some(&uploaded))
+            .expect("Uploaded armor should render");
+
+        assert_eq!(layer.get_pixel(8, 8), &Rgba([255, 0, 0, 255]));
+        // Uploaded armor has no material of its own, so its trims are painted with the regular palette
+        assert_eq!(layer.get_pixel(22, 24), &Rgba([1, 100, 0, 255]));
+
+        let leggings = PlayerArmorSlots {
+            leggings: Some(VanillaMinecraftArmorMaterialData::new("uploaded")),
+            ..Default::default()
+        };
+        assert!(manager.create_armor_texture(&leggings, Some(&uploaded)).is_err());
+
+        let mut overlay = RgbaImage::new(64, 32);
+        overlay.put_pixel(8, 8, Rgba([0, 0, 255, 255]));
+        let [layer, overlay] = [
+            RgbaImage::from_pixel(64, 32, Rgba([200, 100, 255, 255])),
+            overlay,
+        ]
+        .map(|image| {
+            let mut bytes = Vec::new();
+            image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png).unwrap();
+            bytes
+        });
+        let uploaded = UploadedArmorLayers::load([Some(layer), None, Some(overlay), None])
+            .expect("Layer and overlay should load")
+            .expect("Layer was uploaded");
+
+        let slots = PlayerArmorSlots {
+            helmet: Some(VanillaMinecraftArmorMaterialData::new("uploaded").dyed([255, 0, 128])),
+            chestplate: Some(VanillaMinecraftArmorMaterialData::new("uploaded")),
+            ..Default::default()
+        };
+        let (layer, _) = manager
+            .create_armor_texture(&slots, Some(&uploaded))
+            .expect("Dyed armor should render");
+
+        // The dye tints the helmet, but not the overlay drawn over it nor the chestplate sharing its layer
+        assert_eq!(layer.get_pixel(9, 8), &Rgba([200, 0, 128, 255]));
+        assert_eq!(layer.get_pixel(8, 8), &Rgba([0, 0, 255, 255]));
+        assert_eq!(layer.get_pixel(22, 24), &Rgba([200, 100, 255, 255]));
+    }
+}
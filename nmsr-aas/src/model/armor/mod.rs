@@ -1,157 +1,47 @@
+pub mod assets;
+pub mod item;
 pub mod manager;
 
-use std::collections::VecDeque;
+use std::str::FromStr;
 
+use image::{imageops, RgbaImage};
 use nmsr_rendering::high_level::{
-    model::{ArmorMaterial, PlayerArmorSlot},
+    model::{headwear::Headwear, ArmorMaterial, PlayerArmorSlot},
     types::PlayerPartTextureType,
 };
-use strum::{Display, IntoEnumIterator, IntoStaticStr};
-
-use crate::error::{ArmorManagerError, ArmorManagerResult};
-
-#[derive(
-    Debug, Display, Clone, Copy, PartialEq, Eq, IntoStaticStr, strum::EnumString, strum::EnumIter,
-)]
-pub enum VanillaMinecraftArmorMaterial {
-    Chainmail,
-    Diamond,
-    Gold,
-    Iron,
-    Leather(u64),
-    Netherite,
-    Turtle,
-}
-
-impl VanillaMinecraftArmorMaterial {
-    const fn layer_count() -> usize {
-        2
-    }
-
-    #[must_use]
-    pub const fn has_overlay(&self) -> bool {
-        matches!(self, Self::Leather(_))
-    }
-
-    #[must_use]
-    pub fn get_layer_name(&self, id: u32, is_overlay: bool) -> String {
-        let name = self.to_string().to_lowercase();
-
-        if is_overlay {
-            format!("{name}_layer_{id}_overlay.png")
-        } else {
-            format!("{name}_layer_{id}.png")
-        }
-    }
-
-    #[must_use]
-    pub fn get_layer_names(&self) -> Vec<String> {
-        let result = (0..Self::layer_count()).map(|i| (i + 1).to_string());
-
-        if self.has_overlay() {
-            result
-                .flat_map(|layer| vec![layer.clone(), format!("{}_overlay", &layer)])
-                .collect()
-        } else {
-            result.collect()
-        }
-    }
-}
 
-#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, strum::IntoStaticStr, strum::EnumIter)]
-pub enum VanillaMinecraftArmorTrim {
-    Bolt,
-    Coast,
-    Dune,
-    Eye,
-    Flow,
-    Host,
-    Raiser,
-    Rib,
-    Sentry,
-    Shaper,
-    Silence,
-    Snout,
-    Spire,
-    Tide,
-    Vex,
-    Ward,
-    Wayfinder,
-    Wild,
-}
-
-impl VanillaMinecraftArmorTrim {
-    #[must_use]
-    pub fn get_layer_names(&self) -> Vec<String> {
-        let name = self.to_string().to_lowercase();
-
-        vec![format!("{}.png", name), format!("{}_leggings.png", name)]
-    }
-
-    #[must_use]
-    pub fn get_layer_name(&self, is_leggings: bool) -> String {
-        let name = self.to_string().to_lowercase();
-
-        if is_leggings {
-            format!("{name}_leggings.png")
-        } else {
-            format!("{name}.png")
-        }
-    }
-}
-
-#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::IntoStaticStr)]
-pub enum VanillaMinecraftArmorTrimMaterial {
-    Amethyst,
-    Copper,
-    Diamond,
-    Emerald,
-    Gold,
-    Iron,
-    Lapis,
-    Netherite,
-    Quartz,
-    Redstone,
-}
-
-#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
-pub enum VanillaMinecraftArmorTrimPalette {
-    Amethyst,
-    Copper,
-    Diamond,
-    DiamondDarker,
-    Emerald,
-    Gold,
-    GoldDarker,
-    Iron,
-    IronDarker,
-    Lapis,
-    Netherite,
-    NetheriteDarker,
-    Quartz,
-    Redstone,
-}
+use crate::{
+    error::{ArmorManagerError, ArmorManagerResult},
+    model::request::entry::RenderRequestEntry,
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A trim applied on top of a piece of armor.
+///
+/// The names are the ones of the textures in the configured asset packs (or a prefix of them),
+/// and are only resolved by the [`manager::VanillaMinecraftArmorManager`] when rendering.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VanillaMinecraftArmorTrimData {
-    trim: VanillaMinecraftArmorTrim,
-    material: VanillaMinecraftArmorTrimMaterial,
+    pub trim: String,
+    pub material: String,
 }
 
 impl VanillaMinecraftArmorTrimData {
     #[must_use]
-    pub const fn new(
-        trim: VanillaMinecraftArmorTrim,
-        material: VanillaMinecraftArmorTrimMaterial,
-    ) -> Self {
-        Self { trim, material }
+    pub fn new(trim: impl Into<String>, material: impl Into<String>) -> Self {
+        Self {
+            trim: trim.into(),
+            material: material.into(),
+        }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VanillaMinecraftArmorMaterialData {
-    pub material: VanillaMinecraftArmorMaterial,
+    pub material: String,
     pub trims: Vec<VanillaMinecraftArmorTrimData>,
+    pub enchanted: bool,
+    /// The color that the base layer of uploaded armor is dyed with, like leather armor in the game.
+    pub dye: Option<[u8; 3]>,
 }
 
 impl VanillaMinecraftArmorMaterialData {
@@ -165,74 +55,128 @@ impl VanillaMinecraftArmorMaterialData {
         size: (64, 64),
     };
 
+    /// The flag that lays the enchantment glint over a piece of armor, like `diamond:enchanted`.
+    const ENCHANTED_FLAG: &'static str = "enchanted";
+
+    /// The flag that dyes uploaded armor with a hex color, like `uploaded:dye=a06540`.
+    const DYE_FLAG: &'static str = "dye=";
+
+    /// The material of the armor textures uploaded with the request (see [`UploadedArmorLayers`]), like `uploaded_sentry_gold`.
+    pub const UPLOADED_MATERIAL: &'static str = "uploaded";
+
     #[must_use]
-    pub const fn new(material: VanillaMinecraftArmorMaterial) -> Self {
+    pub fn new(material: impl Into<String>) -> Self {
         Self {
-            material,
+            material: material.into(),
             trims: Vec::new(),
+            enchanted: false,
+            dye: None,
         }
     }
 
     #[must_use]
-    pub fn with_trim(
-        mut self,
-        trim: VanillaMinecraftArmorTrim,
-        material: VanillaMinecraftArmorTrimMaterial,
-    ) -> Self {
+    pub fn with_trim(mut self, trim: impl Into<String>, material: impl Into<String>) -> Self {
         self.trims
             .push(VanillaMinecraftArmorTrimData::new(trim, material));
 
         self
     }
+
+    #[must_use]
+    pub const fn enchanted(mut self) -> Self {
+        self.enchanted = true;
+
+        self
+    }
+
+    #[must_use]
+    pub const fn dyed(mut self, dye: [u8; 3]) -> Self {
+        self.dye = Some(dye);
+
+        self
+    }
+
+    #[must_use]
+    pub fn is_uploaded(&self) -> bool {
+        self.material == Self::UPLOADED_MATERIAL
+    }
 }
 
 impl TryFrom<String> for VanillaMinecraftArmorMaterialData {
     type Error = ArmorManagerError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        let mut split_values: VecDeque<_> = value.split('_').collect();
-        let material: VanillaMinecraftArmorMaterial = if split_values.is_empty() {
-            return Err(ArmorManagerError::EmptyArmorSlotError);
-        } else {
-            partial_match(split_values.pop_front().unwrap_or_default())?
-        };
+        let value = value.to_lowercase();
+        let mut flags = value.split(':');
+        let mut split_values = flags.next().unwrap_or_default().split('_').map(str::to_owned);
 
-        let trims = if split_values.is_empty() {
-            Vec::new()
-        } else if split_values.len() % 2 != 0 {
-            return Err(ArmorManagerError::InvalidTrimCountError(split_values.len()));
-        } else {
-            let values = split_values.make_contiguous();
+        let material = split_values
+            .next()
+            .filter(|material| !material.is_empty())
+            .ok_or(ArmorManagerError::EmptyArmorSlotError)?;
 
-            values
-                .chunks_exact(2)
-                .map(|chunk| {
-                    let trim: VanillaMinecraftArmorTrim = partial_match(chunk[0])?;
-                    let material: VanillaMinecraftArmorTrimMaterial = partial_match(chunk[1])?;
+        let trims: Vec<_> = split_values.collect();
+
+        if trims.len() % 2 != 0 {
+            return Err(ArmorManagerError::InvalidTrimCountError(trims.len()));
+        }
+
+        let mut enchanted = false;
+        let mut dye = None;
 
-                    ArmorManagerResult::Ok(VanillaMinecraftArmorTrimData::new(trim, material))
-                })
-                .filter_map(std::result::Result::ok)
-                .collect::<Vec<_>>()
-        };
+        for flag in flags {
+            if flag == Self::ENCHANTED_FLAG {
+                enchanted = true;
+            } else if let Some(color) = flag.strip_prefix(Self::DYE_FLAG) {
+                dye = Some(parse_dye(color)?);
+            } else {
+                return Err(ArmorManagerError::UnknownArmorFlagError(flag.to_string()));
+            }
+        }
 
-        Ok(Self { material, trims })
+        if let Some(dye) = dye.filter(|_| material != Self::UPLOADED_MATERIAL) {
+            return Err(ArmorManagerError::InvalidArmorDyeError(
+                format_dye(dye),
+                format!("only {} armor can be dyed", Self::UPLOADED_MATERIAL),
+            ));
+        }
+
+        Ok(Self {
+            material,
+            trims: trims
+                .chunks_exact(2)
+                .map(|chunk| VanillaMinecraftArmorTrimData::new(&chunk[0], &chunk[1]))
+                .collect(),
+            enchanted,
+            dye,
+        })
     }
 }
 
-fn partial_match<E>(value: &str) -> ArmorManagerResult<E>
-where
-    E: IntoEnumIterator + ToString,
-{
-    E::iter()
-        .find(|x| {
-            x.to_string()
-                .to_lowercase()
-                .starts_with(&value.to_lowercase())
-        })
-        .ok_or(ArmorManagerError::UnknownPartialArmorMaterialName(
-            value.to_string(),
-        ))
+impl From<VanillaMinecraftArmorMaterialData> for String {
+    fn from(value: VanillaMinecraftArmorMaterialData) -> Self {
+        let mut result = value.material;
+
+        for trim in value.trims {
+            result.push('_');
+            result.push_str(&trim.trim);
+            result.push('_');
+            result.push_str(&trim.material);
+        }
+
+        if value.enchanted {
+            result.push(':');
+            result.push_str(VanillaMinecraftArmorMaterialData::ENCHANTED_FLAG);
+        }
+
+        if let Some(dye) = value.dye {
+            result.push(':');
+            result.push_str(VanillaMinecraftArmorMaterialData::DYE_FLAG);
+            result.push_str(&format_dye(dye));
+        }
+
+        result
+    }
 }
 
 impl ArmorMaterial for VanillaMinecraftArmorMaterialData {
@@ -243,98 +187,289 @@ impl ArmorMaterial for VanillaMinecraftArmorMaterialData {
             Self::ARMOR_TEXTURE_ONE
         })
     }
+
+    fn is_enchanted(&self) -> bool {
+        self.enchanted
+    }
+}
+
+/// Armor textures uploaded along with the skin of a `POST` request, for armor that isn't in the asset packs.
+///
+/// They're laid out like the armor layers of the game, and worn by the slots made of the
+/// [`VanillaMinecraftArmorMaterialData::UPLOADED_MATERIAL`] material. Like the layers of a material from the asset
+/// packs, they're shared by those slots: the main layer textures the helmet, the chestplate and the boots, and the
+/// leggings layer textures the leggings. Each slot still has its own trims, enchantment and dye.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct UploadedArmorLayers {
+    /// The main layer and the leggings layer, each with the overlay of dyeable armor that's drawn over it.
+    layers: [Option<(RgbaImage, Option<RgbaImage>)>; 2],
 }
 
-impl VanillaMinecraftArmorTrimPalette {
+impl UploadedArmorLayers {
+    /// The names of the uploaded textures: the main layer, the leggings layer, and the overlays of dyeable armor for each of them.
+    pub const TEXTURE_NAMES: [&'static str; 4] = [
+        "armor_layer_1",
+        "armor_layer_2",
+        "armor_layer_1_overlay",
+        "armor_layer_2_overlay",
+    ];
+
+    /// Load the uploaded textures, given in the order of [`Self::TEXTURE_NAMES`].
+    ///
+    /// Returns `None` if nothing was uploaded.
+    pub fn load(textures: [Option<Vec<u8>>; 4]) -> ArmorManagerResult<Option<Self>> {
+        let [layer_1, layer_2, overlay_1, overlay_2] = textures.map(|texture| texture.map(|bytes| image::load_from_memory(&bytes)));
+
+        let mut layers = [None, None];
+
+        for (index, (layer, overlay)) in [(layer_1, overlay_1), (layer_2, overlay_2)].into_iter().enumerate() {
+            let (layer_name, overlay_name) = (Self::TEXTURE_NAMES[index], Self::TEXTURE_NAMES[index + 2]);
+
+            let Some(layer) = layer else {
+                if overlay.is_some() {
+                    return Err(invalid_upload(overlay_name, &format!("overlays need {layer_name} to be uploaded too")));
+                }
+
+                continue;
+            };
+
+            let layer = layer.map_err(|e| invalid_upload(layer_name, &e))?.into_rgba8();
+
+            if !matches!(layer.dimensions(), (64, 32 | 64)) {
+                return Err(invalid_upload(layer_name, &"armor layers must be 64x32 or 64x64"));
+            }
+
+            let overlay = overlay
+                .map(|overlay| overlay.map_err(|e| invalid_upload(overlay_name, &e)))
+                .transpose()?
+                .map(image::DynamicImage::into_rgba8);
+
+            if overlay.as_ref().is_some_and(|overlay| overlay.dimensions() != layer.dimensions()) {
+                return Err(invalid_upload(overlay_name, &format!("overlays must be the size of {layer_name}")));
+            }
+
+            layers[index] = Some((layer, overlay));
+        }
+
+        Ok(Some(Self { layers }).filter(|uploaded| uploaded.layers.iter().any(Option::is_some)))
+    }
+
+    /// Whether a layer was uploaded for the armor worn in the slot.
     #[must_use]
-    #[rustfmt::skip]
-    const fn get_trim_palette() -> [[u8; 3]; 8] {
-        [
-            [0x00, 0x00, 0x00], [0x20, 0x20, 0x20], [0x40, 0x40, 0x40], [0x60, 0x60, 0x60], [0x80, 0x80, 0x80], [0xA0, 0xA0, 0xA0], [0xC0, 0xC0, 0xC0], [0xE0, 0xE0, 0xE0]
-        ]
+    pub fn covers(&self, slot: PlayerArmorSlot) -> bool {
+        self.layers[usize::from(slot.is_leggings())].is_some()
+    }
+
+    /// The texture of the armor worn in the slot, made of the uploaded layer dyed with the given color, and its overlay
+    /// drawn over it.
+    pub fn get_layer(&self, slot: PlayerArmorSlot, dye: Option<[u8; 3]>) -> ArmorManagerResult<RgbaImage> {
+        let index = usize::from(slot.is_leggings());
+
+        let (layer, overlay) = self.layers[index]
+            .as_ref()
+            .ok_or_else(|| invalid_upload(Self::TEXTURE_NAMES[index], &format!("it's needed for the uploaded {}", slot.to_string().to_lowercase())))?;
+
+        let mut layer = layer.clone();
+
+        if let Some(dye) = dye {
+            for pixel in layer.pixels_mut() {
+                for (channel, dye) in pixel.0.iter_mut().zip(dye) {
+                    *channel = (u16::from(*channel) * u16::from(dye) / 255) as u8;
+                }
+            }
+        }
+
+        if let Some(overlay) = overlay {
+            imageops::overlay(&mut layer, overlay, 0, 0);
+        }
+
+        Ok(layer)
+    }
+}
+
+/// Parse a dye given as a hex color, like `a06540`.
+fn parse_dye(color: &str) -> ArmorManagerResult<[u8; 3]> {
+    let invalid = || ArmorManagerError::InvalidArmorDyeError(color.to_owned(), "dyes are hex colors, like a06540".to_owned());
+
+    if color.len() != 6 || !color.is_ascii() {
+        return Err(invalid());
+    }
+
+    let mut dye = [0; 3];
+
+    for (channel, index) in dye.iter_mut().zip((0..6).step_by(2)) {
+        *channel = u8::from_str_radix(&color[index..index + 2], 16).map_err(|_| invalid())?;
     }
 
+    Ok(dye)
+}
+
+fn format_dye([red, green, blue]: [u8; 3]) -> String {
+    format!("{red:02x}{green:02x}{blue:02x}")
+}
+
+fn invalid_upload(texture: &str, error: &impl ToString) -> ArmorManagerError {
+    ArmorManagerError::InvalidArmorUploadError(texture.to_owned(), error.to_string())
+}
+
+/// What's worn in the helmet slot, which can also take block-style headwear instead of a helmet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VanillaMinecraftHelmetData {
+    Armor(VanillaMinecraftArmorMaterialData),
+    /// A carved pumpkin or a mob skull, like `creeper_head`.
+    Headwear(Headwear),
+    /// The head of another player, like `head:<uuid>`, whose skin is resolved along with the entry.
+    PlayerHead(RenderRequestEntry),
+}
+
+impl VanillaMinecraftHelmetData {
     #[must_use]
-    #[rustfmt::skip]
-    pub const fn get_palette_colors(&self) -> [[u8; 3]; 8] {
-        // Generated using the nmsr-rendering-palette-extractor crate (in utils folder)
-        // and is based on the vanilla textures in `assets\minecraft\textures\trims\`
+    pub const fn armor(&self) -> Option<&VanillaMinecraftArmorMaterialData> {
         match self {
-            Self::Amethyst => [[0x17, 0x06, 0x3B], [0x24, 0x0C, 0x53], [0x36, 0x1C, 0x6A], [0x42, 0x27, 0x76], [0x52, 0x36, 0x87], [0x6C, 0x49, 0xAA], [0x9A, 0x5C, 0xC6], [0xC9, 0x8F, 0xF3]],
-            Self::Copper => [[0x3D, 0x18, 0x0B], [0x4C, 0x20, 0x10], [0x5F, 0x2B, 0x18], [0x6D, 0x34, 0x20], [0x79, 0x3C, 0x28], [0x9A, 0x47, 0x2C], [0xB4, 0x68, 0x4D], [0xE3, 0x82, 0x6C]],
-            Self::Diamond => [[0x01, 0x3C, 0x47], [0x04, 0x51, 0x5F], [0x07, 0x65, 0x78], [0x0C, 0x78, 0x8D], [0x1D, 0x96, 0x9A], [0x2C, 0xBA, 0xA8], [0x6E, 0xEC, 0xD2], [0xCB, 0xFF, 0xF5]],
-            Self::DiamondDarker => [[0x02, 0x74, 0x72], [0x03, 0x46, 0x42], [0x04, 0x40, 0x3E], [0x04, 0x81, 0x85], [0x06, 0x5D, 0x5B], [0x09, 0x51, 0x48], [0x0A, 0x9C, 0xA1], [0x15, 0xB3, 0xA1]],
-            Self::Emerald => [[0x02, 0x3D, 0x0E], [0x03, 0x50, 0x13], [0x09, 0x63, 0x1B], [0x0E, 0x72, 0x22], [0x0E, 0xC7, 0x54], [0x10, 0x7B, 0x24], [0x11, 0xA0, 0x36], [0x82, 0xF6, 0xAD]],
-            Self::Gold => [[0x57, 0x23, 0x00], [0x71, 0x2D, 0x00], [0x80, 0x35, 0x03], [0xA0, 0x45, 0x0A], [0xB1, 0x67, 0x12], [0xDE, 0xB1, 0x2D], [0xEC, 0xD9, 0x3F], [0xFF, 0xFD, 0x90]],
-            Self::GoldDarker => [[0x3E, 0x1B, 0x03], [0x57, 0x23, 0x00], [0x71, 0x2D, 0x00], [0x80, 0x35, 0x03], [0x89, 0x47, 0x0C], [0xA3, 0x5F, 0x14], [0xBA, 0x83, 0x27], [0xC2, 0x9C, 0x2A]],
-            Self::Iron => [[0x46, 0x51, 0x51], [0x57, 0x63, 0x63], [0x65, 0x70, 0x70], [0x71, 0x7D, 0x7D], [0x7B, 0x89, 0x89], [0x9D, 0xAA, 0xAA], [0xBF, 0xC9, 0xC8], [0xC5, 0xD2, 0xD4]],
-            Self::IronDarker => [[0x1D, 0x28, 0x28], [0x2B, 0x34, 0x34], [0x31, 0x3B, 0x3B], [0x3D, 0x49, 0x49], [0x57, 0x63, 0x63], [0x6F, 0x76, 0x76], [0x8A, 0x92, 0x91], [0xA2, 0xB0, 0xB3]],
-            Self::Lapis => [[0x05, 0x16, 0x36], [0x09, 0x1E, 0x45], [0x0C, 0x28, 0x5A], [0x11, 0x2E, 0x63], [0x12, 0x33, 0x65], [0x1C, 0x4D, 0x9C], [0x21, 0x49, 0x7B], [0x41, 0x6E, 0x97]],
-            Self::Netherite => [[0x09, 0x07, 0x07], [0x10, 0x0C, 0x0C], [0x1A, 0x16, 0x16], [0x23, 0x1E, 0x1E], [0x2F, 0x27, 0x27], [0x31, 0x2E, 0x31], [0x44, 0x3A, 0x3B], [0x5A, 0x57, 0x5A]],
-            Self::NetheriteDarker => [[0x0B, 0x09, 0x09], [0x14, 0x0F, 0x0F], [0x1D, 0x13, 0x13], [0x1F, 0x17, 0x17], [0x24, 0x1A, 0x1A], [0x28, 0x1D, 0x1D], [0x28, 0x24, 0x25], [0x2E, 0x28, 0x29]],
-            Self::Quartz => [[0x2A, 0x28, 0x22], [0x45, 0x43, 0x3C], [0x65, 0x61, 0x56], [0x90, 0x8E, 0x80], [0xB6, 0xAD, 0x96], [0xE3, 0xDB, 0xC4], [0xF2, 0xEF, 0xED], [0xF6, 0xEA, 0xDF]],
-            Self::Redstone => [[0x1D, 0x05, 0x02], [0x36, 0x08, 0x03], [0x52, 0x0D, 0x06], [0x65, 0x0B, 0x01], [0x78, 0x11, 0x01], [0x97, 0x16, 0x07], [0xBD, 0x20, 0x08], [0xE6, 0x20, 0x08]],
+            Self::Armor(armor) => Some(armor),
+            _ => None,
         }
     }
 
-    const fn get_darker_palette(self) -> Option<Self> {
+    #[must_use]
+    pub const fn headwear(&self) -> Option<Headwear> {
         match self {
-            Self::Diamond => Some(Self::DiamondDarker),
-            Self::Gold => Some(Self::GoldDarker),
-            Self::Iron => Some(Self::IronDarker),
-            Self::Netherite => Some(Self::NetheriteDarker),
-            _ => None,
+            Self::Armor(_) => None,
+            Self::Headwear(headwear) => Some(*headwear),
+            Self::PlayerHead(_) => Some(Headwear::PlayerHead),
         }
     }
 }
 
-impl VanillaMinecraftArmorTrimMaterial {
-    fn get_palette_for_trim_armor_material(
-        self,
-        armor_material: VanillaMinecraftArmorMaterial,
-    ) -> VanillaMinecraftArmorTrimPalette {
-        if let (Ok(equivalent_armor_material), Some(darker_palette)) = (
-            Self::try_from(armor_material),
-            VanillaMinecraftArmorTrimPalette::from(self).get_darker_palette(),
-        ) {
-            if equivalent_armor_material == self {
-                return darker_palette;
+impl TryFrom<String> for VanillaMinecraftHelmetData {
+    type Error = ArmorManagerError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value = value.to_lowercase();
+
+        if let Some((name, entry)) = value.split_once(':') {
+            if Headwear::from_str(name) == Ok(Headwear::PlayerHead) {
+                let entry = RenderRequestEntry::try_from(entry.to_owned())
+                    .map_err(|e| ArmorManagerError::InvalidPlayerHeadError(e.to_string()))?;
+
+                return Ok(Self::PlayerHead(entry));
             }
         }
 
-        VanillaMinecraftArmorTrimPalette::from(self)
+        match Headwear::from_str(&value) {
+            Ok(Headwear::PlayerHead) => Err(ArmorManagerError::InvalidPlayerHeadError(
+                "Player heads need the player to take the skin of, like `head:<uuid>`".to_owned(),
+            )),
+            Ok(headwear) => Ok(Self::Headwear(headwear)),
+            Err(_) => VanillaMinecraftArmorMaterialData::try_from(value).map(Self::Armor),
+        }
     }
 }
 
-impl From<VanillaMinecraftArmorTrimMaterial> for VanillaMinecraftArmorTrimPalette {
-    fn from(value: VanillaMinecraftArmorTrimMaterial) -> Self {
+impl TryFrom<VanillaMinecraftHelmetData> for String {
+    type Error = ArmorManagerError;
+
+    fn try_from(value: VanillaMinecraftHelmetData) -> Result<Self, Self::Error> {
         match value {
-            VanillaMinecraftArmorTrimMaterial::Amethyst => Self::Amethyst,
-            VanillaMinecraftArmorTrimMaterial::Copper => Self::Copper,
-            VanillaMinecraftArmorTrimMaterial::Diamond => Self::Diamond,
-            VanillaMinecraftArmorTrimMaterial::Emerald => Self::Emerald,
-            VanillaMinecraftArmorTrimMaterial::Gold => Self::Gold,
-            VanillaMinecraftArmorTrimMaterial::Iron => Self::Iron,
-            VanillaMinecraftArmorTrimMaterial::Lapis => Self::Lapis,
-            VanillaMinecraftArmorTrimMaterial::Netherite => Self::Netherite,
-            VanillaMinecraftArmorTrimMaterial::Quartz => Self::Quartz,
-            VanillaMinecraftArmorTrimMaterial::Redstone => Self::Redstone,
+            VanillaMinecraftHelmetData::Armor(armor) => Ok(armor.into()),
+            VanillaMinecraftHelmetData::Headwear(headwear) => Ok(headwear.to_string()),
+            VanillaMinecraftHelmetData::PlayerHead(entry) => {
+                let entry = Self::try_from(entry)
+                    .map_err(|e| ArmorManagerError::InvalidPlayerHeadError(e.to_string()))?;
+
+                Ok(format!("{}:{entry}", Headwear::PlayerHead))
+            }
         }
     }
 }
 
-impl TryFrom<VanillaMinecraftArmorMaterial> for VanillaMinecraftArmorTrimMaterial {
-    type Error = ();
+/// Find the name that the given (possibly partial) name refers to, preferring an exact match.
+fn resolve_partial_name<'a>(
+    names: impl IntoIterator<Item = &'a String> + Clone,
+    value: &str,
+) -> ArmorManagerResult<&'a str> {
+    names
+        .clone()
+        .into_iter()
+        .find(|name| *name == value)
+        .or_else(|| names.into_iter().find(|name| name.starts_with(value)))
+        .map(String::as_str)
+        .ok_or_else(|| ArmorManagerError::UnknownPartialArmorMaterialName(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use nmsr_rendering::high_level::model::headwear::Headwear;
 
-    fn try_from(value: VanillaMinecraftArmorMaterial) -> Result<Self, Self::Error> {
-        match value {
-            VanillaMinecraftArmorMaterial::Diamond => Ok(Self::Diamond),
-            VanillaMinecraftArmorMaterial::Gold => Ok(Self::Gold),
-            VanillaMinecraftArmorMaterial::Iron => Ok(Self::Iron),
-            VanillaMinecraftArmorMaterial::Netherite => Ok(Self::Netherite),
-            VanillaMinecraftArmorMaterial::Leather(_)
-            | VanillaMinecraftArmorMaterial::Turtle
-            | VanillaMinecraftArmorMaterial::Chainmail => Err(()),
-        }
+    use super::{resolve_partial_name, VanillaMinecraftArmorMaterialData, VanillaMinecraftHelmetData};
+    use crate::model::request::entry::RenderRequestEntry;
+
+    #[test]
+    fn test_armor_parsing() {
+        let data = VanillaMinecraftArmorMaterialData::try_from("Diamond_sentry_gold:enchanted".to_string())
+            .expect("Armor should parse");
+
+        assert_eq!(
+            data,
+            VanillaMinecraftArmorMaterialData::new("diamond")
+                .with_trim("sentry", "gold")
+                .enchanted()
+        );
+        assert_eq!(String::from(data), "diamond_sentry_gold:enchanted");
+
+        assert!(VanillaMinecraftArmorMaterialData::try_from("diamond_sentry".to_string()).is_err());
+        assert!(VanillaMinecraftArmorMaterialData::try_from("diamond:shiny".to_string()).is_err());
+        assert!(VanillaMinecraftArmorMaterialData::try_from(String::new()).is_err());
+
+        let data = VanillaMinecraftArmorMaterialData::try_from("uploaded:dye=A06540".to_string())
+            .expect("Dyed uploaded armor should parse");
+
+        assert_eq!(data, VanillaMinecraftArmorMaterialData::new("uploaded").dyed([0xa0, 0x65, 0x40]));
+        assert_eq!(String::from(data), "uploaded:dye=a06540");
+
+        assert!(VanillaMinecraftArmorMaterialData::try_from("uploaded:dye=a065".to_string()).is_err());
+        assert!(VanillaMinecraftArmorMaterialData::try_from("uploaded:dye=a0654g".to_string()).is_err());
+        assert!(VanillaMinecraftArmorMaterialData::try_from("diamond:dye=a06540".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_partial_names() {
+        let names = ["gold".to_string(), "golden".to_string(), "turtle_scute".to_string()];
+
+        assert_eq!(resolve_partial_name(&names, "gold").ok(), Some("gold"));
+        assert_eq!(resolve_partial_name(&names, "golde").ok(), Some("golden"));
+        assert_eq!(resolve_partial_name(&names, "turtle").ok(), Some("turtle_scute"));
+        assert!(resolve_partial_name(&names, "iron").is_err());
+    }
+
+    #[test]
+    fn test_helmet_parsing() {
+        let parse = |value: &str| VanillaMinecraftHelmetData::try_from(value.to_string());
+
+        assert_eq!(
+            parse("diamond:enchanted").ok(),
+            Some(VanillaMinecraftHelmetData::Armor(
+                VanillaMinecraftArmorMaterialData::new("diamond").enchanted()
+            ))
+        );
+        assert_eq!(
+            parse("Carved_Pumpkin").ok(),
+            Some(VanillaMinecraftHelmetData::Headwear(Headwear::CarvedPumpkin))
+        );
+
+        let uuid = "ad4569f3-7576-4376-a7c7-8e8cfcd9b832";
+        let head = parse(&format!("head:{uuid}")).expect("Player head should parse");
+
+        assert_eq!(
+            head,
+            VanillaMinecraftHelmetData::PlayerHead(
+                RenderRequestEntry::try_from(uuid.to_string()).expect("UUID should parse")
+            )
+        );
+        assert_eq!(head.headwear(), Some(Headwear::PlayerHead));
+        assert_eq!(String::try_from(head).ok(), Some(format!("head:{uuid}")));
+
+        assert!(parse("head").is_err(), "Player heads need a player");
+        assert!(parse("head:steve").is_err());
     }
 }
@@ -0,0 +1,430 @@
+use ears_rs::{
+    alfalfa::{AlfalfaData, AlfalfaDataKey},
+    features::{
+        data::{
+            ear::{EarAnchor, EarMode},
+            snout::SnoutData,
+            tail::{TailData, TailMode},
+            wing::{WingData, WingMode},
+        },
+        EarsFeatures,
+    },
+    parser::{v1::writer::EarsWriterV1, EarsFeaturesWriter},
+};
+use image::{ImageFormat, RgbaImage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{RenderRequestError, Result},
+    model::request::RenderRequestMode,
+};
+
+/// Mirror an Ears enum with one that can be (de)serialized, using the `snake_case` names of its variants.
+macro_rules! definition_enum {
+    ($(#[$meta:meta])* $name:ident => $ears:ident { $($variant:ident),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+        #[serde(rename_all = "snake_case")]
+        pub enum $name {
+            $($variant),+
+        }
+
+        impl From<$name> for $ears {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => Self::$variant),+
+                }
+            }
+        }
+
+        impl $name {
+            /// The variant mirroring the given Ears one, if it has one.
+            #[allow(unreachable_patterns)]
+            const fn from_ears(value: $ears) -> Option<Self> {
+                match value {
+                    $($ears::$variant => Some(Self::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+definition_enum!(EarModeDefinition => EarMode {
+    None, Above, Sides, Behind, Around, Floppy, Cross, Out, Tall, TallCross,
+});
+
+definition_enum!(EarAnchorDefinition => EarAnchor { Center, Front, Back });
+
+definition_enum!(
+    /// The mode of a tail, which has no `none` variant since the tail itself is optional.
+    TailModeDefinition => TailMode { Down, Back, Up, Vertical }
+);
+
+definition_enum!(
+    /// The mode of the wings, which has no `none` variant since the wings themselves are optional.
+    WingModeDefinition => WingMode { SymmetricDual, SymmetricSingle, AsymmetricL, AsymmetricR }
+);
+
+/// The Ears features of a skin, as they're encoded into it (or read from it).
+///
+/// ```json
+/// {
+///     "ear_mode": "above",
+///     "tail": { "mode": "down", "segments": 2, "bends": [0, 30] },
+///     "snout": { "width": 4, "height": 2, "depth": 2 },
+///     "wings": { "mode": "symmetric_dual", "animated": true },
+///     "chest_size": 0.5
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+#[schemars(rename = "EarsFeatures")]
+pub struct EarsFeaturesDefinition {
+    pub ear_mode: EarModeDefinition,
+    pub ear_anchor: EarAnchorDefinition,
+    pub tail: Option<TailDefinition>,
+    pub snout: Option<SnoutDefinition>,
+    /// The wings, which also need a wings texture to be uploaded.
+    pub wings: Option<WingsDefinition>,
+    pub claws: bool,
+    pub horn: bool,
+    /// How far the chest sticks out, from `0.0` (flat) to `1.0`.
+    #[schemars(range(min = Self::CHEST_SIZE_BOUNDS.0, max = Self::CHEST_SIZE_BOUNDS.1))]
+    pub chest_size: f32,
+    /// Whether the colors of the emissive palette of the skin are drawn unlit.
+    pub emissive: bool,
+}
+
+impl Default for EarsFeaturesDefinition {
+    fn default() -> Self {
+        Self {
+            ear_mode: EarModeDefinition::None,
+            ear_anchor: EarAnchorDefinition::Center,
+            tail: None,
+            snout: None,
+            wings: None,
+            claws: false,
+            horn: false,
+            chest_size: 0.0,
+            emissive: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TailDefinition {
+    pub mode: TailModeDefinition,
+    #[serde(default = "default_tail_segments")]
+    #[schemars(range(min = EarsFeaturesDefinition::TAIL_SEGMENTS_BOUNDS.0, max = EarsFeaturesDefinition::TAIL_SEGMENTS_BOUNDS.1))]
+    pub segments: u8,
+    /// The angle of each segment relative to the previous one, in degrees.
+    #[serde(default)]
+    #[schemars(
+        length(max = EarsFeaturesDefinition::TAIL_SEGMENTS_BOUNDS.1),
+        inner(range(min = EarsFeaturesDefinition::TAIL_BEND_BOUNDS.0, max = EarsFeaturesDefinition::TAIL_BEND_BOUNDS.1))
+    )]
+    pub bends: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SnoutDefinition {
+    #[schemars(range(min = EarsFeaturesDefinition::SNOUT_WIDTH_BOUNDS.0, max = EarsFeaturesDefinition::SNOUT_WIDTH_BOUNDS.1))]
+    pub width: u8,
+    #[schemars(range(min = EarsFeaturesDefinition::SNOUT_HEIGHT_BOUNDS.0, max = EarsFeaturesDefinition::SNOUT_HEIGHT_BOUNDS.1))]
+    pub height: u8,
+    #[schemars(range(min = EarsFeaturesDefinition::SNOUT_DEPTH_BOUNDS.0, max = EarsFeaturesDefinition::SNOUT_DEPTH_BOUNDS.1))]
+    pub depth: u8,
+    /// How far up the snout is from the bottom of the face, in pixels. The snout has to fit on the 8 pixel tall face.
+    #[serde(default)]
+    #[schemars(range(min = 0, max = 8 - EarsFeaturesDefinition::SNOUT_HEIGHT_BOUNDS.0))]
+    pub offset: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WingsDefinition {
+    pub mode: WingModeDefinition,
+    #[serde(default)]
+    pub animated: bool,
+}
+
+const fn default_tail_segments() -> u8 {
+    1
+}
+
+impl From<&EarsFeatures> for EarsFeaturesDefinition {
+    fn from(features: &EarsFeatures) -> Self {
+        Self {
+            ear_mode: EarModeDefinition::from_ears(features.ear_mode).unwrap_or(EarModeDefinition::None),
+            ear_anchor: EarAnchorDefinition::from_ears(features.ear_anchor).unwrap_or(EarAnchorDefinition::Center),
+            tail: features.tail.and_then(|tail| {
+                Some(TailDefinition {
+                    mode: TailModeDefinition::from_ears(tail.mode)?,
+                    segments: tail.segments,
+                    bends: tail.bends.iter().take(tail.segments as usize).copied().collect(),
+                })
+            }),
+            snout: features.snout.map(|snout| SnoutDefinition {
+                width: snout.width,
+                height: snout.height,
+                depth: snout.depth,
+                offset: snout.offset,
+            }),
+            wings: features.wing.and_then(|wing| {
+                Some(WingsDefinition {
+                    mode: WingModeDefinition::from_ears(wing.mode)?,
+                    animated: wing.animated,
+                })
+            }),
+            claws: features.claws,
+            horn: features.horn,
+            chest_size: features.chest_size,
+            emissive: features.emissive,
+        }
+    }
+}
+
+impl EarsFeaturesDefinition {
+    pub const TAIL_SEGMENTS_BOUNDS: (u8, u8) = (1, 4);
+    pub const TAIL_BEND_BOUNDS: (f32, f32) = (-90.0, 90.0);
+    pub const SNOUT_WIDTH_BOUNDS: (u8, u8) = (1, 7);
+    pub const SNOUT_HEIGHT_BOUNDS: (u8, u8) = (1, 4);
+    pub const SNOUT_DEPTH_BOUNDS: (u8, u8) = (1, 6);
+    pub const CHEST_SIZE_BOUNDS: (f32, f32) = (0.0, 1.0);
+
+    /// The size of the wings and cape textures Ears stores in a skin.
+    pub const TEXTURE_SIZE: (u32, u32) = (20, 16);
+
+    /// Turn this definition into [`EarsFeatures`], making sure it fits in what Ears can store in a skin.
+    pub fn to_features(&self, cape_enabled: bool) -> Result<EarsFeatures> {
+        let tail = self.tail.as_ref().map(TailDefinition::to_data).transpose()?;
+        let snout = self.snout.as_ref().map(SnoutDefinition::to_data).transpose()?;
+
+        let (chest_min, chest_max) = Self::CHEST_SIZE_BOUNDS;
+        RenderRequestMode::validate_unit("chest_size", Some(self.chest_size), chest_min, chest_max)?;
+
+        Ok(EarsFeatures {
+            ear_mode: self.ear_mode.into(),
+            ear_anchor: self.ear_anchor.into(),
+            tail,
+            snout,
+            wing: self.wings.as_ref().map(|wings| WingData {
+                mode: wings.mode.into(),
+                animated: wings.animated,
+            }),
+            claws: self.claws,
+            horn: self.horn,
+            chest_size: self.chest_size,
+            cape_enabled,
+            emissive: self.emissive,
+        })
+    }
+}
+
+impl TailDefinition {
+    fn to_data(&self) -> Result<TailData> {
+        let (segments_min, segments_max) = EarsFeaturesDefinition::TAIL_SEGMENTS_BOUNDS;
+        RenderRequestMode::validate_unit("tail.segments", Some(self.segments), segments_min, segments_max)?;
+
+        if self.bends.len() > self.segments as usize {
+            return Err(RenderRequestError::InvalidRenderSettingError(
+                "tail.bends",
+                format!("at most one bend per segment ({})", self.segments),
+            )
+            .into());
+        }
+
+        let (bend_min, bend_max) = EarsFeaturesDefinition::TAIL_BEND_BOUNDS;
+        let mut bends = [0.0; 4];
+
+        for (bend, &value) in bends.iter_mut().zip(&self.bends) {
+            RenderRequestMode::validate_unit("tail.bends", Some(value), bend_min, bend_max)?;
+            *bend = value;
+        }
+
+        Ok(TailData {
+            mode: self.mode.into(),
+            segments: self.segments,
+            bends,
+        })
+    }
+}
+
+impl SnoutDefinition {
+    fn to_data(&self) -> Result<SnoutData> {
+        let (width_min, width_max) = EarsFeaturesDefinition::SNOUT_WIDTH_BOUNDS;
+        RenderRequestMode::validate_unit("snout.width", Some(self.width), width_min, width_max)?;
+
+        let (height_min, height_max) = EarsFeaturesDefinition::SNOUT_HEIGHT_BOUNDS;
+        RenderRequestMode::validate_unit("snout.height", Some(self.height), height_min, height_max)?;
+
+        let (depth_min, depth_max) = EarsFeaturesDefinition::SNOUT_DEPTH_BOUNDS;
+        RenderRequestMode::validate_unit("snout.depth", Some(self.depth), depth_min, depth_max)?;
+
+        // The snout has to fit on the 8 pixel tall face
+        RenderRequestMode::validate_unit("snout.offset", Some(self.offset), 0, 8 - self.height)?;
+
+        Ok(SnoutData {
+            offset: self.offset,
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+        })
+    }
+}
+
+/// Encode Ears features into a skin, along with the wings and cape textures (as PNG files in the Ears layout),
+/// so that Ears and [`ears_rs::parser::EarsParser`] read them back from it.
+///
+/// Legacy 64×32 skins are upgraded first, since Ears only supports 64×64 skins.
+pub fn encode_ears_features(
+    skin: RgbaImage,
+    definition: &EarsFeaturesDefinition,
+    wings: Option<Vec<u8>>,
+    cape: Option<Vec<u8>>,
+) -> Result<RgbaImage> {
+    let mut skin = ears_rs::utils::upgrade_skin_if_needed(skin);
+
+    if skin.dimensions() != (64, 64) {
+        return Err(RenderRequestError::InvalidSkinSizeError(skin.width(), skin.height()).into());
+    }
+
+    if definition.wings.is_some() && wings.is_none() {
+        return Err(RenderRequestError::InvalidEarsTextureError(
+            "wings",
+            "The wings need a texture to be drawn".to_string(),
+        )
+        .into());
+    }
+
+    let features = definition.to_features(cape.is_some())?;
+
+    let mut alfalfa = AlfalfaData::new();
+    let mut has_alfalfa = false;
+
+    for (name, key, texture) in [("wings", AlfalfaDataKey::Wings, wings), ("cape", AlfalfaDataKey::Cape, cape)] {
+        if let Some(texture) = texture {
+            validate_texture(name, &texture)?;
+            alfalfa.set_data(key, texture);
+            has_alfalfa = true;
+        }
+    }
+
+    EarsWriterV1::write(&mut skin, &features)?;
+
+    if has_alfalfa {
+        ears_rs::alfalfa::write_alfalfa(&alfalfa, &mut skin)?;
+    }
+
+    Ok(skin)
+}
+
+fn validate_texture(name: &'static str, texture: &[u8]) -> Result<()> {
+    let image = image::load_from_memory_with_format(texture, ImageFormat::Png)
+        .map_err(|e| RenderRequestError::InvalidEarsTextureError(name, e.to_string()))?;
+
+    let (width, height) = EarsFeaturesDefinition::TEXTURE_SIZE;
+    if image.width() != width || image.height() != height {
+        return Err(RenderRequestError::InvalidEarsTextureError(
+            name,
+            format!("The texture should be {width}x{height}, not {}x{}", image.width(), image.height()),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use ears_rs::{
+        alfalfa::{read_alfalfa, AlfalfaDataKey},
+        features::data::{ear::EarMode, tail::TailMode, wing::WingMode},
+        parser::EarsParser,
+    };
+    use image::{ImageFormat, RgbaImage};
+
+    use super::{encode_ears_features, EarsFeaturesDefinition};
+    use crate::error::{NMSRaaSError, RenderRequestError};
+
+    fn texture(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        RgbaImage::new(width, height)
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .expect("Failed to encode texture");
+        bytes
+    }
+
+    #[test]
+    fn test_encoded_features_round_trip() {
+        let definition: EarsFeaturesDefinition = serde_json::from_str(
+            r#"{
+                "ear_mode": "tall_cross",
+                "tail": { "mode": "back", "segments": 2, "bends": [10, -20] },
+                "snout": { "width": 4, "height": 2, "depth": 3, "offset": 1 },
+                "wings": { "mode": "symmetric_dual", "animated": true },
+                "chest_size": 0.5
+            }"#,
+        )
+        .expect("Failed to parse definition");
+
+        let wings = texture(20, 16);
+        let skin = encode_ears_features(RgbaImage::new(64, 32), &definition, Some(wings.clone()), None)
+            .expect("Failed to encode features");
+
+        assert_eq!((64, 64), skin.dimensions());
+
+        let features = EarsParser::parse(&skin)
+            .expect("Failed to parse features")
+            .expect("The skin has no features");
+
+        assert_eq!(EarMode::TallCross, features.ear_mode);
+        assert_eq!(Some(TailMode::Back), features.tail.map(|tail| tail.mode));
+        assert_eq!(Some([10.0, -20.0, 0.0, 0.0]), features.tail.map(|tail| tail.bends));
+        assert_eq!(Some(3), features.snout.map(|snout| snout.depth));
+        assert_eq!(Some(WingMode::SymmetricDual), features.wing.map(|wing| wing.mode));
+        assert!(!features.cape_enabled);
+
+        let read_back = EarsFeaturesDefinition::from(&features);
+        assert_eq!(Some(vec![10.0, -20.0]), read_back.tail.map(|tail| tail.bends));
+        assert_eq!(Some(1), read_back.snout.map(|snout| snout.offset));
+
+        let alfalfa = read_alfalfa(&skin)
+            .expect("Failed to read alfalfa")
+            .expect("The skin has no alfalfa");
+        assert_eq!(Some(&wings), alfalfa.get_data(AlfalfaDataKey::Wings));
+    }
+
+    #[test]
+    fn test_invalid_definitions_are_rejected() {
+        let snout: EarsFeaturesDefinition =
+            serde_json::from_str(r#"{ "snout": { "width": 4, "height": 4, "depth": 2, "offset": 5 } }"#)
+                .expect("Failed to parse definition");
+        let wings: EarsFeaturesDefinition = serde_json::from_str(r#"{ "wings": { "mode": "asymmetric_l" } }"#)
+            .expect("Failed to parse definition");
+
+        let cases = [
+            (snout, None),
+            (wings.clone(), None),
+            (wings, Some(texture(64, 32))),
+        ];
+
+        for (definition, wings) in cases {
+            let result = encode_ears_features(RgbaImage::new(64, 64), &definition, wings, None);
+
+            assert!(
+                matches!(
+                    result,
+                    Err(NMSRaaSError::RenderRequestError(
+                        RenderRequestError::InvalidRenderSettingBoundsError("snout.offset", _, _)
+                            | RenderRequestError::InvalidEarsTextureError("wings", _)
+                    ))
+                ),
+                "{definition:?} should be rejected"
+            );
+        }
+    }
+}
@@ -0,0 +1,494 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{Cursor, Read},
+};
+
+use nmsr_rendering::{
+    high_level::parts::geometry::{
+        BedrockBone, BedrockCube, BedrockCubeUv, BedrockFaceUv, BedrockGeometry,
+    },
+    low_level::Vec2,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use zip::ZipArchive;
+
+use crate::error::{RenderRequestError, Result};
+
+/// The largest file we're willing to read out of a skin pack, so that a small archive can't inflate into gigabytes.
+const MAX_SKIN_PACK_ENTRY_SIZE: u64 = 8 * 1024 * 1024;
+
+/// How deep the legacy geometry inheritance (`"geometry.child:geometry.parent"`) can go.
+const MAX_GEOMETRY_INHERITANCE_DEPTH: usize = 8;
+
+/// The geometry of the slim Bedrock Edition skins, which use the arms of the Alex model.
+const SLIM_HUMANOID_GEOMETRY: &str = "geometry.humanoid.customSlim";
+
+/// A Bedrock Edition geometry file (`.geo.json`), in either the current format or the legacy one.
+///
+/// ```json
+/// {
+///     "format_version": "1.12.0",
+///     "minecraft:geometry": [
+///         {
+///             "description": { "identifier": "geometry.custom", "texture_width": 64, "texture_height": 64 },
+///             "bones": [
+///                 { "name": "head", "pivot": [0, 24, 0], "cubes": [{ "origin": [-4, 24, -4], "size": [8, 8, 8], "uv": [0, 0] }] }
+///             ]
+///         }
+///     ]
+/// }
+/// ```
+///
+/// Legacy files (format `1.8.0` and `1.10.0`) define each geometry as a `"geometry.name[:geometry.parent]"` property
+/// instead, and inherit the bones of their parent.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GeometryFile {
+    #[serde(rename = "minecraft:geometry", default)]
+    geometries: Vec<GeometryDefinition>,
+    #[serde(flatten)]
+    legacy_geometries: HashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GeometryDefinition {
+    description: GeometryDescription,
+    #[serde(default)]
+    bones: Vec<BoneDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GeometryDescription {
+    identifier: String,
+    texture_width: Option<u32>,
+    texture_height: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct LegacyGeometryDefinition {
+    texturewidth: Option<u32>,
+    textureheight: Option<u32>,
+    #[serde(default)]
+    bones: Vec<BoneDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BoneDefinition {
+    name: String,
+    parent: Option<String>,
+    #[serde(default)]
+    pivot: [f32; 3],
+    #[serde(default)]
+    rotation: [f32; 3],
+    #[serde(default)]
+    mirror: bool,
+    #[serde(default)]
+    inflate: f32,
+    #[serde(default)]
+    cubes: Vec<CubeDefinition>,
+    #[serde(default, rename = "neverRender")]
+    never_render: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CubeDefinition {
+    origin: [f32; 3],
+    size: [f32; 3],
+    uv: Option<CubeUvDefinition>,
+    inflate: Option<f32>,
+    mirror: Option<bool>,
+    pivot: Option<[f32; 3]>,
+    rotation: Option<[f32; 3]>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum CubeUvDefinition {
+    Box([f32; 2]),
+    PerFace(FaceUvsDefinition),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FaceUvsDefinition {
+    north: Option<FaceUvDefinition>,
+    south: Option<FaceUvDefinition>,
+    east: Option<FaceUvDefinition>,
+    west: Option<FaceUvDefinition>,
+    up: Option<FaceUvDefinition>,
+    down: Option<FaceUvDefinition>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct FaceUvDefinition {
+    uv: [f32; 2],
+    #[serde(default)]
+    uv_size: [f32; 2],
+}
+
+/// A geometry of a [`GeometryFile`], with the bones it inherits already merged in.
+struct NamedGeometry {
+    identifier: String,
+    texture_size: (u32, u32),
+    bones: Vec<BoneDefinition>,
+}
+
+/// A skin read out of an uploaded geometry, which is either a `.geo.json` file or a whole skin pack.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BedrockSkin {
+    /// The texture of the skin, if it came with the geometry (like in skin packs).
+    pub texture: Option<Vec<u8>>,
+    /// The custom geometry of the skin, or `None` if it uses the standard player model.
+    pub geometry: Option<BedrockGeometry>,
+    /// Whether the skin uses the slim arms of the Alex model.
+    pub slim: bool,
+}
+
+impl BedrockSkin {
+    /// Read an uploaded geometry, which is either a `.geo.json` file or a skin pack (`.mcpack`).
+    ///
+    /// Only the first skin of a skin pack is used.
+    pub fn load(bytes: &[u8]) -> Result<Self> {
+        if bytes.starts_with(b"PK") {
+            return Self::load_skin_pack(bytes);
+        }
+
+        Ok(Self {
+            geometry: GeometryFile::parse(bytes)?.into_geometry(None),
+            ..Default::default()
+        })
+    }
+
+    fn load_skin_pack(bytes: &[u8]) -> Result<Self> {
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|e| invalid_skin_pack(&e))?;
+
+        // Skin packs are sometimes zipped along with the folder they're in
+        let skins_path = archive
+            .file_names()
+            .filter(|name| *name == "skins.json" || name.ends_with("/skins.json"))
+            .min_by_key(|name| name.len())
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| {
+                RenderRequestError::InvalidGeometryError("The skin pack has no skins.json".to_string())
+            })?;
+        let root = skins_path.trim_end_matches("skins.json").to_owned();
+
+        let skins: SkinPackDefinition = serde_json::from_slice(&read_entry(&mut archive, &skins_path)?)
+            .map_err(|e| RenderRequestError::InvalidGeometryError(format!("Invalid skins.json: {e}")))?;
+
+        let skin = skins.skins.into_iter().next().ok_or_else(|| {
+            RenderRequestError::InvalidGeometryError("The skin pack has no skins".to_string())
+        })?;
+
+        let texture = read_entry(&mut archive, &format!("{root}{}", skin.texture))?;
+
+        let geometry_path = format!("{root}geometry.json");
+        let geometry = if archive.file_names().any(|name| name == geometry_path) {
+            GeometryFile::parse(&read_entry(&mut archive, &geometry_path)?)?
+                .into_geometry(Some(&skin.geometry))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            texture: Some(texture),
+            geometry,
+            slim: skin.geometry == SLIM_HUMANOID_GEOMETRY,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SkinPackDefinition {
+    skins: Vec<SkinDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SkinDefinition {
+    geometry: String,
+    texture: String,
+}
+
+fn invalid_skin_pack(error: &zip::result::ZipError) -> RenderRequestError {
+    RenderRequestError::InvalidGeometryError(format!("Invalid skin pack: {error}"))
+}
+
+fn read_entry(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<Vec<u8>> {
+    let entry = archive.by_name(name).map_err(|e| invalid_skin_pack(&e))?;
+
+    if entry.size() > MAX_SKIN_PACK_ENTRY_SIZE {
+        return Err(RenderRequestError::InvalidGeometryError(format!(
+            "{name} is too large ({} bytes)",
+            entry.size()
+        ))
+        .into());
+    }
+
+    let mut contents = Vec::with_capacity(entry.size() as usize);
+    // Don't trust the size from the archive when reading
+    entry
+        .take(MAX_SKIN_PACK_ENTRY_SIZE)
+        .read_to_end(&mut contents)
+        .map_err(|e| RenderRequestError::InvalidGeometryError(format!("Unable to read {name}: {e}")))?;
+
+    Ok(contents)
+}
+
+impl GeometryFile {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes).map_err(|e| RenderRequestError::InvalidGeometryError(e.to_string()).into())
+    }
+
+    /// Pick the geometry with the given identifier (or the first one with cubes) and convert it.
+    ///
+    /// Returns `None` if the geometry doesn't exist or has no cubes, in which case the standard player model is used.
+    #[must_use]
+    pub fn into_geometry(self, identifier: Option<&str>) -> Option<BedrockGeometry> {
+        let geometries = self.into_named_geometries();
+
+        let geometry = match identifier {
+            Some(identifier) => geometries.into_iter().find(|g| g.identifier == identifier),
+            None => geometries
+                .into_iter()
+                .find(|g| g.bones.iter().any(|bone| !bone.cubes.is_empty())),
+        }?;
+
+        let geometry = BedrockGeometry::from(geometry);
+
+        geometry
+            .bones
+            .iter()
+            .any(|bone| !bone.cubes.is_empty())
+            .then_some(geometry)
+    }
+
+    fn into_named_geometries(self) -> Vec<NamedGeometry> {
+        let mut geometries: Vec<NamedGeometry> = self
+            .geometries
+            .into_iter()
+            .map(|geometry| NamedGeometry {
+                identifier: geometry.description.identifier,
+                texture_size: (
+                    geometry.description.texture_width.unwrap_or(64),
+                    geometry.description.texture_height.unwrap_or(64),
+                ),
+                bones: geometry.bones,
+            })
+            .collect();
+
+        // Legacy geometries are keyed by their identifier, followed by the identifier of the geometry they inherit from
+        let legacy_geometries: BTreeMap<&str, (Option<&str>, LegacyGeometryDefinition)> = self
+            .legacy_geometries
+            .iter()
+            .filter(|(key, _)| key.starts_with("geometry."))
+            .filter_map(|(key, value)| {
+                let (identifier, parent) = key
+                    .split_once(':')
+                    .map_or((key.as_str(), None), |(identifier, parent)| (identifier, Some(parent)));
+
+                let definition = LegacyGeometryDefinition::deserialize(value).ok()?;
+                Some((identifier, (parent, definition)))
+            })
+            .collect();
+
+        for &identifier in legacy_geometries.keys() {
+            let mut bones: Vec<BoneDefinition> = Vec::new();
+            let mut texture_size = None;
+
+            let mut current = Some(identifier);
+            for _ in 0..MAX_GEOMETRY_INHERITANCE_DEPTH {
+                let Some((parent, definition)) = current.and_then(|id| legacy_geometries.get(id)) else {
+                    break;
+                };
+
+                texture_size = texture_size.or_else(|| definition.texturewidth.zip(definition.textureheight));
+
+                // The bones of a child override the bones of its parent with the same name
+                for bone in &definition.bones {
+                    if !bones.iter().any(|b| b.name.eq_ignore_ascii_case(&bone.name)) {
+                        bones.push(bone.clone());
+                    }
+                }
+
+                current = *parent;
+            }
+
+            geometries.push(NamedGeometry {
+                identifier: identifier.to_owned(),
+                texture_size: texture_size.unwrap_or((64, 64)),
+                bones,
+            });
+        }
+
+        geometries
+    }
+}
+
+impl From<NamedGeometry> for BedrockGeometry {
+    fn from(geometry: NamedGeometry) -> Self {
+        let bones = geometry
+            .bones
+            .into_iter()
+            .map(|bone| {
+                let cubes = if bone.never_render {
+                    Vec::new()
+                } else {
+                    bone.cubes
+                        .into_iter()
+                        .map(|cube| cube.into_cube(bone.inflate, bone.mirror))
+                        .collect()
+                };
+
+                BedrockBone {
+                    name: bone.name,
+                    parent: bone.parent,
+                    pivot: bone.pivot.into(),
+                    rotation: bone.rotation.into(),
+                    cubes,
+                }
+            })
+            .collect();
+
+        Self {
+            texture_size: geometry.texture_size,
+            bones,
+        }
+    }
+}
+
+impl CubeDefinition {
+    /// Convert the cube, which inherits the `inflate` and `mirror` of its bone unless it sets them itself.
+    fn into_cube(self, inflate: f32, mirror: bool) -> BedrockCube {
+        let uv = match self.uv {
+            None => BedrockCubeUv::Box(Vec2::ZERO),
+            Some(CubeUvDefinition::Box(uv)) => BedrockCubeUv::Box(uv.into()),
+            Some(CubeUvDefinition::PerFace(faces)) => BedrockCubeUv::PerFace(
+                [faces.north, faces.south, faces.east, faces.west, faces.up, faces.down].map(|face| {
+                    face.map(|face| BedrockFaceUv {
+                        uv: face.uv.into(),
+                        size: face.uv_size.into(),
+                    })
+                }),
+            ),
+        };
+
+        let origin = self.origin.into();
+        let size = self.size.into();
+
+        BedrockCube {
+            origin,
+            size,
+            uv,
+            inflate: self.inflate.unwrap_or(inflate),
+            mirror: self.mirror.unwrap_or(mirror),
+            // Cubes without a pivot rotate around their center
+            pivot: self.pivot.map_or(origin + size / 2.0, Into::into),
+            rotation: self.rotation.unwrap_or_default().into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use nmsr_rendering::high_level::parts::geometry::BedrockCubeUv;
+    use zip::{write::FileOptions, ZipWriter};
+
+    use super::{BedrockSkin, GeometryFile};
+
+    const MODERN_GEOMETRY: &str = r#"{
+        "format_version": "1.12.0",
+        "minecraft:geometry": [
+            {
+                "description": { "identifier": "geometry.empty", "texture_width": 64, "texture_height": 64 },
+                "bones": [{ "name": "body", "pivot": [0, 24, 0] }]
+            },
+            {
+                "description": { "identifier": "geometry.horns", "texture_width": 128, "texture_height": 128 },
+                "bones": [
+                    { "name": "head", "pivot": [0, 24, 0], "mirror": true, "cubes": [{ "origin": [-4, 24, -4], "size": [8, 8, 8], "uv": [0, 0] }] },
+                    { "name": "horn", "parent": "head", "pivot": [0, 32, 0], "rotation": [0, 0, 15], "cubes": [
+                        { "origin": [2, 32, 0], "size": [1, 4, 1], "uv": { "north": { "uv": [64, 0], "uv_size": [1, 4] } }, "mirror": false }
+                    ] }
+                ]
+            }
+        ]
+    }"#;
+
+    const LEGACY_GEOMETRY: &str = r#"{
+        "format_version": "1.8.0",
+        "geometry.base": {
+            "texturewidth": 64,
+            "textureheight": 32,
+            "bones": [
+                { "name": "head", "pivot": [0, 24, 0], "cubes": [{ "origin": [-4, 24, -4], "size": [8, 8, 8], "uv": [0, 0] }] },
+                { "name": "body", "pivot": [0, 24, 0], "cubes": [{ "origin": [-4, 12, -2], "size": [8, 12, 4], "uv": [16, 16] }] }
+            ]
+        },
+        "geometry.big_head:geometry.base": {
+            "bones": [
+                { "name": "head", "pivot": [0, 24, 0], "inflate": 2, "cubes": [{ "origin": [-4, 24, -4], "size": [8, 8, 8], "uv": [0, 0] }] }
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn test_modern_geometry() {
+        let file = GeometryFile::parse(MODERN_GEOMETRY.as_bytes()).expect("Geometry should parse");
+
+        assert!(
+            file.clone().into_geometry(Some("geometry.empty")).is_none(),
+            "Geometries without cubes use the standard model"
+        );
+
+        let geometry = file.into_geometry(None).expect("The first geometry with cubes should be picked");
+        assert_eq!((128, 128), geometry.texture_size);
+        assert_eq!(2, geometry.bones.len());
+
+        let head = &geometry.bones[0];
+        assert!(head.cubes[0].mirror, "Cubes inherit the mirroring of their bone");
+
+        let horn = &geometry.bones[1];
+        assert_eq!(Some("head"), horn.parent.as_deref());
+        assert!(!horn.cubes[0].mirror);
+        let BedrockCubeUv::PerFace(faces) = &horn.cubes[0].uv else {
+            panic!("The horn has per-face UVs");
+        };
+        assert!(faces[0].is_some() && faces[1..].iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_legacy_geometry_inheritance() {
+        let geometry = GeometryFile::parse(LEGACY_GEOMETRY.as_bytes())
+            .expect("Geometry should parse")
+            .into_geometry(Some("geometry.big_head"))
+            .expect("Geometry should exist");
+
+        assert_eq!((64, 32), geometry.texture_size, "The texture size is inherited");
+        assert_eq!(2, geometry.bones.len());
+
+        let head = geometry.bones.iter().find(|bone| bone.name == "head").unwrap();
+        assert!((head.cubes[0].inflate - 2.0).abs() < f32::EPSILON, "The child overrides the bones of its parent");
+    }
+
+    #[test]
+    fn test_skin_pack() {
+        let mut pack = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default();
+
+        pack.start_file("Pack/skins.json", options).unwrap();
+        pack.write_all(br#"{ "skins": [{ "geometry": "geometry.horns", "texture": "horns.png" }] }"#)
+            .unwrap();
+        pack.start_file("Pack/horns.png", options).unwrap();
+        pack.write_all(b"png").unwrap();
+        pack.start_file("Pack/geometry.json", options).unwrap();
+        pack.write_all(MODERN_GEOMETRY.as_bytes()).unwrap();
+
+        let bytes = pack.finish().unwrap().into_inner();
+        let skin = BedrockSkin::load(&bytes).expect("Skin pack should load");
+
+        assert_eq!(Some(b"png".to_vec()), skin.texture);
+        assert_eq!(2, skin.geometry.expect("The skin has a custom geometry").bones.len());
+        assert!(!skin.slim);
+    }
+}
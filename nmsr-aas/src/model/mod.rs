@@ -1,3 +1,8 @@
+pub mod animation;
 pub mod armor;
+#[cfg(feature = "ears")]
+pub mod ears;
+pub mod geometry;
+pub mod nametag;
 pub mod request;
 pub mod resolver;
@@ -0,0 +1,319 @@
+use std::sync::OnceLock;
+
+use enumset::{EnumSet, EnumSetType};
+use image::{imageops, ImageFormat, Rgba, RgbaImage};
+
+/// The pixel font name tags are written with when the asset packs don't have one, laid out like the `ascii.png` font of
+/// the game: a 16×16 grid of 8×8 glyphs, one for each character code. It's made of the public domain `5x8` font of the
+/// X.Org project (see `font.LICENSE`).
+const FONT: &[u8] = include_bytes!("font.png");
+
+const GLYPH_SIZE: u32 = 8;
+/// The height of a line of text, which the background goes one pixel past on every side.
+const LINE_HEIGHT: u32 = 9;
+const SPACE_WIDTH: u32 = 3;
+
+/// The semi-transparent black the game draws behind name tags.
+const BACKGROUND: Rgba<u8> = Rgba([0, 0, 0, 0x3F]);
+
+/// The colors of the formatting codes `0` to `f`.
+const COLORS: [[u8; 3]; 16] = [
+    [0x00, 0x00, 0x00],
+    [0x00, 0x00, 0xAA],
+    [0x00, 0xAA, 0x00],
+    [0x00, 0xAA, 0xAA],
+    [0xAA, 0x00, 0x00],
+    [0xAA, 0x00, 0xAA],
+    [0xFF, 0xAA, 0x00],
+    [0xAA, 0xAA, 0xAA],
+    [0x55, 0x55, 0x55],
+    [0x55, 0x55, 0xFF],
+    [0x55, 0xFF, 0x55],
+    [0x55, 0xFF, 0xFF],
+    [0xFF, 0x55, 0x55],
+    [0xFF, 0x55, 0xFF],
+    [0xFF, 0xFF, 0x55],
+    [0xFF, 0xFF, 0xFF],
+];
+
+static NAMETAG_FONT: OnceLock<NametagFont> = OnceLock::new();
+
+/// A font that name tags are written with, like the bundled one or the `ascii.png` font of the asset packs.
+pub struct NametagFont {
+    glyphs: RgbaImage,
+    /// The width of the opaque part of each glyph, in pixels.
+    widths: [u32; 256],
+}
+
+impl NametagFont {
+    /// Read a font laid out like the `ascii.png` font of the game, where the glyphs of HD fonts are scaled down to 8×8.
+    #[must_use]
+    pub fn new(glyphs: RgbaImage) -> Self {
+        let size = GLYPH_SIZE * 16;
+
+        let glyphs = if glyphs.dimensions() == (size, size) {
+            glyphs
+        } else {
+            imageops::resize(&glyphs, size, size, imageops::FilterType::Nearest)
+        };
+
+        let widths = std::array::from_fn(|code| {
+            let (cell_x, cell_y) = Self::get_cell(code as u32);
+
+            (0..GLYPH_SIZE)
+                .rev()
+                .find(|&x| (0..GLYPH_SIZE).any(|y| glyphs.get_pixel(cell_x + x, cell_y + y)[3] != 0))
+                .map_or(0, |x| x + 1)
+        });
+
+        Self { glyphs, widths }
+    }
+
+    /// The font bundled with NMSR, used when the asset packs don't have one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bundled font isn't a valid PNG image, which is checked by the tests.
+    pub fn bundled() -> &'static Self {
+        NAMETAG_FONT.get_or_init(|| {
+            let glyphs = image::load_from_memory_with_format(FONT, ImageFormat::Png)
+                .expect("The bundled font should be a valid image")
+                .into_rgba8();
+
+            Self::new(glyphs)
+        })
+    }
+
+    const fn get_cell(code: u32) -> (u32, u32) {
+        ((code % 16) * GLYPH_SIZE, (code / 16) * GLYPH_SIZE)
+    }
+
+    /// The glyph used to draw `character`, which falls back to `?` for the characters the font doesn't have.
+    const fn get_code(character: char) -> u32 {
+        if character.is_ascii_graphic() || character == ' ' {
+            character as u32
+        } else {
+            '?' as u32
+        }
+    }
+
+    /// How far the next character is drawn from `character`.
+    fn get_advance(&self, character: char, style: TextStyle) -> u32 {
+        let width = if character == ' ' {
+            SPACE_WIDTH
+        } else {
+            self.widths[Self::get_code(character) as usize]
+        };
+
+        width + 1 + u32::from(style.has(TextFormatting::Bold))
+    }
+
+    fn draw(&self, image: &mut RgbaImage, character: char, style: TextStyle, x: u32, y: u32) {
+        let (cell_x, cell_y) = Self::get_cell(Self::get_code(character));
+        let is_bold = style.has(TextFormatting::Bold);
+
+        for glyph_y in 0..GLYPH_SIZE {
+            // Italic text leans to the right, like the game shears it
+            let italic_offset = u32::from(style.has(TextFormatting::Italic) && glyph_y < GLYPH_SIZE / 2);
+
+            for glyph_x in 0..GLYPH_SIZE {
+                if self.glyphs.get_pixel(cell_x + glyph_x, cell_y + glyph_y)[3] == 0 {
+                    continue;
+                }
+
+                // Bold text is drawn twice, one pixel apart
+                for bold_offset in 0..=u32::from(is_bold) {
+                    let pixel_x = x + glyph_x + italic_offset + bold_offset;
+
+                    if pixel_x < image.width() {
+                        image.put_pixel(pixel_x, y + glyph_y, style.get_pixel());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(EnumSetType, Debug)]
+enum TextFormatting {
+    Bold,
+    Italic,
+    Underlined,
+    Strikethrough,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TextStyle {
+    color: [u8; 3],
+    formatting: EnumSet<TextFormatting>,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            color: [0xFF, 0xFF, 0xFF],
+            formatting: EnumSet::empty(),
+        }
+    }
+}
+
+impl TextStyle {
+    fn has(self, formatting: TextFormatting) -> bool {
+        self.formatting.contains(formatting)
+    }
+
+    const fn get_pixel(self) -> Rgba<u8> {
+        let [red, green, blue] = self.color;
+        Rgba([red, green, blue, 0xFF])
+    }
+
+    /// Apply a formatting code to the style, which isn't one if `None` is returned.
+    ///
+    /// Like in the game, colors reset the formatting, and obfuscated text (`k`) is drawn as is.
+    fn with_formatting_code(mut self, code: char) -> Option<Self> {
+        let code = code.to_ascii_lowercase();
+
+        if let Some(color) = code.to_digit(16) {
+            return Some(Self {
+                color: COLORS[color as usize],
+                ..Self::default()
+            });
+        }
+
+        let formatting = match code {
+            'k' => return Some(self),
+            'l' => TextFormatting::Bold,
+            'm' => TextFormatting::Strikethrough,
+            'n' => TextFormatting::Underlined,
+            'o' => TextFormatting::Italic,
+            'r' => return Some(Self::default()),
+            _ => return None,
+        };
+
+        self.formatting.insert(formatting);
+        Some(self)
+    }
+}
+
+/// Split `text` into its characters and the style they're drawn with, following the formatting codes of the game
+/// (`§` or `&`, followed by the code). Anything that isn't a formatting code is kept as text.
+fn parse_formatted_text(text: &str) -> Vec<(char, TextStyle)> {
+    let mut style = TextStyle::default();
+    let mut characters = Vec::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(character) = chars.next() {
+        if matches!(character, '§' | '&') {
+            if let Some(new_style) = chars.peek().and_then(|&code| style.with_formatting_code(code)) {
+                style = new_style;
+                chars.next();
+                continue;
+            }
+        }
+
+        characters.push((character, style));
+    }
+
+    characters
+}
+
+/// The size of the texture of a name tag showing `text`, without rendering it.
+#[must_use]
+pub fn get_nametag_size(text: &str, font: &NametagFont) -> (u32, u32) {
+    let width: u32 = parse_formatted_text(text)
+        .into_iter()
+        .map(|(character, style)| font.get_advance(character, style))
+        .sum();
+
+    (width + 1, LINE_HEIGHT + 1)
+}
+
+/// Render the texture of a name tag showing `text`, with the semi-transparent background the game draws behind it.
+///
+/// Each pixel of the texture is a pixel of the font, and the text is one pixel away from the edges of the background.
+#[must_use]
+pub fn render_nametag(text: &str, font: &NametagFont) -> RgbaImage {
+    let (width, height) = get_nametag_size(text, font);
+
+    let mut image = RgbaImage::from_pixel(width, height, BACKGROUND);
+    let mut x = 1;
+
+    for (character, style) in parse_formatted_text(text) {
+        let advance = font.get_advance(character, style);
+        font.draw(&mut image, character, style, x, 1);
+
+        let lines = [(TextFormatting::Strikethrough, 4), (TextFormatting::Underlined, LINE_HEIGHT)];
+
+        for (_, y) in lines.into_iter().filter(|&(line, _)| style.has(line)) {
+            for line_x in x - 1..x + advance {
+                image.put_pixel(line_x, y, style.get_pixel());
+            }
+        }
+
+        x += advance;
+    }
+
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgba, RgbaImage};
+
+    use super::{get_nametag_size, parse_formatted_text, render_nametag, NametagFont, TextFormatting, TextStyle, BACKGROUND};
+
+    #[test]
+    fn test_formatting_codes() {
+        let characters = parse_formatted_text("a§cb&lc§zd&re");
+
+        let styles: Vec<_> = characters.iter().map(|(_, style)| *style).collect();
+        let text: String = characters.iter().map(|(character, _)| character).collect();
+
+        let red = TextStyle {
+            color: [0xFF, 0x55, 0x55],
+            ..TextStyle::default()
+        };
+
+        assert_eq!("abc§zde", text);
+        assert_eq!(TextStyle::default(), styles[0]);
+        assert_eq!(red, styles[1]);
+        assert_eq!(
+            TextStyle {
+                formatting: TextFormatting::Bold.into(),
+                ..red
+            },
+            styles[2]
+        );
+        assert_eq!(TextStyle::default(), styles[6]);
+    }
+
+    #[test]
+    fn test_nametag_texture() {
+        let font = NametagFont::bundled();
+
+        // An `H` is 4 pixels wide and an `i` is 3 pixels wide, with a pixel between each character
+        let nametag = render_nametag("H§ai", font);
+        assert_eq!((10, 10), nametag.dimensions());
+        assert_eq!(nametag.dimensions(), get_nametag_size("H§ai", font));
+
+        assert_eq!(BACKGROUND, *nametag.get_pixel(0, 0));
+        assert_eq!(Rgba([0xFF, 0xFF, 0xFF, 0xFF]), *nametag.get_pixel(1, 2));
+        assert_eq!(Rgba([0x55, 0xFF, 0x55, 0xFF]), *nametag.get_pixel(7, 7));
+        assert_eq!(BACKGROUND, *nametag.get_pixel(9, 9));
+    }
+
+    #[test]
+    fn test_hd_font() {
+        // A font twice the size of the game's, where the `!` is a 2x2 block in the corner of its 16x16 cell
+        let mut glyphs = RgbaImage::new(256, 256);
+        for (x, y) in [(16, 32), (17, 32), (16, 33), (17, 33)] {
+            glyphs.put_pixel(x, y, Rgba([0xFF, 0xFF, 0xFF, 0xFF]));
+        }
+
+        let font = NametagFont::new(glyphs);
+        let nametag = render_nametag("!", &font);
+
+        assert_eq!((3, 10), nametag.dimensions());
+        assert_eq!(Rgba([0xFF, 0xFF, 0xFF, 0xFF]), *nametag.get_pixel(1, 1));
+    }
+}
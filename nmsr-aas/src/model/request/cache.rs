@@ -183,7 +183,7 @@ pub struct ModelCache {
         RenderRequestEntry,
         ResolvedRenderEntryTextures,
         ModelCacheConfiguration,
-        [u8; 1],
+        Vec<u8>,
         ResolvedModelTexturesCacheHandler,
     >,
 }
@@ -233,6 +233,11 @@ impl ModelCache {
         self.resolved_textures.get_cached_entry(entry).await
     }
 
+    /// How long ago the resolved textures of the entry were cached, if they are.
+    pub async fn get_resolved_texture_age(&self, entry: &RenderRequestEntry) -> Result<Option<Duration>> {
+        self.resolved_textures.get_cache_entry_age(entry).await
+    }
+
     pub async fn cache_resolved_texture(
         &self,
         entry: &RenderRequestEntry,
@@ -253,7 +258,7 @@ impl ModelCache {
 }
 
 #[async_trait]
-impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheConfiguration, [u8; 1]>
+impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheConfiguration, Vec<u8>>
     for ResolvedModelTexturesCacheHandler
 {
     #[inline]
@@ -293,7 +298,7 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
         &self,
         entry: &RenderRequestEntry,
         config: &ModelCacheConfiguration,
-        _marker: &[u8; 1],
+        _marker: &Vec<u8>,
         marker_metadata: Metadata,
     ) -> Result<bool> {
         config.is_expired(entry, &marker_metadata)
@@ -309,7 +314,7 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
         if !base.exists() {
             fs::create_dir_all(base)
                 .await
-                .explain(format!("Unable to create cache directory for {:?}", &entry))?;
+                .explain(format!("Unable to create cache directory for {entry:?}"))?;
         }
 
         for (texture_type, texture) in &value.textures {
@@ -342,7 +347,7 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
         entry: &RenderRequestEntry,
         config: &ModelCacheConfiguration,
         base: &Path,
-        marker: &[u8; 1],
+        marker: &Vec<u8>,
     ) -> Result<Option<ResolvedRenderEntryTextures>> {
         let mut textures = HashMap::new();
 
@@ -364,9 +369,8 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
             let texture_path = base.join(format!("{}{}", Into::<&str>::into(texture), ".png"));
 
             if texture_path.exists() {
-                let read = fs::read(texture_path).await.explain(format!(
-                    "Unable to read texture {:?} for {:?}",
-                    &texture, &entry
+                let read = fs::read(&texture_path).await.explain(format!(
+                    "Unable to read texture {texture:?} for {entry:?}"
                 ))?;
 
                 if is_important_texture && !config.validate_png_data(&read) {
@@ -375,7 +379,7 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
                         RenderRequestEntry,
                         ResolvedRenderEntryTextures,
                         ModelCacheConfiguration,
-                        [u8; 1],
+                        Vec<u8>,
                         Self,
                     >::invalidate_self(entry, base)
                     .await?;
@@ -383,11 +387,19 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
                     return Ok(None);
                 }
 
-                textures.insert(texture, MojangTexture::new_unnamed(read));
-            } else if !is_important_texture {
-                // If we haven't found a cached texture for an important texture, then we just skip
-                continue;
-            } else {
+                // Textures with a hash link to the texture cache, where they're named after it
+                let hash = fs::read_link(&texture_path)
+                    .await
+                    .ok()
+                    .and_then(|target| Some(target.file_name()?.to_str()?.to_owned()));
+
+                let texture_data = match hash {
+                    Some(hash) => MojangTexture::new_named(hash, read),
+                    None => MojangTexture::new_unnamed(read),
+                };
+
+                textures.insert(texture, texture_data);
+            } else if is_important_texture {
                 trace!(
                     "Unable to find texture path for important texture {}",
                     texture_path.display()
@@ -398,7 +410,7 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
                     RenderRequestEntry,
                     ResolvedRenderEntryTextures,
                     ModelCacheConfiguration,
-                    [u8; 1],
+                    Vec<u8>,
                     Self,
                 >::invalidate_self(entry, base)
                 .await?;
@@ -416,16 +428,16 @@ impl CacheHandler<RenderRequestEntry, ResolvedRenderEntryTextures, ModelCacheCon
         entry: &RenderRequestEntry,
         _config: &ModelCacheConfiguration,
         marker: &Path,
-    ) -> Result<[u8; 1]> {
+    ) -> Result<Vec<u8>> {
         let result = fs::read(marker)
             .await
             .explain(format!("Unable to read marker file for {entry:?}"))?;
 
-        if result.len() != 1 {
+        if result.is_empty() {
             return Err(ModelCacheError::MarkerMetadataError(entry.clone()).into());
         }
 
-        Ok([result[0]])
+        Ok(result)
     }
 
     async fn write_marker(
@@ -1,9 +1,9 @@
 use derive_more::Debug;
 use indoc::formatdoc;
-use nmsr_rendering::high_level::model::PlayerModel;
+use nmsr_rendering::high_level::model::{mob::HumanoidMob, PlayerModel};
 use serde::{Deserialize, Serialize};
-use std::sync::OnceLock;
-use strum::{Display, EnumCount, EnumString, FromRepr};
+use std::{fmt, str::FromStr, sync::OnceLock};
+use strum::{Display, EnumCount, EnumIter, EnumMessage, EnumString, FromRepr};
 use uuid::Uuid;
 
 use crate::error::{RenderRequestError, RenderRequestResult};
@@ -94,7 +94,7 @@ impl TryFrom<Vec<u8>> for RenderRequestEntry {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, FromRepr, Display, EnumString, EnumCount, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, FromRepr, Display, EnumString, EnumCount, EnumIter, EnumMessage, PartialEq, Eq)]
 pub enum RenderRequestEntryModel {
     #[default]
     #[strum(serialize = "steve", serialize = "wide")]
@@ -111,3 +111,38 @@ impl From<RenderRequestEntryModel> for PlayerModel {
         }
     }
 }
+
+/// The model requested for an entry, which is either the model of the player or a mob wearing the armor instead.
+///
+/// Mobs are kept apart from [`RenderRequestEntryModel`], since that one is the model of the entry itself (and is
+/// stored alongside its cached textures).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderRequestModel {
+    Player(RenderRequestEntryModel),
+    Mob(HumanoidMob),
+}
+
+impl From<RenderRequestEntryModel> for RenderRequestModel {
+    fn from(value: RenderRequestEntryModel) -> Self {
+        Self::Player(value)
+    }
+}
+
+impl FromStr for RenderRequestModel {
+    type Err = strum::ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        RenderRequestEntryModel::from_str(value)
+            .map(Self::Player)
+            .or_else(|_| HumanoidMob::from_str(value).map(Self::Mob))
+    }
+}
+
+impl fmt::Display for RenderRequestModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Player(model) => fmt::Display::fmt(model, f),
+            Self::Mob(mob) => fmt::Display::fmt(mob, f),
+        }
+    }
+}
@@ -5,10 +5,17 @@ use nmsr_rendering::{
     high_level::{
         camera::Camera,
         pipeline::scene::{Size, SunInformation},
+        animation::PlayerAnimation,
+        cape::CapeState,
+        model::{mob::HumanoidMob, ElytraState},
+        parts::geometry::BedrockGeometry,
+        pose::PlayerPose,
+        types::PlayerBodyPartType,
     },
     low_level::{EulerRot, Quat, Vec3},
 };
-use strum::{Display, EnumString};
+use std::sync::Arc;
+use strum::{Display, EnumMessage, EnumString};
 
 use self::entry::{RenderRequestEntry, RenderRequestEntryModel};
 
@@ -18,9 +25,11 @@ mod mode;
 
 pub use mode::*;
 
-use super::armor::VanillaMinecraftArmorMaterialData;
+use super::armor::{
+    item::HeldItem, UploadedArmorLayers, VanillaMinecraftArmorMaterialData, VanillaMinecraftHelmetData,
+};
 
-#[derive(EnumSetType, EnumString, Debug, Display)]
+#[derive(EnumSetType, EnumString, Debug, Display, EnumMessage)]
 #[strum(serialize_all = "snake_case")]
 #[enumset(serialize_repr = "array")]
 pub enum RenderRequestFeatures {
@@ -41,7 +50,12 @@ pub enum RenderRequestFeatures {
     Custom,
     #[cfg(feature = "ears")]
     Ears,
-    OptifineCape
+    /// The pixels of Ears skins using a color of their emissive palette, which are rendered unlit.
+    #[cfg(feature = "ears")]
+    #[strum(serialize = "ears_emissive", serialize = "emissive")]
+    EarsEmissive,
+    OptifineCape,
+    FlatLayers,
 }
 
 #[derive(Debug, Clone, PartialEq, Default, IsEmpty)]
@@ -60,10 +74,39 @@ pub struct RenderRequestExtraSettings {
     pub y_pos: Option<f32>,
     pub z_pos: Option<f32>,
 
-    pub helmet: Option<VanillaMinecraftArmorMaterialData>,
+    pub helmet: Option<VanillaMinecraftHelmetData>,
     pub chestplate: Option<VanillaMinecraftArmorMaterialData>,
     pub leggings: Option<VanillaMinecraftArmorMaterialData>,
     pub boots: Option<VanillaMinecraftArmorMaterialData>,
+    pub elytra: Option<ElytraState>,
+    #[debug(skip)]
+    pub uploaded_armor: Option<Arc<UploadedArmorLayers>>,
+
+    pub main_hand: Option<HeldItem>,
+    pub off_hand: Option<HeldItem>,
+
+    /// The text of the name tag shown above the head, which is the name of the profile of the entry if empty.
+    pub nametag: Option<String>,
+
+    /// The strength of the glow around the emissive parts of Ears skins.
+    pub glow: Option<f32>,
+
+    pub cape_angle: Option<f32>,
+    pub cape_wind: Option<f32>,
+
+    pub body_parts: Option<Vec<PlayerBodyPartType>>,
+    pub exploded_view_offset: Option<f32>,
+
+    pub pose: Option<PlayerPose>,
+
+    pub animation: Option<PlayerAnimation>,
+    pub animation_frames: Option<u32>,
+    /// The time of the render in ticks, which is where the animation starts from.
+    pub time: Option<f32>,
+
+    #[debug(skip)]
+    pub geometry: Option<Arc<BedrockGeometry>>,
+    pub mob: Option<HumanoidMob>,
 }
 
 impl RenderRequestExtraSettings {
@@ -178,13 +221,7 @@ impl RenderRequest {
 
             let mut distance = settings.distance.unwrap_or_default();
 
-            if !self.mode.is_isometric()
-                && settings
-                    .helmet
-                    .as_ref()
-                    .or(settings.boots.as_ref())
-                    .is_some()
-            {
+            if !self.mode.is_isometric() && (settings.helmet.is_some() || settings.boots.is_some()) {
                 distance += 2.0;
             }
 
@@ -251,10 +288,94 @@ impl RenderRequest {
             if let Some(rotation) = settings.arm_rotation {
                 return rotation;
             }
+
+            // The default pose of mobs already places their arms
+            if settings.mob.is_some() {
+                return 0.0;
+            }
         }
         self.mode.get_arm_rotation()
     }
 
+    /// Get the body parts to render, which are either the ones requested or the ones used by the mode.
+    pub(crate) fn get_body_parts(&self) -> Vec<PlayerBodyPartType> {
+        self.extra_settings
+            .as_ref()
+            .and_then(|settings| settings.body_parts.clone())
+            .unwrap_or_else(|| self.mode.get_body_parts())
+    }
+
+    pub(crate) fn get_exploded_view_offset(&self) -> Option<f32> {
+        self.extra_settings
+            .as_ref()
+            .and_then(|settings| settings.exploded_view_offset)
+    }
+
+    pub(crate) fn get_elytra(&self) -> Option<ElytraState> {
+        self.extra_settings
+            .as_ref()
+            .and_then(|settings| settings.elytra)
+    }
+
+    pub(crate) fn get_mob(&self) -> Option<HumanoidMob> {
+        self.extra_settings.as_ref().and_then(|settings| settings.mob)
+    }
+
+    pub(crate) fn get_geometry(&self) -> Option<Arc<BedrockGeometry>> {
+        self.extra_settings
+            .as_ref()
+            .and_then(|settings| settings.geometry.clone())
+    }
+
+    pub(crate) fn get_uploaded_armor(&self) -> Option<Arc<UploadedArmorLayers>> {
+        self.extra_settings
+            .as_ref()
+            .and_then(|settings| settings.uploaded_armor.clone())
+    }
+
+    pub(crate) fn get_cape_state(&self) -> CapeState {
+        let Some(settings) = &self.extra_settings else {
+            return CapeState::default();
+        };
+
+        CapeState {
+            angle: settings.cape_angle.unwrap_or(CapeState::DEFAULT_ANGLE),
+            wind: settings.cape_wind,
+        }
+    }
+
+    #[cfg(feature = "ears")]
+    pub(crate) fn get_glow(&self) -> Option<f32> {
+        self.extra_settings
+            .as_ref()
+            .and_then(|settings| settings.glow)
+            .filter(|strength| *strength > 0.0)
+    }
+
+    pub(crate) fn get_pose(&self) -> Option<PlayerPose> {
+        self.extra_settings
+            .as_ref()
+            .and_then(|settings| settings.pose)
+    }
+
+    /// Get the animation to render, along with the amount of frames to render it with.
+    pub(crate) fn get_animation(&self) -> Option<(&PlayerAnimation, u32)> {
+        let settings = self.extra_settings.as_ref()?;
+
+        settings
+            .animation
+            .as_ref()
+            .zip(settings.animation_frames)
+    }
+
+    /// Get the time of the render in ticks.
+    pub(crate) fn get_time(&self) -> f32 {
+        self.extra_settings
+            .as_ref()
+            .and_then(|settings| settings.time)
+            .unwrap_or_default()
+    }
+
     pub(crate) fn get_shadow_y_pos(&self) -> Option<f32> {
         if self.features.contains(RenderRequestFeatures::Shadow) {
             if self.mode.is_head() || self.mode.is_head_iso() {
@@ -280,13 +401,15 @@ impl RenderRequest {
                 .remove(RenderRequestFeatures::UnProcessedSkin);
         }
 
-        // If we're rendering just the head or face, remove the armor except for the helmet
+        // If we're rendering just the head or face, remove the armor except for the helmet, and the held items
         // And remove some extra features we know aren't targeting the head
         if request.mode.is_head_or_face() {
             if let Some(extra_settings) = request.extra_settings.as_mut() {
                 extra_settings.chestplate = None;
                 extra_settings.leggings = None;
                 extra_settings.boots = None;
+                extra_settings.main_hand = None;
+                extra_settings.off_hand = None;
             }
 
             request.features.remove(RenderRequestFeatures::BodyLayers);
@@ -294,6 +417,24 @@ impl RenderRequest {
             request.features.remove(RenderRequestFeatures::OptifineCape);
         }
         
+        // If we're rendering just the cape, there's no player to wear armor or an elytra, to hold items, to cast a shadow
+        // or to show a name tag above, and excluding the cape itself makes no sense
+        if request.mode.is_cape() {
+            if let Some(extra_settings) = request.extra_settings.as_mut() {
+                extra_settings.helmet = None;
+                extra_settings.chestplate = None;
+                extra_settings.leggings = None;
+                extra_settings.boots = None;
+                extra_settings.elytra = None;
+                extra_settings.main_hand = None;
+                extra_settings.off_hand = None;
+                extra_settings.nametag = None;
+            }
+
+            request.features.remove(RenderRequestFeatures::Shadow);
+            request.features.insert(RenderRequestFeatures::Cape);
+        }
+
         // If the request is custom, we add the custom feature, otherwise we remove it
         if request.mode.is_custom() {
             request.features.insert(RenderRequestFeatures::Custom);
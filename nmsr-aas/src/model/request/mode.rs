@@ -1,4 +1,3 @@
-use core::fmt::Debug;
 use std::f32::consts::FRAC_1_SQRT_2;
 
 use nmsr_rendering::high_level::{
@@ -6,12 +5,12 @@ use nmsr_rendering::high_level::{
     pipeline::scene::Size,
     types::PlayerBodyPartType,
 };
-use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+use strum::{Display, EnumIter, EnumMessage, EnumString, IntoEnumIterator};
 use tracing::instrument;
 
 use crate::error::{RenderRequestError, Result};
 
-#[derive(EnumString, Debug, PartialEq, Eq, Clone, Copy, EnumIter, Display)]
+#[derive(EnumString, Debug, PartialEq, Eq, Clone, Copy, EnumIter, Display, EnumMessage)]
 #[strum(serialize_all = "snake_case")]
 pub enum RenderRequestMode {
     #[strum(serialize = "skin", serialize = "texture")]
@@ -32,6 +31,9 @@ pub enum RenderRequestMode {
     FullBodyIso,
     #[strum(serialize = "head_iso", serialize = "headiso")]
     HeadIso,
+    Cape,
+    #[strum(serialize = "cape_flat", serialize = "capeflat")]
+    CapeFlat,
     Custom,
 }
 
@@ -48,7 +50,12 @@ impl RenderRequestMode {
     pub(crate) const fn is_isometric(self) -> bool {
         matches!(
             self,
-            Self::FullBodyIso | Self::HeadIso | Self::FrontBust | Self::FrontFull | Self::Face
+            Self::FullBodyIso
+                | Self::HeadIso
+                | Self::FrontBust
+                | Self::FrontFull
+                | Self::Face
+                | Self::CapeFlat
         )
     }
 
@@ -63,7 +70,7 @@ impl RenderRequestMode {
     pub(crate) const fn is_arms_open(self) -> bool {
         matches!(
             self,
-            Self::FullBody | Self::BodyBust | RenderRequestMode::BlockbenchExport
+            Self::FullBody | Self::BodyBust | Self::BlockbenchExport
         )
     }
 
@@ -82,8 +89,12 @@ impl RenderRequestMode {
         matches!(self, Self::Face)
     }
 
+    pub(crate) const fn is_cape(self) -> bool {
+        matches!(self, Self::Cape | Self::CapeFlat)
+    }
+
     pub(crate) const fn is_square(self) -> bool {
-        self.is_bust() || self.is_head_or_face()
+        self.is_bust() || self.is_head_or_face() || self.is_cape()
     }
 
     pub(crate) const fn is_skin(self) -> bool {
@@ -118,30 +129,28 @@ impl RenderRequestMode {
         }
     }
 
-    #[allow(unused_variables)]
-    pub fn validate_unit<T: PartialOrd + Debug + Copy>(
+    pub fn validate_unit<T: PartialOrd + Copy + Into<serde_json::Value>>(
         unit: &'static str,
         value: Option<T>,
         min: T,
         max: T,
     ) -> Result<()> {
-        let check = value
-            .filter(|value| *value < min || *value > max)
-            .map(|_| (unit, format!("between {min:?} and {max:?}")));
-
-        if let Some((unit, bounds)) = check {
-            return Err(RenderRequestError::InvalidRenderSettingError(unit, bounds).into());
+        if value.is_some_and(|value| value < min || value > max) {
+            return Err(RenderRequestError::InvalidRenderSettingBoundsError(
+                unit,
+                min.into(),
+                max.into(),
+            )
+            .into());
         }
 
         Ok(())
     }
 
-    pub(crate) fn wrap_unit(value: Option<&mut f32>, min: f32, max: f32) -> Result<()> {
+    pub(crate) fn wrap_unit(value: Option<&mut f32>, min: f32, max: f32) {
         if let Some(value) = value {
-            *value = min + (*value - min + (max - min)) % (max - min)
+            *value = min + (*value - min + (max - min)) % (max - min);
         }
-        
-        return Ok(())
     }
 
     pub(crate) const fn get_base_render_mode(self) -> Option<Self> {
@@ -189,11 +198,17 @@ impl RenderRequestMode {
         if self.is_head_or_face() {
             look_at_y += 11.5;
         }
+        if self.is_cape() {
+            look_at_y -= 0.5;
+        }
 
         let mut distance = 45.0;
         if self.is_head_or_face() {
             distance -= 20.0;
         }
+        if self.is_cape() {
+            distance -= 10.0;
+        }
         if self.is_head() {
             distance -= 6.0;
         }
@@ -209,12 +224,16 @@ impl RenderRequestMode {
                 aspect -= 3.0;
             }
 
+            if self.is_cape() {
+                aspect -= 5.0;
+            }
+
             ProjectionParameters::Orthographic { aspect }
         } else {
             ProjectionParameters::Perspective { fov: 45.0 }
         };
 
-        let rotation = if self.is_front() || self.is_custom() {
+        let rotation = if self.is_front() || self.is_custom() || matches!(self, Self::CapeFlat) {
             CameraRotation {
                 yaw: 0.0,
                 pitch: 0.0,
@@ -252,7 +271,7 @@ impl RenderRequestMode {
     }
 
     #[instrument(level = "trace", skip(self))]
-    pub(crate) fn get_body_parts(&self) -> Vec<PlayerBodyPartType> {
+    pub(crate) fn get_body_parts(self) -> Vec<PlayerBodyPartType> {
         match self {
             Self::Custom | Self::FullBody | Self::FrontFull | Self::FullBodyIso => {
                 PlayerBodyPartType::iter().collect()
@@ -267,6 +286,8 @@ impl RenderRequestMode {
                     .filter(|m| !excluded.contains(&m.get_non_layer_part()))
                     .collect()
             }
+            // Capes are rendered on their own, without any of the player's body parts
+            Self::Cape | Self::CapeFlat => vec![],
             Self::Skin | Self::BlockbenchExport => unreachable!(),
         }
     }
@@ -2,6 +2,7 @@ use self::{
     geyser::resolve_geyser_uuid_to_texture_and_model,
     mojang::{client::MojangClient, model::GameProfileTexture},
 };
+use super::armor::VanillaMinecraftHelmetData;
 use super::request::{
     cache::ModelCache,
     entry::{RenderRequestEntry, RenderRequestEntryModel},
@@ -12,10 +13,12 @@ use derive_more::Debug;
 #[cfg(feature = "ears")]
 use ears_rs::{alfalfa::AlfalfaDataKey, features::EarsFeatures, parser::EarsParser};
 #[cfg(feature = "ears")]
+use image::{GenericImageView, Pixel, RgbaImage};
+#[cfg(feature = "ears")]
 use nmsr_rendering::high_level::parts::provider::ears::PlayerPartEarsTextureType;
-use nmsr_rendering::high_level::types::PlayerPartTextureType;
-use std::{collections::HashMap, sync::Arc};
-use image::{ImageBuffer, ImageEncoder, ImageFormat, Rgba};
+use nmsr_rendering::high_level::{model::headwear::Headwear, types::PlayerPartTextureType};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use image::{ImageBuffer, ImageEncoder, Rgba};
 use strum::EnumCount;
 use tracing::{instrument, trace_span, Instrument, Span};
 
@@ -33,7 +36,9 @@ pub enum ResolvedRenderEntryTextureType {
     Skin,
     #[cfg(feature = "ears")]
     Ears(ResolvedRenderEntryEarsTextureType),
-    OptifineCape
+    OptifineCape,
+    /// The skin of the player whose head is worn in the helmet slot.
+    PlayerHead,
 }
 
 impl From<ResolvedRenderEntryTextureType> for &'static str {
@@ -43,7 +48,8 @@ impl From<ResolvedRenderEntryTextureType> for &'static str {
             ResolvedRenderEntryTextureType::Skin => "Skin",
             #[cfg(feature = "ears")]
             ResolvedRenderEntryTextureType::Ears(ears) => ears.key(),
-            ResolvedRenderEntryTextureType::OptifineCape => "OptifineCape"
+            ResolvedRenderEntryTextureType::OptifineCape => "OptifineCape",
+            ResolvedRenderEntryTextureType::PlayerHead => "PlayerHead",
         }
     }
 }
@@ -67,13 +73,26 @@ impl From<ResolvedRenderEntryEarsTextureType> for PlayerPartEarsTextureType {
     }
 }
 
+/// Where Ears keeps a texture in the skin.
+#[cfg(feature = "ears")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EarsTextureSource {
+    /// The texture is a PNG stored in the Alfalfa data of the skin, under the given key.
+    Alfalfa(AlfalfaDataKey),
+    /// The texture is made of the pixels of the skin that use one of the colors of its emissive palette.
+    EmissivePalette,
+}
+
 #[cfg(feature = "ears")]
 impl ResolvedRenderEntryEarsTextureType {
-    const fn alfalfa_key(self) -> Option<AlfalfaDataKey> {
+    /// The position and size of the emissive palette in the skin, in the unused corner of the right arm layer.
+    const EMISSIVE_PALETTE: (u32, u32, u32, u32) = (52, 32, 4, 4);
+
+    const fn source(self) -> EarsTextureSource {
         match self {
-            Self::Cape => Some(AlfalfaDataKey::Cape),
-            Self::Wings => Some(AlfalfaDataKey::Wings),
-            Self::Emissive => None,
+            Self::Cape => EarsTextureSource::Alfalfa(AlfalfaDataKey::Cape),
+            Self::Wings => EarsTextureSource::Alfalfa(AlfalfaDataKey::Wings),
+            Self::Emissive => EarsTextureSource::EmissivePalette,
         }
     }
 
@@ -85,7 +104,7 @@ impl ResolvedRenderEntryEarsTextureType {
 impl From<ResolvedRenderEntryTextureType> for PlayerPartTextureType {
     fn from(value: ResolvedRenderEntryTextureType) -> Self {
         match value {
-            ResolvedRenderEntryTextureType::Skin => Self::Skin,
+            ResolvedRenderEntryTextureType::Skin => Self::VANILLA_SKIN,
             ResolvedRenderEntryTextureType::Cape => Self::Cape,
             #[cfg(feature = "ears")]
             ResolvedRenderEntryTextureType::Ears(ResolvedRenderEntryEarsTextureType::Cape) => {
@@ -98,6 +117,7 @@ impl From<ResolvedRenderEntryTextureType> for PlayerPartTextureType {
             ResolvedRenderEntryTextureType::OptifineCape => {
                 Self::Cape
             }
+            ResolvedRenderEntryTextureType::PlayerHead => Headwear::PlayerHead.get_texture(),
         }
     }
 }
@@ -108,13 +128,13 @@ pub struct MojangTexture {
 }
 
 impl MojangTexture {
-    pub(crate) fn new_named(hash: String, data: Vec<u8>) -> Self {
+    pub(crate) const fn new_named(hash: String, data: Vec<u8>) -> Self {
         Self {
             hash: Some(hash),
             data,
         }
     }
-    pub(crate) fn new_unnamed(data: Vec<u8>) -> Self {
+    pub(crate) const fn new_unnamed(data: Vec<u8>) -> Self {
         Self { hash: None, data }
     }
 
@@ -131,6 +151,8 @@ impl MojangTexture {
 
 pub struct ResolvedRenderEntryTextures {
     pub model: Option<RenderRequestEntryModel>,
+    /// The name of the profile of the entry, for entries that have one.
+    pub name: Option<String>,
     pub textures: HashMap<ResolvedRenderEntryTextureType, MojangTexture>,
 }
 
@@ -153,32 +175,42 @@ impl ResolvedRenderEntryTextures {
     pub const fn new(
         textures: HashMap<ResolvedRenderEntryTextureType, MojangTexture>,
         model: Option<RenderRequestEntryModel>,
+        name: Option<String>,
     ) -> Self {
-        Self { model, textures }
+        Self { model, name, textures }
     }
 
+    /// Create the resolved textures from the marker of their cache entry, which is the model followed by the name of
+    /// the profile (if any).
     #[must_use]
     pub fn new_from_marker_slice(
         textures: HashMap<ResolvedRenderEntryTextureType, MojangTexture>,
         marker: &[u8],
     ) -> Self {
         let model = RenderRequestEntryModel::from_repr(marker[0] as usize);
+        let name = String::from_utf8(marker[1..].to_vec())
+            .ok()
+            .filter(|name| !name.is_empty());
 
-        Self { model, textures }
+        Self { model, name, textures }
     }
 
     #[must_use]
-    pub fn to_marker_slice(&self) -> [u8; 1] {
+    pub fn to_marker_slice(&self) -> Vec<u8> {
         let model = self
             .model
             .map_or(RenderRequestEntryModel::COUNT as u8, |m| m as u8);
 
-        [model]
+        let mut marker = vec![model];
+        marker.extend_from_slice(self.name.as_deref().unwrap_or_default().as_bytes());
+
+        marker
     }
 }
 
 impl RenderRequestResolver {
-    pub fn new(model_cache: ModelCache, client: Arc<MojangClient>) -> Self {
+    #[must_use]
+    pub const fn new(model_cache: ModelCache, client: Arc<MojangClient>) -> Self {
         Self {
             model_cache,
             mojang_requests_client: client,
@@ -245,6 +277,7 @@ impl RenderRequestResolver {
         }
 
         let model: Option<RenderRequestEntryModel>;
+        let mut name: Option<String> = None;
         let skin_texture: Option<MojangTexture>;
         let cape_texture: Option<MojangTexture>;
         let optifine_cape_texture: Option<MojangTexture>;
@@ -252,7 +285,7 @@ impl RenderRequestResolver {
         match &entry {
             RenderRequestEntry::MojangPlayerUuid(id) | RenderRequestEntry::MojangOfflinePlayerUuid(id) => {
                 if matches!(&entry, RenderRequestEntry::MojangOfflinePlayerUuid(_)) && !self.mojang_requests_client.mojank_config().allow_offline_mode_uuids {
-                    return Err(RenderRequestError::InvalidPlayerUuidRequest(
+                    Err(RenderRequestError::InvalidPlayerUuidRequest(
                         id.to_string(),
                         id.get_version_num(),
                     ))?;                    
@@ -266,13 +299,13 @@ impl RenderRequestResolver {
                 
                 let textures = result.textures()?;
 
-                let name = textures.name().unwrap();
+                let profile_name = textures.name().unwrap();
 
-                //println!("Optifine Request User: {}", name);
+                //println!("Optifine Request User: {}", profile_name);
 
                 let has_optifine_cape = self
                     .mojang_requests_client
-                    .check_optifine_cape_status(name)
+                    .check_optifine_cape_status(profile_name)
                     .await
                     .unwrap();
 
@@ -292,11 +325,13 @@ impl RenderRequestResolver {
                 skin_texture = self.fetch_game_profile_texture(textures.skin(), MojangTextureRequestType::Skin).await?;
                 cape_texture = self.fetch_game_profile_texture(cape, MojangTextureRequestType::Cape).await?;
                 if has_optifine_cape {
-                    let texture_id = format!("OptifineCapeTexture_{}", name);
+                    let texture_id = format!("OptifineCapeTexture_{profile_name}");
                     optifine_cape_texture = Some(self.fetch_texture_from_mojang(&texture_id, MojangTextureRequestType::OptifineCape).await?);
                 } else {
                     optifine_cape_texture = None;
                 }
+
+                name = Some(profile_name.to_owned());
             }
             RenderRequestEntry::GeyserPlayerUuid(id) => {
                 let (texture_id, player_model) =
@@ -341,7 +376,7 @@ impl RenderRequestResolver {
             textures.insert(ResolvedRenderEntryTextureType::Skin, skin_texture);
         }
 
-        let result = ResolvedRenderEntryTextures::new(textures, model);
+        let result = ResolvedRenderEntryTextures::new(textures, model, name);
 
         self.model_cache
             .cache_resolved_texture(entry, &result)
@@ -350,6 +385,17 @@ impl RenderRequestResolver {
         Ok(result)
     }
 
+    #[instrument(skip(self))]
+    async fn resolve_cape_hash_textures(&self, cape_hash: &str) -> Result<ResolvedRenderEntryTextures> {
+        let cape_texture = self
+            .fetch_texture_from_mojang(cape_hash, MojangTextureRequestType::Cape)
+            .await?;
+
+        let textures = HashMap::from([(ResolvedRenderEntryTextureType::Cape, cape_texture)]);
+
+        Ok(ResolvedRenderEntryTextures::new(textures, None, None))
+    }
+
     #[cfg(feature = "ears")]
     fn resolve_ears_textures(
         skin_texture: &MojangTexture,
@@ -366,53 +412,105 @@ impl RenderRequestResolver {
             let features = EarsParser::parse(&image).ok().flatten();
             let alfalfa = ears_rs::alfalfa::read_alfalfa(&image).ok().flatten();
 
-            if let Some(alfalfa) = alfalfa {
-                for texture_type in &[
-                    ResolvedRenderEntryEarsTextureType::Cape,
-                    ResolvedRenderEntryEarsTextureType::Wings,
-                ] {
-                    if let Some(alfalfa_key) = texture_type.alfalfa_key() {
-                        if let Some(data) = alfalfa.get_data(alfalfa_key) {
-                            let hash = format!("{:x}", xxh3_128(data));
-
-                            let data = if alfalfa_key == AlfalfaDataKey::Cape {
-                                let image = image::load_from_memory(data)
-                                    .map(DynamicImage::into_rgba8)
-                                    .map(ears_rs::utils::convert_ears_cape_to_mojang_cape)
-                                    .ok()
-                                    .and_then(|i| {
-                                        create_png_from_bytes((i.width(), i.height()), &i).ok()
-                                    });
-
-                                image.map_or(Cow::Borrowed(data), Cow::Owned)
-                            } else {
-                                Cow::Borrowed(data)
-                            };
-
-                            textures.insert(
-                                ResolvedRenderEntryTextureType::Ears(*texture_type),
-                                MojangTexture::new_named(hash, data.into_owned()),
-                            );
+            for texture_type in [
+                ResolvedRenderEntryEarsTextureType::Cape,
+                ResolvedRenderEntryEarsTextureType::Wings,
+                ResolvedRenderEntryEarsTextureType::Emissive,
+            ] {
+                let data = match texture_type.source() {
+                    EarsTextureSource::Alfalfa(alfalfa_key) => {
+                        let Some(data) = alfalfa.as_ref().and_then(|alfalfa| alfalfa.get_data(alfalfa_key)) else {
+                            continue;
+                        };
+
+                        if alfalfa_key == AlfalfaDataKey::Cape {
+                            let image = image::load_from_memory(data)
+                                .map(DynamicImage::into_rgba8)
+                                .map(ears_rs::utils::convert_ears_cape_to_mojang_cape)
+                                .ok()
+                                .and_then(|i| {
+                                    create_png_from_bytes((i.width(), i.height()), &i).ok()
+                                });
+
+                            image.map_or(Cow::Borrowed(data), Cow::Owned)
+                        } else {
+                            Cow::Borrowed(data)
                         }
                     }
-                }
+                    EarsTextureSource::EmissivePalette => {
+                        if !features.as_ref().is_some_and(|features| features.emissive) {
+                            continue;
+                        }
+
+                        let Some(emissive) = Self::extract_emissive_texture(&image)
+                            .and_then(|i| create_png_from_bytes((i.width(), i.height()), &i).ok())
+                        else {
+                            continue;
+                        };
+
+                        Cow::Owned(emissive)
+                    }
+                };
+
+                let hash = format!("{:x}", xxh3_128(&data));
+
+                textures.insert(
+                    ResolvedRenderEntryTextureType::Ears(texture_type),
+                    MojangTexture::new_named(hash, data.into_owned()),
+                );
             }
 
             features
         })
     }
 
+    /// The pixels of the skin that use one of the colors of its emissive palette, on an otherwise transparent texture.
+    ///
+    /// Nothing is extracted if the palette is empty, or if the skin doesn't have the vanilla layout Ears needs.
+    #[cfg(feature = "ears")]
+    fn extract_emissive_texture(skin: &RgbaImage) -> Option<RgbaImage> {
+        let (x, y, width, height) = ResolvedRenderEntryEarsTextureType::EMISSIVE_PALETTE;
+
+        if skin.dimensions() != PlayerPartEarsTextureType::Emissive.size() {
+            return None;
+        }
+
+        let palette: Vec<_> = skin
+            .view(x, y, width, height)
+            .pixels()
+            .filter(|(_, _, pixel)| pixel[3] > 0)
+            .map(|(_, _, pixel)| pixel.to_rgb())
+            .collect();
+
+        if palette.is_empty() {
+            return None;
+        }
+
+        let mut emissive = RgbaImage::new(skin.width(), skin.height());
+
+        for (pixel_x, pixel_y, pixel) in skin.enumerate_pixels() {
+            let is_palette = (x..x + width).contains(&pixel_x) && (y..y + height).contains(&pixel_y);
+
+            if !is_palette && pixel[3] > 0 && palette.contains(&pixel.to_rgb()) {
+                emissive.put_pixel(pixel_x, pixel_y, *pixel);
+            }
+        }
+
+        Some(emissive)
+    }
+
     pub async fn resolve(&self, request: &RenderRequest) -> Result<ResolvedRenderRequest> {
         // First, we need to resolve the skin and cape textures.
-        let resolved_textures = self
-            .resolve_entry_textures(&request.entry)
-            .await
-            .map_err(|e| {
-                MojangRequestError::UnableToResolveRenderRequestEntity(
-                    Box::new(e),
-                    request.entry.clone(),
-                )
-            })?;
+        let resolved_textures = match &request.entry {
+            // Texture hashes are skins everywhere but in the cape modes, where they're the cape to render.
+            RenderRequestEntry::TextureHash(cape_hash) if request.mode.is_cape() => {
+                self.resolve_cape_hash_textures(cape_hash).await
+            }
+            entry => self.resolve_entry_textures(entry).await,
+        }
+        .map_err(|e| {
+            MojangRequestError::UnableToResolveRenderRequestEntity(Box::new(e), request.entry.clone())
+        })?;
 
         let final_model = request
             .model
@@ -425,12 +523,44 @@ impl RenderRequestResolver {
             textures.insert(texture_type, texture.data);
         }
 
+        // The head of another player worn in the helmet slot is textured with their skin
+        let player_head = request
+            .extra_settings
+            .as_ref()
+            .and_then(|settings| settings.helmet.as_ref());
+
+        if let Some(VanillaMinecraftHelmetData::PlayerHead(entry)) = player_head {
+            let mut head_textures = self.resolve_entry_textures(entry).await.map_err(|e| {
+                MojangRequestError::UnableToResolveRenderRequestEntity(Box::new(e), entry.clone())
+            })?;
+
+            if let Some(skin) = head_textures.textures.remove(&ResolvedRenderEntryTextureType::Skin) {
+                textures.insert(ResolvedRenderEntryTextureType::PlayerHead, skin.data);
+            }
+        }
+
         Ok(ResolvedRenderRequest {
             model: final_model,
+            profile_name: resolved_textures.name,
             textures,
         })
     }
 
+    /// Resolve the textures of an entry on their own, along with how long ago they were cached, to inspect the entry
+    /// without rendering it.
+    pub async fn resolve_entry(
+        &self,
+        entry: &RenderRequestEntry,
+    ) -> Result<(ResolvedRenderEntryTextures, Option<Duration>)> {
+        let textures = self.resolve_entry_textures(entry).await.map_err(|e| {
+            MojangRequestError::UnableToResolveRenderRequestEntity(Box::new(e), entry.clone())
+        })?;
+
+        let cache_age = self.model_cache.get_resolved_texture_age(entry).await?;
+
+        Ok((textures, cache_age))
+    }
+
     #[inline]
     pub(crate) async fn do_cache_clean_up(&self) -> Result<()> {
         self.model_cache.do_cache_clean_up().await
@@ -440,6 +570,8 @@ impl RenderRequestResolver {
 #[derive(Debug, Clone)]
 pub struct ResolvedRenderRequest {
     pub model: RenderRequestEntryModel,
+    /// The name of the profile of the entry, for entries that have one.
+    pub profile_name: Option<String>,
     #[debug(skip)]
     pub textures: HashMap<ResolvedRenderEntryTextureType, Vec<u8>>,
 }
@@ -7,7 +7,7 @@ use crate::{
 };
 use hyper::{body::Bytes, Method};
 use std::sync::Arc;
-use tracing::Span;
+use tracing::{instrument, Span};
 use uuid::Uuid;
 
 pub struct MojangClient {
@@ -94,10 +94,12 @@ impl MojangClient {
         Ok(bytes.to_vec())
     }
 
+    #[must_use]
     pub fn mojank_config(&self) -> &MojankConfiguration {
         self.mojank_config.as_ref()
     }
 
+    #[allow(clippy::literal_string_with_formatting_args)] // The URL templates use the same placeholders
     fn build_request_url(&self, req_type: MojangTextureRequestType, texture_id: &str) -> String {
         let mojank = self.mojank_config();
 
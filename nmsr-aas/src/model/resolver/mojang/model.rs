@@ -37,7 +37,7 @@ impl GameProfileTexture {
     pub fn hash(&self) -> MojangRequestResult<&str> {
         self.url
             .split('/')
-            .last()
+            .next_back()
             .map(|s| s.strip_suffix(".png").unwrap_or(s))
             .ok_or_else(|| MojangRequestError::InvalidTextureUrlError(self.url.clone()))
     }
@@ -65,7 +65,7 @@ impl GameProfileTextures {
     }
 
     #[must_use]
-    pub fn name(&self) -> Option<&String> {
+    pub const fn name(&self) -> Option<&String> {
         Some(&self.profile_name)
     }
 }
@@ -9,21 +9,28 @@ use hyper::{
     header::{CONTENT_DISPOSITION, CONTENT_TYPE},
     Method,
 };
-use nmsr_rendering::high_level::{pipeline::{scene::Scene, SceneContextWrapper}, types::PlayerPartTextureType};
+use nmsr_rendering::high_level::{
+    model::{headwear::Headwear, item::PlayerHand},
+    pipeline::{scene::Scene, SceneContextWrapper},
+    types::PlayerPartTextureType,
+};
 use nmsr_rendering_blockbench_model_generator_experiment::{
     blockbench::generate_project,
     error::BlockbenchGeneratorError,
     generator::{ModelGenerationProject, ModelProjectImageIO},
 };
+use strum::IntoEnumIterator;
 use tracing::instrument;
 
 use crate::{
     error::Result,
     model::{
         armor::VanillaMinecraftArmorMaterialData,
+        nametag::render_nametag,
         request::{RenderRequest, RenderRequestFeatures},
+        resolver::ResolvedRenderEntryTextureType,
     },
-    routes::render_model::create_part_context,
+    routes::render_model::{create_part_context, get_held_items, get_nametag_text},
     utils::png::create_png_from_bytes,
 };
 
@@ -39,7 +46,7 @@ impl ModelProjectImageIO for NMSRaaSImageIO {
         image: &[u8],
     ) -> std::result::Result<image::RgbaImage, BlockbenchGeneratorError> {
         load_image(image).map_err(|e| {
-            BlockbenchGeneratorError::ExplainedError(format!("Failed to load png: {}", e))
+            BlockbenchGeneratorError::ExplainedError(format!("Failed to load png: {e}"))
         })
     }
 
@@ -47,8 +54,8 @@ impl ModelProjectImageIO for NMSRaaSImageIO {
         &self,
         image: &image::RgbaImage,
     ) -> std::result::Result<Vec<u8>, BlockbenchGeneratorError> {
-        create_png_from_bytes((image.width(), image.height()), &image).map_err(|e| {
-            BlockbenchGeneratorError::ExplainedError(format!("Failed to create png: {}", e))
+        create_png_from_bytes((image.width(), image.height()), image).map_err(|e| {
+            BlockbenchGeneratorError::ExplainedError(format!("Failed to create png: {e}"))
         })
     }
 }
@@ -70,16 +77,53 @@ pub(crate) async fn internal_bbmodel_export(
         .into_response());
     }
 
-    let mut part_context = create_part_context(&request, &resolved);
+    let nametag_font = state.armor_manager.get_nametag_font();
+    let mut part_context = create_part_context(&request, &resolved, nametag_font);
     
     if let Some(pos) = part_context.shadow_y_pos {
         part_context.shadow_y_pos = Some(pos - 0.01);
     }
+
+    // Blockbench doesn't have unlit textures, and the emissive pixels are in the skin already
+    #[cfg(feature = "ears")]
+    if let Some(features) = part_context.ears_features.as_mut() {
+        features.emissive = false;
+    }
     
     let mut textures = HashMap::new();
 
+    if let (Some(nametag), Some(text)) = (part_context.nametag, get_nametag_text(&request, &resolved)) {
+        textures.insert(nametag.get_texture(), render_nametag(text, nametag_font));
+    }
+
     for (texture_type, texture_bytes) in resolved.textures {
-        textures.insert(texture_type.into(), load_image(&texture_bytes)?);
+        #[cfg(feature = "ears")]
+        if texture_type
+            == ResolvedRenderEntryTextureType::Ears(
+                crate::model::resolver::ResolvedRenderEntryEarsTextureType::Emissive,
+            )
+        {
+            continue;
+        }
+
+        let mut texture = load_image(&texture_bytes)?;
+
+        if texture_type == ResolvedRenderEntryTextureType::PlayerHead {
+            texture = NMSRState::process_player_head(texture)?;
+        }
+
+        textures.insert(texture_type.into(), texture);
+    }
+
+    if part_context.elytra.is_some() && !part_context.has_cape {
+        textures.insert(
+            PlayerPartTextureType::VANILLA_ELYTRA,
+            state.armor_manager.get_elytra_texture()?,
+        );
+    }
+
+    if let Some(mob) = part_context.mob {
+        textures.insert(mob.get_texture(), state.armor_manager.get_mob_texture(mob)?);
     }
 
     if request.features.contains(RenderRequestFeatures::Shadow) {
@@ -89,8 +133,18 @@ pub(crate) async fn internal_bbmodel_export(
         );
     }
 
+    if let Some(headwear) = part_context.headwear.filter(|headwear| *headwear != Headwear::PlayerHead) {
+        textures.insert(headwear.get_texture(), state.armor_manager.get_headwear_texture(headwear)?);
+    }
+
+    for (hand, item) in PlayerHand::iter().zip(get_held_items(&request)) {
+        if let Some(item) = item {
+            textures.insert(hand.get_texture(item.model.texture_size), (*item.texture).clone());
+        }
+    }
+
     if let Some(slots) = &part_context.armor_slots {
-        let (armor_1, armor_2) = state.armor_manager.create_armor_texture(slots).await?;
+        let (armor_1, armor_2) = state.armor_manager.create_armor_texture(slots, request.get_uploaded_armor().as_deref())?;
 
         textures.insert(
             VanillaMinecraftArmorMaterialData::ARMOR_TEXTURE_ONE,
@@ -109,7 +163,7 @@ pub(crate) async fn internal_bbmodel_export(
         ModelGenerationProject::new_with_part_context(NMSRaaSImageIO, part_context);
 
     for (texture_type, mut texture) in textures {
-        if texture_type == PlayerPartTextureType::Skin {
+        if texture_type.is_skin() {
             texture = NMSRState::process_skin(texture, request.features)?;
         }
         
@@ -125,11 +179,10 @@ pub(crate) async fn internal_bbmodel_export(
         HeaderValue::from_static(APPLICATION_JSON_MIME),
     );
 
-    let entry_str = String::try_from(request.entry).unwrap_or("model".to_string());
+    let entry_str = String::try_from(request.entry).unwrap_or_else(|_| "model".to_string());
 
     if let Ok(value) = HeaderValue::from_str(&format!(
-        "attachment; filename={name}.bbmodel",
-        name = entry_str
+        "attachment; filename={entry_str}.bbmodel"
     )) {
         res.headers_mut().insert(CONTENT_DISPOSITION, value);
     }
@@ -0,0 +1,64 @@
+use super::{extractors::decode_base64, render::IMAGE_PNG_MIME};
+use crate::{
+    error::{RenderRequestError, Result},
+    model::ears::{encode_ears_features, EarsFeaturesDefinition},
+    utils::png::create_png_from_bytes,
+};
+use axum::{
+    extract::rejection::JsonRejection,
+    http::HeaderValue,
+    response::{IntoResponse, Response},
+    Json,
+};
+use hyper::header::CONTENT_TYPE;
+use nmsr_rendering::errors::NMSRRenderingError;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// The body of a request to encode Ears features into a skin.
+///
+/// The textures are encoded as base64, optionally wrapped in a `data:` URL, and the wings and cape are PNG files
+/// in the 20×16 layout Ears uses for them.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EarsEncodeRequest {
+    /// A 64x64 or legacy 64x32 skin.
+    #[schemars(extend("format" = "byte"))]
+    pub skin: String,
+    #[serde(default)]
+    pub features: EarsFeaturesDefinition,
+    /// The 20x16 wings texture, needed when the features have wings.
+    #[schemars(extend("format" = "byte"))]
+    pub wings: Option<String>,
+    /// The 20x16 cape texture, in the layout Ears uses for capes.
+    #[schemars(extend("format" = "byte"))]
+    pub cape: Option<String>,
+}
+
+/// Encode Ears features into a skin, returning the skin that Ears reads them back from.
+#[axum::debug_handler]
+pub async fn encode_ears(body: std::result::Result<Json<EarsEncodeRequest>, JsonRejection>) -> Result<Response> {
+    let Json(body) = body.map_err(RenderRequestError::from)?;
+
+    let skin = decode_base64(&body.skin).map_err(RenderRequestError::InvalidBase64Skin)?;
+    let skin = image::load_from_memory(&skin)
+        .map_err(NMSRRenderingError::ImageFromRawError)?
+        .into_rgba8();
+
+    let decode_texture = |name: &'static str, texture: Option<String>| {
+        texture
+            .map(|encoded| {
+                decode_base64(&encoded).map_err(|e| {
+                    RenderRequestError::InvalidEarsTextureError(name, format!("Invalid base64: {e}"))
+                })
+            })
+            .transpose()
+    };
+
+    let wings = decode_texture("wings", body.wings)?;
+    let cape = decode_texture("cape", body.cape)?;
+
+    let skin = encode_ears_features(skin, &body.features, wings, cape)?;
+    let png = create_png_from_bytes(skin.dimensions(), &skin)?;
+
+    Ok(([(CONTENT_TYPE, HeaderValue::from_static(IMAGE_PNG_MIME))], png).into_response())
+}
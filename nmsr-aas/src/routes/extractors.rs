@@ -1,23 +1,260 @@
 use super::{
-    query::{RenderRequestMultipartParams, RenderRequestQueryParams},
+    query::{RenderRequestJsonParams, RenderRequestQueryParams},
     RenderRequestValidator,
 };
 use crate::{
-    error::{NMSRaaSError, RenderRequestError, Result},
-    model::request::{
-        entry::RenderRequestEntry, RenderRequest, RenderRequestExtraSettings, RenderRequestMode,
+    error::{ArmorManagerError, NMSRaaSError, RenderRequestError, Result},
+    model::{
+        armor::{
+            item::HeldItem, UploadedArmorLayers, VanillaMinecraftArmorMaterialData,
+            VanillaMinecraftHelmetData,
+        },
+        geometry::BedrockSkin,
+        request::{
+            entry::{RenderRequestEntry, RenderRequestEntryModel},
+            RenderRequest, RenderRequestExtraSettings, RenderRequestMode,
+        },
     },
 };
 use async_trait::async_trait;
 use axum::{
-    extract::{FromRequest, Path, Query, Request},
-    RequestExt,
+    extract::{rejection::JsonRejection, FromRequest, FromRequestParts, Path, Query, Request},
+    http::request::Parts,
+    Json, RequestExt,
 };
 use axum_extra::extract::Multipart;
-use hyper::Method;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hyper::{header::CONTENT_TYPE, Method};
 use is_empty::IsEmpty;
-use serde_json::{json, Value};
-use std::collections::HashMap;
+use nmsr_rendering::high_level::{model::PlayerArmorSlot, parts::geometry::BedrockGeometry};
+use serde_json::Value;
+use std::sync::Arc;
+
+const APPLICATION_JSON_MIME: &str = "application/json";
+
+/// The names of the multipart fields (or JSON properties) that carry the skin texture.
+const SKIN_FIELD_NAMES: [&str; 2] = ["skin", "texture"];
+
+/// The name of the multipart field (or JSON property) that carries the Bedrock Edition geometry of the skin.
+const GEOMETRY_FIELD_NAME: &str = "geometry";
+
+/// The files uploaded along with the skin of a `POST` request.
+#[derive(Default)]
+struct RenderRequestUploads {
+    geometry: Option<BedrockGeometry>,
+    armor: Option<UploadedArmorLayers>,
+}
+
+impl RenderRequestUploads {
+    /// Read the uploaded skin, geometry and armor textures, where the skin can also come from a skin pack uploaded
+    /// as the geometry.
+    ///
+    /// Uploaded armor layers are worn in the empty slots they cover, unless a slot already asks for them.
+    fn load(
+        skin: Option<Vec<u8>>,
+        geometry: Option<Vec<u8>>,
+        armor: [Option<Vec<u8>>; 4],
+        query: &mut RenderRequestQueryParams,
+    ) -> Result<(RenderRequestEntry, Self)> {
+        let bedrock_skin = geometry
+            .map(|geometry| BedrockSkin::load(&geometry))
+            .transpose()?
+            .unwrap_or_default();
+
+        if bedrock_skin.slim && query.model.is_none() && query.get_model().is_none() {
+            query.model = Some(RenderRequestEntryModel::Alex.into());
+        }
+
+        let skin = skin
+            .or(bedrock_skin.texture)
+            .ok_or(RenderRequestError::MissingRenderRequestEntry)?;
+
+        let armor = UploadedArmorLayers::load(armor)?;
+
+        if let Some(armor) = &armor {
+            Self::wear_uploaded_armor(armor, query);
+        }
+
+        let uploads = Self {
+            geometry: bedrock_skin.geometry,
+            armor,
+        };
+
+        Ok((RenderRequestEntry::try_from(skin)?, uploads))
+    }
+
+    fn wear_uploaded_armor(armor: &UploadedArmorLayers, query: &mut RenderRequestQueryParams) {
+        let is_worn = query
+            .helmet
+            .as_ref()
+            .and_then(VanillaMinecraftHelmetData::armor)
+            .into_iter()
+            .chain([&query.chestplate, &query.leggings, &query.boots].into_iter().flatten())
+            .any(VanillaMinecraftArmorMaterialData::is_uploaded);
+
+        if is_worn {
+            return;
+        }
+
+        let uploaded = VanillaMinecraftArmorMaterialData::new(VanillaMinecraftArmorMaterialData::UPLOADED_MATERIAL);
+
+        if armor.covers(PlayerArmorSlot::Chestplate) {
+            query.helmet.get_or_insert_with(|| VanillaMinecraftHelmetData::Armor(uploaded.clone()));
+            query.chestplate.get_or_insert_with(|| uploaded.clone());
+            query.boots.get_or_insert_with(|| uploaded.clone());
+        }
+
+        if armor.covers(PlayerArmorSlot::Leggings) {
+            query.leggings.get_or_insert(uploaded);
+        }
+    }
+
+    fn apply(self, request: &mut RenderRequest) {
+        if self.geometry.is_none() && self.armor.is_none() {
+            return;
+        }
+
+        let settings = request.extra_settings.get_or_insert_with(Default::default);
+        settings.geometry = self.geometry.map(Arc::new);
+        settings.uploaded_armor = self.armor.map(Arc::new);
+    }
+}
+
+fn is_json_request(request: &Request) -> bool {
+    request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with(APPLICATION_JSON_MIME))
+}
+
+/// Extract the skin and options from a `multipart/form-data` body.
+///
+/// The skin, geometry and armor texture fields are read straight into bytes, while every other field is read as text and
+/// deserialized with the same rules as the query string of a `GET` request.
+async fn extract_multipart_params<S>(
+    request: Request,
+    state: &S,
+) -> Result<(RenderRequestEntry, RenderRequestQueryParams, RenderRequestUploads)>
+where
+    S: Send + Sync,
+{
+    let mut multipart = Multipart::from_request(request, state)
+        .await
+        .map_err(RenderRequestError::from)?;
+
+    let mut skin = None;
+    let mut geometry = None;
+    let mut armor: [Option<Vec<u8>>; 4] = Default::default();
+    let mut fields: Vec<(String, String)> = Vec::new();
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(RenderRequestError::from)?
+    {
+        let Some(name) = field.name().map(ToOwned::to_owned) else {
+            continue;
+        };
+
+        if SKIN_FIELD_NAMES.contains(&name.as_str()) {
+            let bytes = field.bytes().await.map_err(RenderRequestError::from)?;
+            skin.replace(bytes.to_vec());
+        } else if name == GEOMETRY_FIELD_NAME {
+            let bytes = field.bytes().await.map_err(RenderRequestError::from)?;
+            geometry.replace(bytes.to_vec());
+        } else if let Some(index) = UploadedArmorLayers::TEXTURE_NAMES.iter().position(|texture| *texture == name) {
+            let bytes = field.bytes().await.map_err(RenderRequestError::from)?;
+            armor[index].replace(bytes.to_vec());
+        } else {
+            let value = field.text().await.map_err(RenderRequestError::from)?;
+            fields.push((name, value));
+        }
+    }
+
+    let encoded = serde_urlencoded::to_string(&fields)
+        .map_err(|e| RenderRequestError::MultipartDecodeError(e.to_string()))?;
+
+    let mut query = serde_urlencoded::from_str::<RenderRequestQueryParams>(&encoded)
+        .map_err(|e| RenderRequestError::MultipartDecodeError(e.to_string()))?;
+
+    let (entry, uploads) = RenderRequestUploads::load(skin, geometry, armor, &mut query)?;
+
+    Ok((entry, query, uploads))
+}
+
+/// Decode a base64 string, which can also be wrapped in a `data:` URL.
+pub(crate) fn decode_base64(encoded: &str) -> std::result::Result<Vec<u8>, base64::DecodeError> {
+    let encoded = encoded
+        .split_once(";base64,")
+        .map_or(encoded, |(_, data)| data);
+
+    STANDARD.decode(encoded.trim())
+}
+
+/// Extract the skin and options from an `application/json` body.
+///
+/// The skin is expected to be encoded as base64, optionally wrapped in a `data:` URL, while the other
+/// properties are the options of the request, and unknown properties are rejected.
+/// The geometry is either the object of a `.geo.json` file, or a file encoded like the skin, and the armor
+/// textures are encoded like the skin.
+async fn extract_json_params<S>(
+    request: Request,
+    state: &S,
+) -> Result<(RenderRequestEntry, RenderRequestQueryParams, RenderRequestUploads)>
+where
+    S: Send + Sync,
+{
+    let Json(mut params) = Json::<RenderRequestJsonParams>::from_request(request, state)
+        .await
+        .map_err(|rejection| match rejection {
+            JsonRejection::JsonDataError(error) => RenderRequestError::JsonDecodeError(error.body_text()),
+            rejection => RenderRequestError::from(rejection),
+        })?;
+
+    let skin = params
+        .skin
+        .as_deref()
+        .map(decode_base64)
+        .transpose()
+        .map_err(RenderRequestError::InvalidBase64Skin)?;
+
+    let geometry = match &params.geometry {
+        None | Some(Value::Null) => None,
+        Some(Value::String(encoded)) => Some(decode_base64(encoded).map_err(|e| {
+            RenderRequestError::InvalidGeometryError(format!("Invalid base64: {e}"))
+        })?),
+        Some(geometry @ Value::Object(_)) => Some(geometry.to_string().into_bytes()),
+        Some(_) => {
+            return Err(RenderRequestError::JsonDecodeError(
+                "geometry can only be an object or a base64 string".to_string(),
+            )
+            .into())
+        }
+    };
+
+    let mut armor: [Option<Vec<u8>>; 4] = Default::default();
+
+    for (texture, name) in armor.iter_mut().zip(UploadedArmorLayers::TEXTURE_NAMES) {
+        *texture = match params.uploads.remove(name) {
+            None | Some(Value::Null) => None,
+            Some(Value::String(encoded)) => Some(decode_base64(&encoded).map_err(|e| {
+                ArmorManagerError::InvalidArmorUploadError(name.to_owned(), format!("Invalid base64: {e}"))
+            })?),
+            Some(_) => {
+                return Err(RenderRequestError::JsonDecodeError(format!("{name} can only be a base64 string")).into())
+            }
+        };
+    }
+
+    if let Some(name) = params.uploads.keys().min() {
+        return Err(RenderRequestError::JsonDecodeError(format!("unknown option `{name}`")).into());
+    }
+
+    let (entry, uploads) = RenderRequestUploads::load(skin, geometry, armor, &mut params.options)?;
+
+    Ok((entry, params.options, uploads))
+}
 
 #[async_trait]
 impl<S> FromRequest<S> for RenderRequest
@@ -34,10 +271,17 @@ where
     ///  - `GET /:mode/:entry?options`
     ///  - `POST /:mode`
     ///
-    /// The entry is in the URL path, and the options are in the query string.
+    /// For `GET` requests, the entry is in the URL path, and the options are in the query string.
+    ///
+    /// For `POST` requests, the body is either:
+    ///  - `multipart/form-data`, with the skin in the `skin` (or `texture`) field and the options as text fields
+    ///  - `application/json`, with the skin encoded as base64 in the `skin` (or `texture`) property and the options as properties
+    ///
+    /// Either body can also carry the Bedrock Edition geometry of the skin (or a whole skin pack) in `geometry`,
+    /// and armor textures that aren't in the asset packs in `armor_layer_1`, `armor_layer_2` and their `_overlay`s.
     ///
     async fn from_request(mut request: Request, state: &S) -> Result<Self> {
-        let (mode, entry, mut query) = if request.method() == Method::POST {
+        let (mode, entry, query, uploads) = if request.method() == Method::POST {
             let Path(mode_str) = request
                 .extract_parts_with_state::<Path<String>, S>(state)
                 .await
@@ -45,46 +289,16 @@ where
 
             let mode = RenderRequestMode::try_from(mode_str.as_str())
                 .ok()
-                .filter(|r| state.validate_mode(r))
+                .filter(|r| state.validate_mode(*r))
                 .ok_or_else(|| RenderRequestError::InvalidRenderMode(mode_str))?;
 
-            let mut multipart = Multipart::from_request(request, state)
-                .await
-                .map_err(RenderRequestError::from)?;
-
-            let mut data: HashMap<String, Value> = HashMap::new();
-
-            while let Some(field) = multipart
-                .next_field()
-                .await
-                .map_err(RenderRequestError::from)?
-            {
-                if let Some(name) = field.name().map(ToOwned::to_owned) {
-                    let entry_content = if field.content_type().is_none() {
-                        let str = field.text().await.map_err(RenderRequestError::from)?;
-                        serde_json::from_str(&str).unwrap_or(Value::String(str))
-                    } else {
-                        Value::from(
-                            field
-                                .bytes()
-                                .await
-                                .map_err(RenderRequestError::from)?
-                                .to_vec(),
-                        )
-                    };
-
-                    data.insert(name.clone(), entry_content);
-                }
-            }
-
-            let object = json!(data);
-
-            let query = serde_json::from_value::<RenderRequestMultipartParams>(object.clone())
-                .map_err(|e| RenderRequestError::MultipartDecodeError(e, object.clone()))?;
-
-            let entry = RenderRequestEntry::try_from(query.skin)?;
+            let (entry, query, uploads) = if is_json_request(&request) {
+                extract_json_params(request, state).await?
+            } else {
+                extract_multipart_params(request, state).await?
+            };
 
-            (mode, entry, query.query)
+            (mode, entry, query, uploads)
         } else {
             let Path((mode_str, entry_str)) = request
                 .extract_parts_with_state::<Path<(String, String)>, S>(state)
@@ -93,7 +307,7 @@ where
 
             let mode = RenderRequestMode::try_from(mode_str.as_str())
                 .ok()
-                .filter(|r| state.validate_mode(r))
+                .filter(|r| state.validate_mode(*r))
                 .ok_or_else(|| RenderRequestError::InvalidRenderMode(mode_str))?;
 
             let entry = RenderRequestEntry::try_from(entry_str)?;
@@ -103,71 +317,213 @@ where
                 .await
                 .map_err(RenderRequestError::from)?;
 
-            (mode, entry, query)
+            (mode, entry, query, RenderRequestUploads::default())
         };
 
-        query.validate(mode)?;
+        let mut request = build_render_request(mode, entry, query, state)?;
+        uploads.apply(&mut request);
 
-        let excluded_features = query.get_excluded_features();
+        Ok(request)
+    }
+}
 
-        let model = query.get_model();
+/// A [`RenderRequest`] created from one of the presets defined in the configuration.
+pub struct PresetRenderRequest(pub RenderRequest);
 
-        let extra_settings = Some(RenderRequestExtraSettings {
-            width: query.width,
-            height: query.height,
+#[async_trait]
+impl<S> FromRequestParts<S> for PresetRenderRequest
+where
+    S: Send + Sync + RenderRequestValidator,
+{
+    type Rejection = NMSRaaSError;
 
-            yaw: query.yaw,
-            pitch: query.pitch,
-            roll: query.roll,
+    /// Extract a [`PresetRenderRequest`] from the request.
+    ///
+    /// URLs have the following format:
+    ///  - `GET /preset/:name/:entry?options`
+    ///
+    /// The preset provides the mode and the options, and the query string can only override
+    /// the options that the preset marks as overridable.
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self> {
+        let Path((preset_name, entry_str)) =
+            Path::<(String, String)>::from_request_parts(parts, state)
+                .await
+                .map_err(RenderRequestError::from)?;
 
-            arm_rotation: query.arms,
-            distance: query.distance,
+        let preset = state
+            .get_preset(&preset_name)
+            .ok_or(RenderRequestError::UnknownPresetError(preset_name))?;
 
-            x_pos: query.x_pos,
-            y_pos: query.y_pos,
-            z_pos: query.z_pos,
+        let mode = Some(preset.mode)
+            .filter(|r| state.validate_mode(*r))
+            .ok_or_else(|| RenderRequestError::InvalidRenderMode(preset.mode.to_string()))?;
 
-            helmet: query.helmet,
-            chestplate: query.chestplate,
-            leggings: query.leggings,
-            boots: query.boots,
-        })
-        .filter(|s| !s.is_empty());
-
-        let mut request = Self::new_from_excluded_features(
-            mode,
-            entry,
-            model,
-            excluded_features,
-            extra_settings,
-        );
-        
-        state.cleanup_request(&mut request);
-        
-        Ok(request)
+        let entry = RenderRequestEntry::try_from(entry_str)?;
+
+        let Query(overrides) = Query::<RenderRequestQueryParams>::from_request_parts(parts, state)
+            .await
+            .map_err(RenderRequestError::from)?;
+
+        let mut query = RenderRequestQueryParams::from(preset);
+        query.override_with(overrides, &preset.overridable)?;
+
+        build_render_request(mode, entry, query, state).map(Self)
     }
 }
 
+fn build_render_request<S>(
+    mode: RenderRequestMode,
+    entry: RenderRequestEntry,
+    mut query: RenderRequestQueryParams,
+    state: &S,
+) -> Result<RenderRequest>
+where
+    S: RenderRequestValidator,
+{
+    query.validate(mode)?;
+
+    let excluded_features = query.get_excluded_features();
+
+    let model = query.get_model();
+    let mob = query.get_mob();
+    let pose = query.get_pose();
+
+    let animation = query
+        .animation
+        .as_ref()
+        .map(|name| {
+            state
+                .get_animation(name)
+                .ok_or_else(|| RenderRequestError::UnknownAnimationError(name.clone()))
+        })
+        .transpose()?;
+    let animation_frames = animation.as_ref().map(|_| {
+        query
+            .frames
+            .unwrap_or(RenderRequestQueryParams::DEFAULT_ANIMATION_FRAMES)
+    });
+
+    let main_hand = query.mainhand.as_deref().map(|item| load_held_item(state, item)).transpose()?;
+    let off_hand = query.offhand.as_deref().map(|item| load_held_item(state, item)).transpose()?;
+
+    let extra_settings = Some(RenderRequestExtraSettings {
+        width: query.width,
+        height: query.height,
+
+        yaw: query.yaw,
+        pitch: query.pitch,
+        roll: query.roll,
+
+        arm_rotation: query.arms,
+        distance: query.distance,
+
+        x_pos: query.x_pos,
+        y_pos: query.y_pos,
+        z_pos: query.z_pos,
+
+        helmet: query.helmet,
+        chestplate: query.chestplate,
+        leggings: query.leggings,
+        boots: query.boots,
+        elytra: query.elytra,
+        uploaded_armor: None,
+
+        main_hand,
+        off_hand,
+
+        nametag: query.nametag,
+        glow: query.glow,
+
+        cape_angle: query.cape_angle,
+        cape_wind: query.wind,
+
+        body_parts: query.parts,
+        exploded_view_offset: query.explode,
+
+        pose,
+
+        animation,
+        animation_frames,
+        time: query.t,
+
+        geometry: None,
+        mob,
+    })
+    .filter(|s| !s.is_empty());
+
+    let mut request = RenderRequest::new_from_excluded_features(
+        mode,
+        entry,
+        model,
+        excluded_features,
+        extra_settings,
+    );
+    
+    state.cleanup_request(&mut request);
+    
+    Ok(request)
+}
+
+/// Load the model of an item held by the entry, which can only be found with the asset packs.
+fn load_held_item<S>(state: &S, item: &str) -> Result<HeldItem>
+where
+    S: RenderRequestValidator,
+{
+    state
+        .get_armor_manager()
+        .ok_or_else(|| ArmorManagerError::UnknownItemError(item.to_owned()))?
+        .get_held_item(item)
+}
+
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, fmt::Write, io::Cursor, sync::Arc};
 
-    use axum::{debug_handler, extract::State, routing::get, Router, body::Body};
+    use axum::{debug_handler, extract::State, routing::{get, post}, Router, body::Body};
+    use base64::{engine::general_purpose::STANDARD, Engine};
     use enumset::{enum_set, EnumSet};
     use hyper::Request;
+    use image::{ImageFormat, RgbaImage};
+    use nmsr_rendering::high_level::{
+        animation::PlayerAnimationPreset,
+        model::mob::HumanoidMob,
+        pose::{PartRotation, PlayerPose, PlayerPosePreset},
+        types::PlayerBodyPartType,
+    };
+    use serde_json::json;
     use tokio::sync::mpsc::Sender;
     use tower::ServiceExt;
     use uuid::uuid;
 
+    use super::PresetRenderRequest;
     use crate::{
+        config::RenderPresetConfiguration,
+        error::{NMSRaaSError, RenderRequestError, Result},
+        model::armor::{VanillaMinecraftArmorMaterialData, VanillaMinecraftHelmetData},
         model::request::{
             entry::{RenderRequestEntry, RenderRequestEntryModel}, RenderRequest, RenderRequestExtraSettings, RenderRequestFeatures, RenderRequestMode
         },
         routes::RenderRequestValidator,
     };
 
+    #[derive(Clone)]
+    struct PresetTestState {
+        sender: Sender<Result<RenderRequest>>,
+        presets: Arc<HashMap<String, RenderPresetConfiguration>>,
+    }
+
+    impl RenderRequestValidator for PresetTestState {
+        fn validate_mode(&self, _mode: RenderRequestMode) -> bool {
+            true
+        }
+
+        fn get_preset(&self, name: &str) -> Option<&RenderPresetConfiguration> {
+            self.presets.get(name)
+        }
+    }
+
     impl RenderRequestValidator for Sender<RenderRequest> {
-        fn validate_mode(&self, _mode: &RenderRequestMode) -> bool {
+        fn validate_mode(&self, _mode: RenderRequestMode) -> bool {
             true
         }
     }
@@ -177,6 +533,46 @@ mod tests {
         state.send(request).await.unwrap();
     }
 
+    async fn preset_test_handler(
+        State(state): State<PresetTestState>,
+        request: Result<PresetRenderRequest>,
+    ) {
+        state.sender.send(request.map(|r| r.0)).await.unwrap();
+    }
+
+    fn test_presets() -> HashMap<String, RenderPresetConfiguration> {
+        let preset = serde_json::from_value(json!({
+            "mode": "fullbody",
+            "yaw": 45.0,
+            "width": 256,
+            "head": [10.0, 0.0, 0.0],
+            "overridable": ["width"]
+        }))
+        .expect("Failed to parse preset");
+
+        HashMap::from([("avatar".to_owned(), preset)])
+    }
+
+    async fn render_request_from_preset(url: &str) -> Result<RenderRequest> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<Result<RenderRequest>>(1);
+
+        let request = Request::builder()
+            .uri(url)
+            .body(Body::empty())
+            .expect("Failed to build request");
+
+        let app: Router = Router::new()
+            .route("/preset/:name/:entry", get(preset_test_handler))
+            .with_state(PresetTestState {
+                sender: tx,
+                presets: Arc::new(test_presets()),
+            });
+
+        app.oneshot(request).await.expect("Failed to send request");
+
+        rx.recv().await.expect("Failed to receive request")
+    }
+
     async fn render_request_from_url(url: &str) -> RenderRequest {
         let (tx, mut rx) = tokio::sync::mpsc::channel::<RenderRequest>(1);
 
@@ -194,6 +590,215 @@ mod tests {
         rx.recv().await.expect("Failed to receive request")
     }
 
+    async fn render_request_status(url: &str) -> hyper::StatusCode {
+        let (tx, _rx) = tokio::sync::mpsc::channel::<RenderRequest>(1);
+
+        let request = Request::builder()
+            .uri(url)
+            .body(Body::empty())
+            .expect("Failed to build request");
+
+        let app: Router = Router::new()
+            .route("/:mode/:entry", get(test_handler))
+            .with_state(tx);
+
+        app.oneshot(request).await.expect("Failed to send request").status()
+    }
+
+    async fn render_request_from_post(url: &str, content_type: &str, body: String) -> RenderRequest {
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<RenderRequest>(1);
+
+        let request = Request::builder()
+            .method("POST")
+            .uri(url)
+            .header("Content-Type", content_type)
+            .body(Body::from(body))
+            .expect("Failed to build request");
+
+        let app: Router = Router::new()
+            .route("/:mode", post(test_handler))
+            .with_state(tx);
+
+        app.oneshot(request).await.expect("Failed to send request");
+
+        rx.recv().await.expect("Failed to receive request")
+    }
+
+    #[tokio::test]
+    async fn test_render_request_from_post_body() {
+        let skin = vec![0x89, b'P', b'N', b'G', 0x00, 0xFF];
+
+        let expected = RenderRequest {
+            mode: RenderRequestMode::FullBody,
+            entry: RenderRequestEntry::PlayerSkin(skin.clone()),
+            model: Some(RenderRequestEntryModel::Alex),
+            features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::Shadow | RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom)),
+            extra_settings: Some(RenderRequestExtraSettings {
+                yaw: Some(30.0f32),
+                ..Default::default()
+            }),
+        };
+
+        let json = format!(
+            r#"{{"skin": "{}", "model": "alex", "yaw": 30, "no": "shadow"}}"#,
+            STANDARD.encode(&skin)
+        );
+
+        let result = render_request_from_post("http://localhost:8621/fullbody", "application/json", json).await;
+        assert_eq!(expected, result, "Failed to extract from json body");
+
+        let json = format!(
+            r#"{{"skin": "{}", "exclude": ["shadow"], "head": [0, 10, 0], "noshading": true, "nolayers": false, "elytra": true}}"#,
+            STANDARD.encode(&skin)
+        );
+
+        let result = render_request_from_post("http://localhost:8621/fullbody", "application/json", json).await;
+        let query_result = render_request_from_url(
+            "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?exclude=shadow&head=0,10,0&noshading&elytra",
+        )
+        .await;
+        assert_eq!(query_result.features, result.features, "Json arrays and booleans should act like query values");
+        assert_eq!(query_result.extra_settings, result.extra_settings, "Json arrays and booleans should act like query values");
+
+        for (options, reason) in [
+            (r#""head": {"x": 0}"#, "Json objects should be rejected"),
+            (r#""width": "256""#, "Json options should have their own types"),
+            (r#""yaww": 30"#, "Unknown json options should be rejected"),
+        ] {
+            let json = format!(r#"{{"skin": "{}", {options}}}"#, STANDARD.encode(&skin));
+            let (tx, _rx) = tokio::sync::mpsc::channel::<RenderRequest>(1);
+            let response = Router::new()
+                .route("/:mode", post(test_handler))
+                .with_state(tx)
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("http://localhost:8621/fullbody")
+                        .header("Content-Type", "application/json")
+                        .body(Body::from(json))
+                        .expect("Failed to build request"),
+                )
+                .await
+                .expect("Failed to send request");
+            assert_eq!(hyper::StatusCode::BAD_REQUEST, response.status(), "{reason}");
+        }
+
+        let boundary = "nmsr-boundary";
+        let mut multipart = String::new();
+
+        for (name, value) in [("model", "alex"), ("yaw", "30"), ("no", "shadow")] {
+            write!(multipart, "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n")
+                .expect("Failed to write form field");
+        }
+
+        // Keep the skin as valid UTF-8 for the sake of building the body as a string
+        let skin = b"not really a png".to_vec();
+        write!(
+            multipart,
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"skin\"; filename=\"skin.png\"\r\nContent-Type: image/png\r\n\r\n{}\r\n--{boundary}--\r\n",
+            String::from_utf8_lossy(&skin)
+        )
+        .expect("Failed to write skin");
+
+        let result = render_request_from_post(
+            "http://localhost:8621/fullbody",
+            &format!("multipart/form-data; boundary={boundary}"),
+            multipart,
+        )
+        .await;
+
+        assert_eq!(
+            RenderRequest {
+                entry: RenderRequestEntry::PlayerSkin(skin),
+                ..expected
+            },
+            result,
+            "Failed to extract from multipart body"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_render_request_with_geometry() {
+        let skin = STANDARD.encode([0x89, b'P', b'N', b'G']);
+        let geometry = json!({
+            "format_version": "1.12.0",
+            "minecraft:geometry": [{
+                "description": { "identifier": "geometry.tail", "texture_width": 64, "texture_height": 64 },
+                "bones": [{ "name": "tail", "parent": "body", "pivot": [0, 12, 2], "cubes": [{ "origin": [-1, 10, 2], "size": [2, 2, 6], "uv": [56, 16] }] }]
+            }]
+        });
+
+        for geometry in [geometry.clone(), json!(STANDARD.encode(geometry.to_string()))] {
+            let json = json!({ "skin": skin, "geometry": geometry }).to_string();
+            let result = render_request_from_post("http://localhost:8621/fullbody", "application/json", json).await;
+
+            let geometry = result.get_geometry().expect("The geometry should be set");
+            assert_eq!("tail", geometry.bones[0].name);
+        }
+
+        let (tx, _rx) = tokio::sync::mpsc::channel::<RenderRequest>(1);
+        let response = Router::new()
+            .route("/:mode", post(test_handler))
+            .with_state(tx)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("http://localhost:8621/fullbody")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(json!({ "geometry": geometry }).to_string()))
+                    .expect("Failed to build request"),
+            )
+            .await
+            .expect("Failed to send request");
+        assert_eq!(hyper::StatusCode::BAD_REQUEST, response.status(), "A skin is needed unless the geometry is a skin pack");
+    }
+
+    #[tokio::test]
+    async fn test_render_request_with_uploaded_armor() {
+        let skin = STANDARD.encode([0x89, b'P', b'N', b'G']);
+
+        let mut layer = Vec::new();
+        RgbaImage::new(64, 32)
+            .write_to(&mut Cursor::new(&mut layer), ImageFormat::Png)
+            .unwrap();
+        let layer = format!("data:image/png;base64,{}", STANDARD.encode(layer));
+
+        let uploaded = VanillaMinecraftArmorMaterialData::new(VanillaMinecraftArmorMaterialData::UPLOADED_MATERIAL);
+
+        let json = json!({ "skin": skin, "armor_layer_1": layer, "boots": "diamond" }).to_string();
+        let result = render_request_from_post("http://localhost:8621/fullbody", "application/json", json).await;
+        let settings = result.extra_settings.as_ref().expect("Extra settings should be set");
+
+        assert!(result.get_uploaded_armor().is_some());
+        assert_eq!(Some(VanillaMinecraftHelmetData::Armor(uploaded.clone())), settings.helmet);
+        assert_eq!(Some(uploaded.clone()), settings.chestplate);
+        assert_eq!(None, settings.leggings, "Only the slots covered by the uploaded layers are filled");
+        assert_eq!(Some(VanillaMinecraftArmorMaterialData::new("diamond")), settings.boots);
+
+        let json = json!({ "skin": skin, "armor_layer_1": layer, "chestplate": "uploaded_sentry_gold" }).to_string();
+        let result = render_request_from_post("http://localhost:8621/fullbody", "application/json", json).await;
+        let settings = result.extra_settings.as_ref().expect("Extra settings should be set");
+
+        assert_eq!(None, settings.helmet, "Slots aren't filled when the request already wears the uploaded armor");
+        assert_eq!(Some(uploaded.with_trim("sentry", "gold")), settings.chestplate);
+
+        let (tx, _rx) = tokio::sync::mpsc::channel::<RenderRequest>(1);
+        let response = Router::new()
+            .route("/:mode", post(test_handler))
+            .with_state(tx)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("http://localhost:8621/fullbody")
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(json!({ "skin": skin, "armor_layer_2_overlay": layer }).to_string()))
+                    .expect("Failed to build request"),
+            )
+            .await
+            .expect("Failed to send request");
+        assert_eq!(hyper::StatusCode::BAD_REQUEST, response.status(), "Overlays need their layer");
+    }
+
     #[tokio::test]
     async fn test_render_request_from_request_parts() {
         let entry =
@@ -300,4 +905,281 @@ mod tests {
             assert_eq!(element, result, "Failed to extract for url: {url}");
         }
     }
+
+    #[tokio::test]
+    async fn test_mob_render_request_from_request_parts() {
+        let url = "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?model=armor_stand&helmet=diamond";
+
+        let expected = RenderRequest {
+            mode: RenderRequestMode::FullBody,
+            entry: RenderRequestEntry::MojangPlayerUuid(uuid!("ad4569f3-7576-4376-a7c7-8e8cfcd9b832")),
+            model: None,
+            features: EnumSet::all().difference(enum_set!(RenderRequestFeatures::UnProcessedSkin | RenderRequestFeatures::Custom)),
+            extra_settings: Some(RenderRequestExtraSettings {
+                helmet: Some(VanillaMinecraftHelmetData::Armor(VanillaMinecraftArmorMaterialData::new("diamond"))),
+                mob: Some(HumanoidMob::ArmorStand),
+                ..Default::default()
+            }),
+        };
+
+        assert_eq!(expected, render_request_from_url(url).await);
+    }
+
+    #[tokio::test]
+    async fn test_render_request_from_preset() {
+        let url = "http://localhost:8621/preset/avatar/ad4569f3-7576-4376-a7c7-8e8cfcd9b832";
+
+        let result = render_request_from_preset(url).await.expect("Failed to extract preset");
+        let settings = result.extra_settings.expect("Preset should have extra settings");
+        assert_eq!(RenderRequestMode::FullBody, result.mode);
+        assert_eq!(Some(45.0), settings.yaw);
+        assert_eq!(Some(256), settings.width);
+        assert_eq!(
+            Some(PartRotation::from([10.0, 0.0, 0.0])),
+            settings.pose.map(|pose| pose.get_rotation(PlayerBodyPartType::Head)),
+            "Preset limb rotations should be used"
+        );
+
+        let result = render_request_from_preset(&format!("{url}?width=512"))
+            .await
+            .expect("Failed to extract preset with an allowed override");
+        let settings = result.extra_settings.expect("Preset should have extra settings");
+        assert_eq!(Some(45.0), settings.yaw, "Preset settings should be kept");
+        assert_eq!(Some(512), settings.width, "Allowed override should win over the preset");
+
+        let result = render_request_from_preset(&format!("{url}?yaw=90")).await;
+        assert!(
+            matches!(
+                result,
+                Err(NMSRaaSError::RenderRequestError(
+                    RenderRequestError::PresetOverrideNotAllowedError("yaw")
+                ))
+            ),
+            "Overriding a setting that isn't overridable should be rejected"
+        );
+
+        let result = render_request_from_preset(
+            "http://localhost:8621/preset/unknown/ad4569f3-7576-4376-a7c7-8e8cfcd9b832",
+        )
+        .await;
+        let Err(NMSRaaSError::RenderRequestError(error)) = result else {
+            panic!("Unknown preset should be rejected");
+        };
+        assert!(matches!(error, RenderRequestError::UnknownPresetError(ref name) if name == "unknown"));
+        assert_eq!(hyper::StatusCode::NOT_FOUND, error.status_code());
+    }
+
+    #[test]
+    fn test_preset_validation() {
+        for preset in test_presets().values() {
+            preset.validate().expect("Test presets should be valid");
+        }
+
+        let preset: RenderPresetConfiguration = serde_json::from_value(json!({
+            "mode": "fullbody",
+            "overridable": ["witdh"]
+        }))
+        .expect("Failed to parse preset");
+        assert!(matches!(
+            preset.validate(),
+            Err(NMSRaaSError::RenderRequestError(
+                RenderRequestError::UnknownOverridableSettingError(ref setting)
+            )) if setting == "witdh"
+        ));
+
+        let preset: RenderPresetConfiguration = serde_json::from_value(json!({
+            "mode": "fullbody",
+            "arms": 270.0
+        }))
+        .expect("Failed to parse preset");
+        assert!(matches!(
+            preset.validate(),
+            Err(NMSRaaSError::RenderRequestError(
+                RenderRequestError::InvalidRenderSettingBoundsError("arms", _, _)
+            ))
+        ));
+
+        let preset: RenderPresetConfiguration = serde_json::from_value(json!({
+            "mode": "fullbody",
+            "right_arm": [0.0, 0.0]
+        }))
+        .expect("Failed to parse preset");
+        assert!(matches!(
+            preset.validate(),
+            Err(NMSRaaSError::RenderRequestError(
+                RenderRequestError::InvalidRenderSettingError("right arm rotation (right_arm parameter)", _)
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_render_request_body_parts() {
+        let url = "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832";
+
+        let result = render_request_from_url(&format!("{url}?parts=head,left_arm_layer")).await;
+        assert_eq!(
+            Some(vec![PlayerBodyPartType::Head, PlayerBodyPartType::LeftArmLayer]),
+            result.extra_settings.and_then(|s| s.body_parts)
+        );
+
+        for parts in ["head,tail", "", ","] {
+            assert_eq!(
+                hyper::StatusCode::BAD_REQUEST,
+                render_request_status(&format!("{url}?parts={parts}")).await,
+                "?parts={parts} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_render_request_pose() {
+        let url = "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832";
+
+        let pose_of = |request: RenderRequest| request.extra_settings.and_then(|s| s.pose);
+
+        let result = render_request_from_url(&format!("{url}?pose=waving")).await;
+        assert_eq!(Some(PlayerPosePreset::Waving.get_pose()), pose_of(result));
+
+        let result = render_request_from_url(&format!("{url}?pose=crossed_arms")).await;
+        assert_eq!(Some(PlayerPosePreset::CrossedArms.get_pose()), pose_of(result));
+
+        let result =
+            render_request_from_url(&format!("{url}?pose=walking&head=10,20,0&left_leg=-5,0,0&lean=5")).await;
+        assert_eq!(
+            Some(PlayerPose {
+                head: PartRotation::new(10.0, 20.0, 0.0),
+                left_leg: PartRotation::pitch(-5.0),
+                body_lean: 5.0,
+                ..PlayerPosePreset::Walking.get_pose()
+            }),
+            pose_of(result),
+            "Limb rotations should override the ones of the pose"
+        );
+
+        let result = render_request_from_url(&format!("{url}?right_arm=0,0,90")).await;
+        assert_eq!(
+            Some(PlayerPose {
+                right_arm: PartRotation::new(0.0, 0.0, 90.0),
+                ..Default::default()
+            }),
+            pose_of(result),
+            "Limb rotations should work without a pose"
+        );
+
+        for query in ["head=1,2", "left_arm=1,2,3,4", "pose=dancing"] {
+            assert_eq!(
+                hyper::StatusCode::BAD_REQUEST,
+                render_request_status(&format!("{url}?{query}")).await,
+                "?{query} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_render_request_glow() {
+        let url = "http://localhost:8621/fullbody/ad4569f3-7576-4376-a7c7-8e8cfcd9b832?bloom=2";
+
+        #[cfg(feature = "ears")]
+        assert_eq!(Some(2.0), render_request_from_url(url).await.get_glow());
+
+        #[cfg(not(feature = "ears"))]
+        assert_eq!(hyper::StatusCode::BAD_REQUEST, render_request_status(url).await);
+    }
+
+    #[tokio::test]
+    async fn test_render_request_animation() {
+        let entry = "ad4569f3-7576-4376-a7c7-8e8cfcd9b832";
+
+        let result = render_request_from_url(&format!("http://localhost:8621/fullbody/{entry}?animation=walk&frames=10")).await;
+        let settings = result.extra_settings.expect("Animation should be in the extra settings");
+        assert_eq!(Some(PlayerAnimationPreset::Walk.get_animation()), settings.animation);
+        assert_eq!(Some(10), settings.animation_frames);
+
+        let result = render_request_from_url(&format!("http://localhost:8621/fullbody/{entry}?animation=walk&time=40")).await;
+        assert_eq!(Some(40.0), result.extra_settings.and_then(|settings| settings.time));
+
+        for url in [
+            format!("http://localhost:8621/fullbody/{entry}?frames=10"),
+            format!("http://localhost:8621/fullbody/{entry}?animation=dance"),
+            format!("http://localhost:8621/fullbody/{entry}?t=-1"),
+            format!("http://localhost:8621/skin/{entry}?animation=walk"),
+            format!("http://localhost:8621/export/{entry}?animation=walk"),
+        ] {
+            assert_eq!(
+                hyper::StatusCode::BAD_REQUEST,
+                render_request_status(&url).await,
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_render_request_cape() {
+        let hash = "2340c0e03dd24a11b15a8b33c2a7e9e32abb2051b2481d0ba7defd635ca7a933";
+
+        let result = render_request_from_url(&format!(
+            "http://localhost:8621/cape/{hash}?no=cape,shadow&chestplate=diamond&yaw=30"
+        ))
+        .await;
+
+        assert_eq!(RenderRequestMode::Cape, result.mode);
+        assert_eq!(RenderRequestEntry::TextureHash(hash.to_owned()), result.entry);
+        assert!(result.features.contains(RenderRequestFeatures::Cape), "The cape can't be excluded from the cape modes");
+        assert!(!result.features.contains(RenderRequestFeatures::Shadow));
+
+        let settings = result.extra_settings.expect("The yaw should be in the extra settings");
+        assert_eq!(None, settings.chestplate, "There's no player to wear armor in the cape modes");
+        assert_eq!(Some(30.0), settings.yaw);
+
+        let result = render_request_from_url(&format!("http://localhost:8621/capeflat/{hash}")).await;
+        assert_eq!(RenderRequestMode::CapeFlat, result.mode);
+
+        for url in [
+            format!("http://localhost:8621/cape/{hash}?animation=walk"),
+            format!("http://localhost:8621/cape_flat/{hash}?parts=body"),
+        ] {
+            assert_eq!(
+                hyper::StatusCode::BAD_REQUEST,
+                render_request_status(&url).await,
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_render_request_cape_physics() {
+        let entry = "ad4569f3-7576-4376-a7c7-8e8cfcd9b832";
+
+        let result = render_request_from_url(&format!("http://localhost:8621/fullbody/{entry}?capeangle=20&velocity=4")).await;
+        let settings = result.extra_settings.expect("The cape settings should be in the extra settings");
+        assert_eq!(Some(20.0), settings.cape_angle);
+        assert_eq!(Some(4.0), settings.cape_wind);
+
+        for url in [
+            format!("http://localhost:8621/fullbody/{entry}?cape_angle=-10"),
+            format!("http://localhost:8621/fullbody/{entry}?wind=100"),
+        ] {
+            assert_eq!(
+                hyper::StatusCode::BAD_REQUEST,
+                render_request_status(&url).await,
+                "{url} should be rejected"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_render_request_voxel_layers() {
+        let entry = "ad4569f3-7576-4376-a7c7-8e8cfcd9b832";
+
+        let flat = render_request_from_url(&format!("http://localhost:8621/fullbody/{entry}")).await;
+        assert!(flat.features.contains(RenderRequestFeatures::FlatLayers));
+
+        for url in [
+            format!("http://localhost:8621/fullbody/{entry}?voxels"),
+            format!("http://localhost:8621/fullbody/{entry}?no=flat_layers"),
+        ] {
+            let result = render_request_from_url(&url).await;
+            assert!(!result.features.contains(RenderRequestFeatures::FlatLayers), "{url} should use voxel layers");
+        }
+    }
 }
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::{rejection::PathRejection, Path, State},
+    Json,
+};
+#[cfg(feature = "ears")]
+use ears_rs::{alfalfa::AlfalfaDataKey, parser::EarsParser};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use super::NMSRState;
+#[cfg(feature = "ears")]
+use crate::model::ears::EarsFeaturesDefinition;
+use crate::{
+    error::{RenderRequestError, Result},
+    model::{request::entry::RenderRequestEntry, resolver::ResolvedRenderEntryTextureType},
+};
+
+/// What's known about an entry (like a player) without rendering it.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RenderEntryInfo {
+    /// The model of the entry, which is the default one if the entry doesn't have one.
+    pub model: String,
+    /// The name of the profile of the entry, for entries that have one.
+    pub profile_name: Option<String>,
+    pub has_cape: bool,
+    pub has_optifine_cape: bool,
+    /// Whether the skin is one of the default skins of the game, which is null when the asset packs don't have them.
+    pub default_skin: Option<bool>,
+    /// The hashes of the textures of the entry, by texture type. Textures without a known hash are null.
+    pub textures: BTreeMap<&'static str, Option<String>>,
+    /// How long ago the textures of the entry were cached, in seconds.
+    pub cache_age: Option<u64>,
+    /// The Ears data of the skin, if it has any.
+    #[cfg(feature = "ears")]
+    pub ears: Option<EarsInfo>,
+}
+
+/// The Ears data encoded in the skin of an entry.
+#[cfg(feature = "ears")]
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct EarsInfo {
+    pub features: EarsFeaturesDefinition,
+    pub cape_enabled: bool,
+    /// The keys of the alfalfa data stored in the skin, like its wings and cape.
+    pub alfalfa: Vec<&'static str>,
+}
+
+/// The alfalfa data keys that are reported, along with their names.
+#[cfg(feature = "ears")]
+const ALFALFA_KEYS: [(AlfalfaDataKey, &str); 3] = [
+    (AlfalfaDataKey::Erase, "erase"),
+    (AlfalfaDataKey::Wings, "wings"),
+    (AlfalfaDataKey::Cape, "cape"),
+];
+
+#[axum::debug_handler]
+pub async fn info(
+    State(state): State<NMSRState<'static>>,
+    path: std::result::Result<Path<String>, PathRejection>,
+) -> Result<Json<RenderEntryInfo>> {
+    let Path(entry) = path.map_err(RenderRequestError::from)?;
+    let entry = RenderRequestEntry::try_from(entry)?;
+
+    let (resolved, cache_age) = state.resolver.resolve_entry(&entry).await?;
+
+    let skin = resolved
+        .textures
+        .get(&ResolvedRenderEntryTextureType::Skin)
+        .and_then(|skin| image::load_from_memory(skin.data()).ok())
+        .map(image::DynamicImage::into_rgba8);
+
+    let textures = resolved
+        .textures
+        .iter()
+        .map(|(texture_type, texture)| ((*texture_type).into(), texture.hash().cloned()))
+        .collect();
+
+    Ok(Json(RenderEntryInfo {
+        model: resolved.model.unwrap_or_default().to_string(),
+        profile_name: resolved.name,
+        has_cape: resolved.textures.contains_key(&ResolvedRenderEntryTextureType::Cape),
+        has_optifine_cape: resolved.textures.contains_key(&ResolvedRenderEntryTextureType::OptifineCape),
+        default_skin: state
+            .armor_manager
+            .has_default_skins()
+            .then(|| skin.as_ref().is_some_and(|skin| state.armor_manager.is_default_skin(skin))),
+        textures,
+        cache_age: cache_age.map(|age| age.as_secs()),
+        #[cfg(feature = "ears")]
+        ears: skin.as_ref().and_then(read_ears_info),
+    }))
+}
+
+#[cfg(feature = "ears")]
+fn read_ears_info(skin: &image::RgbaImage) -> Option<EarsInfo> {
+    let features = EarsParser::parse(skin).ok().flatten()?;
+    let alfalfa = ears_rs::alfalfa::read_alfalfa(skin).ok().flatten();
+
+    let alfalfa = ALFALFA_KEYS
+        .into_iter()
+        .filter(|(key, _)| alfalfa.as_ref().is_some_and(|alfalfa| alfalfa.get_data(*key).is_some()))
+        .map(|(_, name)| name)
+        .collect();
+
+    Some(EarsInfo {
+        features: EarsFeaturesDefinition::from(&features),
+        cape_enabled: features.cape_enabled,
+        alfalfa,
+    })
+}
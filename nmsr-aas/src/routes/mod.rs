@@ -1,13 +1,21 @@
 pub mod bbmodel_export;
+#[cfg(feature = "ears")]
+mod ears;
 pub mod extractors;
+mod info;
+mod openapi;
 pub mod query;
 mod render;
 mod render_model;
 mod render_skin;
 use crate::{
-    config::{FeaturesConfiguration, ModelCacheConfiguration, NmsrConfiguration},
-    error::Result,
+    config::{
+        FeaturesConfiguration, ModelCacheConfiguration, NmsrConfiguration,
+        RenderPresetConfiguration,
+    },
+    error::{RenderRequestError, Result},
     model::{
+        animation::AnimationDefinition,
         armor::manager::VanillaMinecraftArmorManager,
         request::{
             cache::ModelCache, entry::RenderRequestEntry, RenderRequest, RenderRequestFeatures,
@@ -15,26 +23,55 @@ use crate::{
         },
         resolver::{mojang::client::MojangClient, RenderRequestResolver},
     },
+    utils::skin,
 };
 use deadpool::managed::Object;
 use enumset::EnumSet;
-use image::RgbaImage;
-#[cfg(feature = "ears")]
+use image::{imageops, RgbaImage};
 use nmsr_rendering::high_level::camera::Camera;
+use nmsr_rendering::high_level::animation::{PlayerAnimation, PlayerAnimationPreset};
+use nmsr_rendering::high_level::types::PlayerPartTextureType;
 use nmsr_rendering::high_level::pipeline::{
     pools::SceneContextPoolManager, Backends, Features, GraphicsContext, GraphicsContextDescriptor,
     GraphicsContextPools,
 };
-pub use render::{render, render_get_warning, render_post_warning};
-use std::{borrow::Cow, hint::black_box, sync::Arc, time::Duration};
+#[cfg(feature = "ears")]
+pub use ears::encode_ears;
+pub use info::info;
+pub use openapi::openapi;
+pub use render::{render, render_get_warning, render_post_warning, render_preset};
+use std::{
+    borrow::Cow, collections::HashMap, hint::black_box, str::FromStr, sync::Arc, time::Duration,
+};
 use strum::IntoEnumIterator;
 use tracing::{debug_span, info, info_span, instrument, Instrument};
 
 pub trait RenderRequestValidator {
-    fn validate_mode(&self, mode: &RenderRequestMode) -> bool;
+    fn validate_mode(&self, mode: RenderRequestMode) -> bool;
 
     #[allow(unused_variables)]
     fn cleanup_request(&self, request: &mut RenderRequest) {}
+
+    #[allow(unused_variables)]
+    fn get_preset(&self, name: &str) -> Option<&RenderPresetConfiguration> {
+        None
+    }
+
+    fn get_animation(&self, name: &str) -> Option<PlayerAnimation> {
+        PlayerAnimationPreset::from_str(name)
+            .ok()
+            .map(PlayerAnimation::from)
+    }
+
+    /// The names of the animations defined in the configuration, on top of the built-in ones.
+    fn get_custom_animation_names(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// The armor manager, which knows the armor materials and trims found in the asset packs.
+    fn get_armor_manager(&self) -> Option<&VanillaMinecraftArmorManager> {
+        None
+    }
 }
 
 #[derive(Clone)]
@@ -45,16 +82,18 @@ pub struct NMSRState<'a> {
     pools: Arc<GraphicsContextPools<'a>>,
     cache_config: ModelCacheConfiguration,
     features_config: FeaturesConfiguration,
+    presets: Arc<HashMap<String, RenderPresetConfiguration>>,
+    animations: Arc<HashMap<String, PlayerAnimation>>,
 }
 
-impl<'a> RenderRequestValidator for NMSRState<'a> {
-    fn validate_mode(&self, mode: &RenderRequestMode) -> bool {
-        !self.features_config.disabled_modes.contains(mode)
+impl RenderRequestValidator for NMSRState<'_> {
+    fn validate_mode(&self, mode: RenderRequestMode) -> bool {
+        !self.features_config.disabled_modes.contains(&mode)
     }
 
     fn cleanup_request(&self, request: &mut RenderRequest) {
         let mut disabled_features: EnumSet<RenderRequestFeatures> = EnumSet::new();
-        for feature in self.features_config.disabled_features.iter() {
+        for feature in &self.features_config.disabled_features {
             disabled_features.insert(*feature);
         }
 
@@ -64,19 +103,39 @@ impl<'a> RenderRequestValidator for NMSRState<'a> {
 
         request.features.remove_all(disabled_features);
     }
+
+    fn get_preset(&self, name: &str) -> Option<&RenderPresetConfiguration> {
+        self.presets.get(name)
+    }
+
+    fn get_animation(&self, name: &str) -> Option<PlayerAnimation> {
+        self.animations.get(name).cloned().or_else(|| {
+            PlayerAnimationPreset::from_str(name)
+                .ok()
+                .map(PlayerAnimation::from)
+        })
+    }
+
+    fn get_custom_animation_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.animations.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    fn get_armor_manager(&self) -> Option<&VanillaMinecraftArmorManager> {
+        Some(&self.armor_manager)
+    }
 }
 
 impl<'a> NMSRState<'a> {
-    const ONE_YEAR_DURATION: Duration = Duration::from_secs(
-        60 /* seconds */ * 60 /* minutes */ * 24 /* hours */ * 365, /* days */
-    );
+    const ONE_YEAR_DURATION: Duration = Duration::from_hours(24 /* hours */ * 365 /* days */);
 
     pub async fn new(config: &NmsrConfiguration) -> Result<Self> {
         let mojang_client = MojangClient::new(Arc::new(config.mojank.clone()))?;
         let cache_config = config.caching.clone();
         let model_cache = ModelCache::new("cache".into(), cache_config).await?;
 
-        let rendering_config = config.rendering.clone();
+        let rendering_config = config.rendering;
 
         let resolver = RenderRequestResolver::new(model_cache, Arc::new(mojang_client));
 
@@ -97,7 +156,18 @@ impl<'a> NMSRState<'a> {
 
         let pools = GraphicsContextPools::new(graphics_context.clone())?;
 
-        let armor_manager = VanillaMinecraftArmorManager::new("cache".into()).await?;
+        let armor_manager = VanillaMinecraftArmorManager::new(&config.assets)?;
+
+        let mut animations = HashMap::new();
+        for (name, path) in &config.animations {
+            animations.insert(name.clone(), AnimationDefinition::load(path).await?);
+        }
+
+        for (name, preset) in &config.presets {
+            Self::validate_preset(preset, &animations).map_err(|error| {
+                RenderRequestError::InvalidPresetError(name.clone(), Box::new(error))
+            })?;
+        }
 
         Ok(Self {
             resolver: Arc::new(resolver),
@@ -106,9 +176,28 @@ impl<'a> NMSRState<'a> {
             cache_config: config.caching.clone(),
             armor_manager: Arc::new(armor_manager),
             features_config: config.features.clone().unwrap_or_default(),
+            presets: Arc::new(config.presets.clone()),
+            animations: Arc::new(animations),
         })
     }
 
+    fn validate_preset(
+        preset: &RenderPresetConfiguration,
+        animations: &HashMap<String, PlayerAnimation>,
+    ) -> Result<()> {
+        preset.validate()?;
+
+        if let Some(animation) = &preset.animation {
+            if !animations.contains_key(animation)
+                && PlayerAnimationPreset::from_str(animation).is_err()
+            {
+                return Err(RenderRequestError::UnknownAnimationError(animation.clone()).into());
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn create_scene_context(&self) -> Result<Object<SceneContextPoolManager<'a>>> {
         Ok(self.pools.create_scene_context().await?)
     }
@@ -119,6 +208,24 @@ impl<'a> NMSRState<'a> {
         skin_image: RgbaImage,
         features: EnumSet<RenderRequestFeatures>,
     ) -> Result<RgbaImage> {
+        let Some(PlayerPartTextureType::Skin { scale }) =
+            PlayerPartTextureType::skin_of_size(skin_image.width(), skin_image.height())
+        else {
+            return Err(RenderRequestError::InvalidSkinSizeError(
+                skin_image.width(),
+                skin_image.height(),
+            )
+            .into());
+        };
+
+        if scale > 1 {
+            // HD skins can't have Ears features, so only their layout needs to be fixed up
+            let mut skin_image = skin::upgrade_legacy_skin(skin_image, scale);
+            skin::strip_alpha(&mut skin_image, scale);
+
+            return Ok(skin_image);
+        }
+
         let mut skin_image = ears_rs::utils::upgrade_skin_if_needed(skin_image);
 
         #[cfg(feature = "ears")]
@@ -133,6 +240,18 @@ impl<'a> NMSRState<'a> {
         Ok(skin_image)
     }
 
+    /// Process the skin of a player head worn in the helmet slot, which is laid out on a vanilla 64×64 skin.
+    pub fn process_player_head(skin_image: RgbaImage) -> Result<RgbaImage> {
+        let skin_image = Self::process_skin(skin_image, EnumSet::empty())?;
+        let size = PlayerPartTextureType::VANILLA_SKIN_SIZE;
+
+        if skin_image.width() == size {
+            return Ok(skin_image);
+        }
+
+        Ok(imageops::resize(&skin_image, size, size, imageops::FilterType::Nearest))
+    }
+
     #[cfg(feature = "ears")]
     pub fn apply_ears_camera_settings(
         features: &ears_rs::features::EarsFeatures,
@@ -168,6 +287,19 @@ impl<'a> NMSRState<'a> {
         camera.set_distance(camera.get_distance() + distance_offset);
     }
 
+    /// Make room for the name tag above the head, which the framing of the modes would otherwise cut off.
+    pub fn apply_nametag_camera_settings(mode: RenderRequestMode, camera: &mut Camera) {
+        // Custom mode places the camera wherever it's asked to
+        if mode.is_custom() {
+            return;
+        }
+
+        camera.set_look_at_y(camera.get_look_at_y() + 3.0);
+
+        camera.set_aspect(camera.get_aspect() + 3.0);
+        camera.set_distance(camera.get_distance() + 6.0);
+    }
+
     #[instrument(skip(self))]
     pub(crate) async fn init(&self) -> Result<()> {
         info!("Pre-loading our cache biases.");
@@ -210,7 +342,7 @@ impl<'a> NMSRState<'a> {
                 RenderRequestMode::Skin,
                 entry.clone(),
                 None,
-                EnumSet::EMPTY,
+                EnumSet::empty(),
                 None,
             );
 
@@ -230,14 +362,15 @@ impl<'a> NMSRState<'a> {
             RenderRequestMode::FullBody,
             entry,
             None,
-            EnumSet::EMPTY,
+            EnumSet::empty(),
             None,
         );
 
         let resolved = self.resolver.resolve(&request).await?;
 
         for mode in RenderRequestMode::iter() {
-            if !mode.uses_rendering_pipeline() {
+            // The cape modes fail for players without a cape, so they can't be used to prewarm
+            if !mode.uses_rendering_pipeline() || mode.is_cape() {
                 continue;
             }
 
@@ -0,0 +1,869 @@
+#[cfg(feature = "ears")]
+use super::ears::EarsEncodeRequest;
+use super::{info::RenderEntryInfo, query::RenderRequestQueryParams, NMSRState, RenderRequestValidator};
+use crate::model::{
+    armor::UploadedArmorLayers,
+    request::{entry::RenderRequestEntryModel, RenderRequestFeatures, RenderRequestMode},
+};
+use axum::{extract::State, Json};
+use enumset::EnumSet;
+use nmsr_rendering::high_level::{
+    animation::PlayerAnimationPreset,
+    model::{headwear::Headwear, mob::HumanoidMob},
+    pose::PlayerPosePreset,
+    types::PlayerBodyPartType,
+};
+use schemars::{generate::SchemaSettings, SchemaGenerator};
+use serde::{
+    de::{self, value::StrDeserializer, DeserializeSeed, IntoDeserializer, MapAccess, Visitor},
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+use serde_json::{json, Map, Value};
+use std::{cell::Cell, fmt};
+use strum::{EnumMessage, IntoEnumIterator};
+
+/// How a setting of [`RenderRequestQueryParams`] is deserialized, as told by [`SettingProbe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingKind {
+    Number,
+    Integer,
+    String,
+    /// A setting like `?noshading`, which can be given without a value (or as a boolean in a JSON body).
+    Flag,
+    /// A comma-separated list (or an array in a JSON body).
+    List,
+}
+
+/// A setting of [`RenderRequestQueryParams`], along with the aliases serde accepts for it.
+#[derive(Debug)]
+struct Setting {
+    name: &'static str,
+    aliases: Vec<&'static str>,
+    kind: SettingKind,
+}
+
+impl SettingKind {
+    fn query_schema(self) -> Value {
+        match self {
+            Self::Number => json!({ "type": "number", "format": "float" }),
+            Self::Integer => json!({ "type": "integer" }),
+            Self::String | Self::Flag | Self::List => json!({ "type": "string" }),
+        }
+    }
+}
+
+/// The error used while probing [`RenderRequestQueryParams`], which only cares about duplicate fields.
+#[derive(Debug)]
+enum ProbeError {
+    DuplicateField(&'static str),
+    Other,
+}
+
+impl fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateField(field) => write!(f, "duplicate field {field}"),
+            Self::Other => f.write_str("probe failed"),
+        }
+    }
+}
+
+impl std::error::Error for ProbeError {}
+
+impl de::Error for ProbeError {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        Self::Other
+    }
+
+    fn duplicate_field(field: &'static str) -> Self {
+        Self::DuplicateField(field)
+    }
+}
+
+/// A deserializer that only records the field names (including aliases) of the struct asking for them.
+struct FieldNamesProbe<'a>(&'a mut Vec<&'static str>);
+
+impl<'de> Deserializer<'de> for FieldNamesProbe<'_> {
+    type Error = ProbeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProbeError> {
+        Err(ProbeError::Other)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, ProbeError> {
+        self.0.extend_from_slice(fields);
+        Err(ProbeError::Other)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// A map with the given keys, whose values are all given by `value`.
+struct ProbeMap<'a, D> {
+    keys: std::slice::Iter<'a, &'static str>,
+    value: D,
+}
+
+impl<'de, D> MapAccess<'de> for ProbeMap<'_, D>
+where
+    D: Deserializer<'de, Error = ProbeError> + Copy,
+{
+    type Error = ProbeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, ProbeError> {
+        self.keys
+            .next()
+            .map(|key| seed.deserialize::<StrDeserializer<ProbeError>>(key.into_deserializer()))
+            .transpose()
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, ProbeError> {
+        seed.deserialize(self.value)
+    }
+}
+
+/// The value of a setting that isn't there, which every (optional) setting accepts.
+#[derive(Clone, Copy)]
+struct MissingValue;
+
+impl<'de> Deserializer<'de> for MissingValue {
+    type Error = ProbeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProbeError> {
+        visitor.visit_none()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any struct
+    }
+}
+
+/// The value of a setting that records the type the setting asks for.
+///
+/// Settings that accept anything are given `true`, which only flags accept.
+#[derive(Clone, Copy)]
+struct SettingProbe<'a>(&'a Cell<Option<SettingKind>>);
+
+impl SettingProbe<'_> {
+    fn record<V>(self, kind: SettingKind) -> Result<V, ProbeError> {
+        self.0.set(Some(kind));
+        Err(ProbeError::Other)
+    }
+}
+
+impl<'de> Deserializer<'de> for SettingProbe<'_> {
+    type Error = ProbeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProbeError> {
+        self.0.set(Some(SettingKind::Flag));
+        visitor.visit_bool(true)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProbeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProbeError> {
+        self.record(SettingKind::Number)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProbeError> {
+        self.record(SettingKind::Number)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProbeError> {
+        self.record(SettingKind::Integer)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProbeError> {
+        self.record(SettingKind::Integer)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProbeError> {
+        self.record(SettingKind::Integer)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProbeError> {
+        self.record(SettingKind::Integer)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProbeError> {
+        self.record(SettingKind::String)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProbeError> {
+        self.record(SettingKind::String)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u128 char bytes byte_buf
+        unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any struct
+    }
+}
+
+/// The settings of [`RenderRequestQueryParams`], found out from how serde deserializes it.
+///
+/// The name of a setting (and whether a field name is one of its aliases) comes from the error serde gives for a setting
+/// that's given twice, and its kind from what it asks the deserializer for.
+fn query_settings() -> Vec<Setting> {
+    let mut names = Vec::new();
+    let _ = RenderRequestQueryParams::deserialize(FieldNamesProbe(&mut names));
+
+    let mut settings: Vec<Setting> = Vec::new();
+
+    for name in names {
+        let keys = [name, name];
+        let duplicated = RenderRequestQueryParams::deserialize(de::value::MapAccessDeserializer::new(ProbeMap {
+            keys: keys.iter(),
+            value: MissingValue,
+        }));
+
+        let canonical = match duplicated {
+            Err(ProbeError::DuplicateField(canonical)) => canonical,
+            _ => name,
+        };
+
+        if let Some(setting) = settings.iter_mut().find(|setting| setting.name == canonical) {
+            if name != canonical {
+                setting.aliases.push(name);
+            }
+            continue;
+        }
+
+        let kind = Cell::new(None);
+        let keys = [canonical];
+        let probed = RenderRequestQueryParams::deserialize(de::value::MapAccessDeserializer::new(ProbeMap {
+            keys: keys.iter(),
+            value: SettingProbe(&kind),
+        }));
+
+        let kind = match (kind.get(), probed) {
+            // Lists don't take booleans like flags do
+            (Some(SettingKind::Flag), Err(_)) => SettingKind::List,
+            (kind, _) => kind.unwrap_or(SettingKind::String),
+        };
+
+        settings.push(Setting {
+            name: canonical,
+            aliases: if canonical == name { Vec::new() } else { vec![name] },
+            kind,
+        });
+    }
+
+    settings
+}
+
+/// A query parameter accepted by the render endpoints, as described in [`RenderRequestQueryParams`].
+struct QueryParameter {
+    setting: Setting,
+    description: &'static str,
+    schema: Value,
+    /// The constraints of a list when it's given as an array, like the schema of its `items`.
+    array: Map<String, Value>,
+}
+
+impl QueryParameter {
+    fn to_parameter(&self) -> Value {
+        let Setting { name, aliases, kind } = &self.setting;
+
+        let description = if aliases.is_empty() {
+            self.description.to_owned()
+        } else {
+            let aliases: Vec<_> = aliases.iter().map(|a| format!("`{a}`")).collect();
+            let aliases = aliases.join(", ");
+            format!("{} Aliases: {aliases}.", self.description)
+        };
+
+        let mut schema = self.schema.clone();
+        if let (Some(items), Some(schema)) = (self.array.get("items"), schema.as_object_mut()) {
+            schema.insert("x-items".to_owned(), items.clone());
+        }
+
+        json!({
+            "name": name,
+            "in": "query",
+            "required": false,
+            "description": description,
+            "allowEmptyValue": *kind == SettingKind::Flag,
+            "schema": schema,
+            "x-aliases": aliases,
+        })
+    }
+
+    fn to_property(&self) -> (String, Value) {
+        let mut schema = match self.setting.kind {
+            SettingKind::Flag => json!({ "oneOf": [{ "type": "boolean" }, self.schema] }),
+            SettingKind::List => {
+                let mut array = self.array.clone();
+                array.insert("type".to_owned(), json!("array"));
+                json!({ "oneOf": [self.schema, array] })
+            }
+            _ => self.schema.clone(),
+        };
+
+        if let Some(schema) = schema.as_object_mut() {
+            schema.insert("description".to_owned(), json!(self.description));
+        }
+
+        (self.setting.name.to_owned(), schema)
+    }
+}
+
+fn serializations_of<T: EnumMessage>(values: impl Iterator<Item = T>) -> Vec<&'static str> {
+    values
+        .flat_map(|value| value.get_serializations().iter().copied())
+        .collect()
+}
+
+fn lowercase_names_of<T: IntoEnumIterator + ToString>() -> Vec<String> {
+    T::iter().map(|value| value.to_string().to_lowercase()).collect()
+}
+
+/// The description of a setting in the `OpenAPI` document.
+fn describe_setting(name: &str) -> Option<&'static str> {
+    let description = match name {
+        "exclude" => "Comma-separated list of features to exclude from the render.",
+        "noshading" => "Disable shading. Kept for compatibility with old URLs.",
+        "nolayers" => "Disable the skin layers. Kept for compatibility with old URLs.",
+        "nooptifine" => "Disable OptiFine capes.",
+        "yaw" => "Yaw of the camera, in degrees. Values outside the range are wrapped.",
+        "pitch" => "Pitch of the camera, in degrees. Values outside the range are wrapped.",
+        "roll" => "Roll of the camera, in degrees. Values outside the range are wrapped.",
+        "width" => "Width of the output image. Square modes are bound by the width limits on both axes.",
+        "height" => "Height of the output image. Can't be combined with `width` outside of custom mode.",
+        "model" => "Model of the entry, or a mob to render wearing the armor instead of the entry.",
+        "alex" => "Use the alex model. Kept for compatibility with old URLs.",
+        "steve" => "Use the steve model. Kept for compatibility with old URLs.",
+        "process" => "Process the skin (upgrade it to the 1.8 format, strip alpha from the body regions and apply Ears erase regions).",
+        "voxels" => "Build the skin layers out of voxels, like the 3D Skin Layers mod does. Same as excluding `flat_layers`.",
+        "arms" => "Rotation of the arms, in degrees.",
+        "distance" => "Extra distance of the camera.",
+        "x_pos" => "X position of the camera (custom mode only).",
+        "y_pos" => "Y position of the camera (custom mode only).",
+        "z_pos" => "Z position of the camera (custom mode only).",
+        "pos" => "Position of the camera as `x,y,z` (custom mode only).",
+        "helmet" => "Helmet worn by the entry, or block-style headwear: a carved pumpkin, a mob skull or the head of another player (`head:<uuid>`).",
+        "chestplate" => "Chestplate worn by the entry.",
+        "leggings" => "Leggings worn by the entry.",
+        "boots" => "Boots worn by the entry.",
+        "elytra" => "Elytra worn by the entry, using its cape texture (or the vanilla one without a cape). Replaces the cape. Folded when no value is given.",
+        "mainhand" => "Item or block held in the right hand of the entry, like `diamond_sword`, using the models of the configured asset packs.",
+        "offhand" => "Item or block held in the left hand of the entry, like `shield`.",
+        "nametag" => "Show a name tag above the head with this text, which can use formatting codes like `§c` or `&l`. Leave it empty to show the name of the profile of the entry.",
+        "glow" => "Make the emissive parts of Ears skins glow, with this strength.",
+        "cape_angle" => "How far the cape swings out from the back, in degrees.",
+        "wind" => "Render a wavy cape that bends like cloth, blown back by wind of this speed in blocks per second (or how fast the entry is moving).",
+        "parts" => "Comma-separated list of body parts to render, instead of the ones used by the mode.",
+        "explode" => "Distance to move each body part away from the body, for exploded views.",
+        "pose" => "Pose of the entry. Limb rotations and `lean` override the rotations of the pose.",
+        "head" => "Rotation of the head as `pitch,yaw,roll`, in degrees.",
+        "left_arm" => "Rotation of the left arm as `pitch,yaw,roll`, in degrees.",
+        "right_arm" => "Rotation of the right arm as `pitch,yaw,roll`, in degrees.",
+        "left_leg" => "Rotation of the left leg as `pitch,yaw,roll`, in degrees.",
+        "right_leg" => "Rotation of the right leg as `pitch,yaw,roll`, in degrees.",
+        "lean" => "How much the upper body leans forward, in degrees.",
+        "animation" => "Animation to render as an animated PNG. Either one of the built-in animations or one defined in the configuration.",
+        "frames" => "Amount of frames to render the animation with.",
+        "t" => "Time of the render in ticks (twentieths of a second), which moves the animated parts of Ears skins, like their wings. Animations start from this time.",
+        _ => return None,
+    };
+
+    Some(description)
+}
+
+/// What the schema of a setting adds to the type serde deserializes it as, other than the bounds it's validated with.
+fn setting_constraints<V: RenderRequestValidator>(name: &str, validator: &V) -> Value {
+    match name {
+        "exclude" => json!({ "items": { "$ref": "#/components/schemas/RenderRequestFeatures" } }),
+        "width" => json!({ "minimum": RenderRequestMode::MIN_RENDER_WIDTH, "maximum": RenderRequestMode::MAX_RENDER_WIDTH }),
+        "height" => json!({ "minimum": RenderRequestMode::MIN_RENDER_HEIGHT, "maximum": RenderRequestMode::MAX_RENDER_HEIGHT }),
+        "model" => {
+            let mut models: Vec<_> = serializations_of(RenderRequestEntryModel::iter())
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            models.extend(lowercase_names_of::<HumanoidMob>());
+
+            json!({ "enum": models })
+        }
+        "pos" => {
+            let (min, max) = RenderRequestQueryParams::POSITION_BOUNDS;
+            let items = json!({ "type": "number", "format": "float", "minimum": min, "maximum": max });
+
+            json!({ "items": items, "minItems": 3, "maxItems": 3 })
+        }
+        "helmet" => json!({ "$ref": "#/components/schemas/Helmet" }),
+        "chestplate" | "leggings" | "boots" => json!({ "$ref": "#/components/schemas/ArmorMaterial" }),
+        "elytra" => json!({ "enum": ["folded", "open"] }),
+        "nametag" => json!({ "maxLength": RenderRequestQueryParams::MAX_NAMETAG_LENGTH }),
+        "parts" => json!({ "items": { "$ref": "#/components/schemas/PlayerBodyPartType" } }),
+        "pose" => json!({ "enum": serializations_of(PlayerPosePreset::iter()) }),
+        "animation" => {
+            let built_in_animations = serializations_of(PlayerAnimationPreset::iter());
+            let mut animations: Vec<_> = built_in_animations.iter().map(ToString::to_string).collect();
+            for name in validator.get_custom_animation_names() {
+                if !animations.contains(&name) {
+                    animations.push(name);
+                }
+            }
+
+            json!({ "enum": animations, "x-built-in": built_in_animations })
+        }
+        "frames" => {
+            let (min, max) = RenderRequestQueryParams::ANIMATION_FRAMES_BOUNDS;
+            json!({ "minimum": min, "maximum": max, "default": RenderRequestQueryParams::DEFAULT_ANIMATION_FRAMES })
+        }
+        _ => json!({}),
+    }
+}
+
+/// The query parameters of the render endpoints, built from the settings serde knows about and the bounds they're
+/// validated with.
+fn query_parameters<V: RenderRequestValidator>(validator: &V) -> Vec<QueryParameter> {
+    let mut defaults = RenderRequestQueryParams::default();
+    let mut constraints: Vec<(&str, Value)> = Vec::new();
+
+    for (name, _, (min, max)) in defaults.bounded_settings() {
+        constraints.push((name, json!({ "minimum": min, "maximum": max })));
+    }
+
+    // Angles are wrapped into their range instead of being rejected, so any number is accepted.
+    for (name, _, range) in defaults.camera_angles() {
+        constraints.push((name, json!({ "x-wrapped-range": range })));
+    }
+
+    for (name, _, _, range) in defaults.limb_rotations() {
+        let items = json!({ "type": "number", "format": "float", "x-wrapped-range": range });
+        constraints.push((name, json!({ "items": items, "minItems": 3, "maxItems": 3 })));
+    }
+
+    query_settings()
+        .into_iter()
+        // Glowing is rejected without the ears feature, since only Ears skins have emissive parts
+        .filter(|setting| cfg!(feature = "ears") || setting.name != "glow")
+        .map(|setting| {
+            let description = describe_setting(setting.name).unwrap_or_default();
+            let described = setting_constraints(setting.name, validator);
+
+            let mut schema = setting.kind.query_schema();
+            let mut array = Map::new();
+
+            let validated = constraints
+                .iter()
+                .filter(|(name, _)| *name == setting.name)
+                .map(|(_, constraints)| constraints);
+
+            for constraints in std::iter::once(&described).chain(validated).filter_map(Value::as_object) {
+                for (key, value) in constraints {
+                    match key.as_str() {
+                        "items" | "minItems" | "maxItems" => {
+                            array.insert(key.clone(), value.clone());
+                        }
+                        "$ref" => schema = json!({ "$ref": value }),
+                        _ => {
+                            if let Some(schema) = schema.as_object_mut() {
+                                schema.insert(key.clone(), value.clone());
+                            }
+                        }
+                    }
+                }
+            }
+
+            QueryParameter {
+                setting,
+                description,
+                schema,
+                array,
+            }
+        })
+        .collect()
+}
+
+fn armor_material_schema<V: RenderRequestValidator>(validator: &V) -> Value {
+    let (materials, trims, trim_materials): (Vec<_>, Vec<_>, Vec<_>) = validator
+        .get_armor_manager()
+        .map(|manager| {
+            (
+                manager.get_materials().collect(),
+                manager.get_trims().collect(),
+                manager.get_trim_materials().collect(),
+            )
+        })
+        .unwrap_or_default();
+
+    json!({
+        "type": "string",
+        "description": "An armor material, optionally followed by any number of `_<trim>_<trim material>` pairs and by `:enchanted` to lay the enchantment glint over it (e.g. `diamond_sentry_gold:enchanted`). Armor made of `uploaded` can also end with `:dye=<hex color>` to dye its base layer before its overlay is drawn (e.g. `uploaded:dye=a06540`). Every name may be shortened to a prefix. The available names are the ones found in the configured asset packs.",
+        "x-materials": materials,
+        "x-trims": trims,
+        "x-trim-materials": trim_materials,
+    })
+}
+
+fn helmet_schema() -> Value {
+    let headwear: Vec<_> = Headwear::iter()
+        .filter(|headwear| *headwear != Headwear::PlayerHead)
+        .map(|headwear| headwear.to_string())
+        .collect();
+
+    json!({
+        "anyOf": [
+            { "$ref": "#/components/schemas/ArmorMaterial" },
+            { "type": "string", "enum": headwear },
+            { "type": "string", "pattern": "^(player_)?head:.+$", "description": "The head of a player, given by their UUID or skin texture hash." },
+        ],
+    })
+}
+
+fn problem_details_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["type", "title", "status", "detail", "code"],
+        "properties": {
+            "type": { "type": "string", "format": "uri" },
+            "title": { "type": "string" },
+            "status": { "type": "integer" },
+            "detail": { "type": "string" },
+            "code": { "type": "string", "description": "Stable, machine-readable error code." },
+        },
+        "additionalProperties": true,
+    })
+}
+
+fn render_responses() -> Value {
+    let problem = json!({
+        "application/problem+json": { "schema": { "$ref": "#/components/schemas/ProblemDetails" } },
+        "text/plain": { "schema": { "type": "string" } },
+    });
+
+    json!({
+        "200": {
+            "description": "The rendered image (animated when an animation is requested), the raw skin, or a Blockbench project for the export mode.",
+            "content": {
+                "image/png": { "schema": { "type": "string", "format": "binary" } },
+                "application/json": { "schema": { "type": "object" } },
+            },
+        },
+        "400": { "description": "The render request is invalid.", "content": problem },
+        "404": { "description": "The requested player doesn't exist.", "content": problem },
+        "500": { "description": "The render failed.", "content": problem },
+    })
+}
+
+/// The body of a render of an uploaded skin, as a multipart form or as JSON with base64 encoded files.
+fn upload_request_body(parameters: &[QueryParameter]) -> Value {
+    let mut post_properties: Map<String, Value> =
+        parameters.iter().map(QueryParameter::to_property).collect();
+
+    let mut multipart_properties = post_properties.clone();
+    multipart_properties.insert(
+        "skin".to_owned(),
+        json!({ "type": "string", "format": "binary", "description": "The skin texture. Alias: `texture`. Optional when `geometry` is a skin pack." }),
+    );
+    multipart_properties.insert(
+        "geometry".to_owned(),
+        json!({ "type": "string", "format": "binary", "description": "The Bedrock Edition geometry of the skin, as a `.geo.json` file or a skin pack (`.mcpack`) whose first skin is rendered. Skins without custom geometry use the standard player model." }),
+    );
+
+    post_properties.insert(
+        "skin".to_owned(),
+        json!({ "type": "string", "format": "byte", "description": "The skin texture encoded as base64, optionally as a `data:` URL. Alias: `texture`. Optional when `geometry` is a skin pack." }),
+    );
+    post_properties.insert(
+        "geometry".to_owned(),
+        json!({
+            "oneOf": [{ "type": "object" }, { "type": "string", "format": "byte" }],
+            "description": "The Bedrock Edition geometry of the skin, either as the contents of a `.geo.json` file, or as a `.geo.json` file or skin pack (`.mcpack`) encoded as base64. Skins without custom geometry use the standard player model.",
+        }),
+    );
+
+    for (index, name) in UploadedArmorLayers::TEXTURE_NAMES.into_iter().enumerate() {
+        let description = match index {
+            0 => "A 64x32 or 64x64 armor texture laid out like `_layer_1.png`, shared by the helmet, chestplate and boots made of the `uploaded` material (e.g. `uploaded_sentry_gold`). Fills the empty ones of those slots unless a slot already wears `uploaded`.",
+            1 => "A 64x32 or 64x64 armor texture laid out like `_layer_2.png`, worn by leggings made of the `uploaded` material. Fills the leggings slot if it's empty, unless a slot already wears `uploaded`.",
+            _ => "The overlay of dyeable armor, drawn over the armor layer it's named after once the `:dye=<hex color>` of the slot has tinted that layer. Must be the size of that layer.",
+        };
+
+        multipart_properties.insert(
+            name.to_owned(),
+            json!({ "type": "string", "format": "binary", "description": description }),
+        );
+        post_properties.insert(
+            name.to_owned(),
+            json!({ "type": "string", "format": "byte", "description": format!("{description} Encoded as base64, optionally as a `data:` URL.") }),
+        );
+    }
+
+    json!({
+        "required": true,
+        "content": {
+            "multipart/form-data": {
+                "schema": { "type": "object", "properties": multipart_properties },
+            },
+            "application/json": {
+                "schema": { "type": "object", "properties": post_properties, "additionalProperties": false },
+            },
+        },
+    })
+}
+
+/// Build the `OpenAPI` 3 description of the render API from the types used to parse render requests.
+pub fn build_openapi_document<V: RenderRequestValidator>(validator: &V) -> Value {
+    let parameters = query_parameters(validator);
+
+    let modes = serializations_of(RenderRequestMode::iter().filter(|&mode| validator.validate_mode(mode)));
+    let features = serializations_of(EnumSet::<RenderRequestFeatures>::all().iter());
+    let body_parts: Vec<_> = PlayerBodyPartType::iter().map(|part| part.to_string()).collect();
+
+    let mode_parameter = json!({
+        "name": "mode",
+        "in": "path",
+        "required": true,
+        "schema": { "$ref": "#/components/schemas/RenderRequestMode" },
+    });
+
+    let mut get_parameters = vec![
+        mode_parameter.clone(),
+        json!({
+            "name": "entry",
+            "in": "path",
+            "required": true,
+            "description": "A player UUID (Mojang, offline or Geyser) or a Mojang texture hash. Texture hashes are capes in the cape modes and skins everywhere else.",
+            "schema": { "type": "string", "pattern": "^([a-f0-9-]{32,36}|[a-f0-9]{36,64})$" },
+        }),
+    ];
+    get_parameters.extend(parameters.iter().map(QueryParameter::to_parameter));
+
+    let mut preset_parameters = get_parameters.clone();
+    preset_parameters[0] = json!({
+        "name": "preset",
+        "in": "path",
+        "required": true,
+        "description": "The name of a preset defined in the configuration.",
+        "schema": { "type": "string" },
+    });
+
+    // The schemas of the types the other endpoints read and return are derived from the types themselves
+    let mut generator = SchemaGenerator::new(SchemaSettings::openapi3());
+
+    let mut document = json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "NMSR-as-a-Service",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": {
+            "/{mode}/{entry}": {
+                "get": {
+                    "operationId": "render",
+                    "summary": "Render a player or texture hash.",
+                    "parameters": get_parameters,
+                    "responses": render_responses(),
+                },
+            },
+            "/info/{entry}": info_path(&mut generator),
+            "/preset/{preset}/{entry}": {
+                "get": {
+                    "operationId": "renderPreset",
+                    "summary": "Render a player or texture hash using one of the configured presets.",
+                    "description": "Only the query parameters that the preset marks as overridable are accepted.",
+                    "parameters": preset_parameters,
+                    "responses": render_responses(),
+                },
+            },
+            "/{mode}": {
+                "post": {
+                    "operationId": "renderUploadedSkin",
+                    "summary": "Render an uploaded skin.",
+                    "parameters": [mode_parameter],
+                    "requestBody": upload_request_body(&parameters),
+                    "responses": render_responses(),
+                },
+            },
+        },
+        "components": {
+            "schemas": {
+                "RenderRequestMode": { "type": "string", "enum": modes },
+                "RenderRequestFeatures": { "type": "string", "enum": features },
+                "PlayerBodyPartType": { "type": "string", "enum": body_parts },
+                "ArmorMaterial": armor_material_schema(validator),
+                "Helmet": helmet_schema(),
+                "ProblemDetails": problem_details_schema(),
+            },
+        },
+    });
+
+    #[cfg(feature = "ears")]
+    {
+        document["paths"]["/ears"] = ears_encode_path(&mut generator);
+    }
+
+    if let Some(schemas) = document["components"]["schemas"].as_object_mut() {
+        schemas.extend(generator.take_definitions(true));
+    }
+
+    document
+}
+
+/// The endpoint that describes an entry without rendering it.
+fn info_path(generator: &mut SchemaGenerator) -> Value {
+    let problem = json!({
+        "application/problem+json": { "schema": { "$ref": "#/components/schemas/ProblemDetails" } },
+    });
+
+    json!({
+        "get": {
+            "operationId": "info",
+            "summary": "Describe a player or texture hash without rendering it.",
+            "parameters": [{
+                "name": "entry",
+                "in": "path",
+                "required": true,
+                "description": "A player UUID (Mojang, offline or Geyser) or the texture hash of a skin.",
+                "schema": { "type": "string", "pattern": "^([a-f0-9-]{32,36}|[a-f0-9]{36,64})$" },
+            }],
+            "responses": {
+                "200": {
+                    "description": "What's known about the entry.",
+                    "content": { "application/json": { "schema": generator.subschema_for::<RenderEntryInfo>() } },
+                },
+                "400": { "description": "The entry is invalid.", "content": problem },
+                "404": { "description": "The requested player doesn't exist.", "content": problem },
+                "500": { "description": "The entry couldn't be resolved.", "content": problem },
+            },
+        },
+    })
+}
+
+/// The endpoint that encodes Ears features into an uploaded skin.
+#[cfg(feature = "ears")]
+fn ears_encode_path(generator: &mut SchemaGenerator) -> Value {
+    let problem = json!({
+        "application/problem+json": { "schema": { "$ref": "#/components/schemas/ProblemDetails" } },
+    });
+
+    json!({
+        "post": {
+            "operationId": "encodeEars",
+            "summary": "Encode Ears features into an uploaded skin.",
+            "description": "Returns the skin with the Ears features and textures written into it, as Ears reads them.",
+            "requestBody": {
+                "required": true,
+                "content": {
+                    "application/json": { "schema": generator.subschema_for::<EarsEncodeRequest>() },
+                },
+            },
+            "responses": {
+                "200": {
+                    "description": "The skin with the Ears features encoded into it.",
+                    "content": { "image/png": { "schema": { "type": "string", "format": "binary" } } },
+                },
+                "400": { "description": "The skin, textures or features are invalid.", "content": problem },
+            },
+        },
+    })
+}
+
+#[axum::debug_handler]
+pub async fn openapi(State(state): State<NMSRState<'static>>) -> Json<Value> {
+    Json(build_openapi_document(&state))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::{build_openapi_document, describe_setting, query_settings, SettingKind};
+    use crate::{model::request::RenderRequestMode, routes::RenderRequestValidator};
+
+    struct TestValidator;
+
+    impl RenderRequestValidator for TestValidator {
+        fn validate_mode(&self, _mode: RenderRequestMode) -> bool {
+            true
+        }
+
+        fn get_custom_animation_names(&self) -> Vec<String> {
+            vec!["dance".to_owned()]
+        }
+    }
+
+    fn get_parameters(document: &Value) -> &Vec<Value> {
+        document["paths"]["/{mode}/{entry}"]["get"]["parameters"]
+            .as_array()
+            .expect("Render endpoint should have parameters")
+    }
+
+    #[test]
+    fn test_every_query_parameter_is_documented() {
+        let settings = query_settings();
+        assert!(!settings.is_empty(), "Failed to collect the query settings");
+
+        for setting in &settings {
+            assert!(
+                describe_setting(setting.name).is_some(),
+                "Query parameter {} isn't described in the OpenAPI document",
+                setting.name
+            );
+        }
+
+        let setting = |name: &str| {
+            settings
+                .iter()
+                .find(|setting| setting.name == name)
+                .unwrap_or_else(|| panic!("Missing setting {name}"))
+        };
+
+        assert_eq!(vec!["no"], setting("exclude").aliases);
+        assert_eq!(vec!["y"], setting("yaw").aliases);
+        assert_eq!(vec!["xpos"], setting("x_pos").aliases);
+
+        for (name, kind) in [
+            ("exclude", SettingKind::List),
+            ("noshading", SettingKind::Flag),
+            ("yaw", SettingKind::Number),
+            ("width", SettingKind::Integer),
+            ("model", SettingKind::String),
+            ("helmet", SettingKind::String),
+            ("elytra", SettingKind::Flag),
+            ("nametag", SettingKind::Flag),
+            ("head", SettingKind::List),
+            ("frames", SettingKind::Integer),
+        ] {
+            assert_eq!(kind, setting(name).kind, "{name} has the wrong kind");
+        }
+    }
+
+    #[test]
+    fn test_document_contents() {
+        let document = build_openapi_document(&TestValidator);
+        let parameters = get_parameters(&document);
+
+        let parameter = |name: &str| {
+            parameters
+                .iter()
+                .find(|parameter| parameter["name"] == name)
+                .unwrap_or_else(|| panic!("Missing parameter {name}"))
+        };
+
+        for wrapped in ["yaw", "pitch", "roll"] {
+            let schema = &parameter(wrapped)["schema"];
+            assert!(schema.get("minimum").is_none() && schema.get("maximum").is_none(), "{wrapped} is wrapped, so it shouldn't be bounded");
+        }
+
+        let animations = parameter("animation")["schema"]["enum"]
+            .as_array()
+            .expect("Animations should be listed");
+        assert!(animations.contains(&Value::from("dance")), "Configured animations should be listed");
+        assert!(animations.contains(&Value::from("walk")), "Built-in animations should be listed");
+    }
+}
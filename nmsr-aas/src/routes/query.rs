@@ -1,14 +1,27 @@
 use crate::{
+    config::RenderPresetConfiguration,
     error::{RenderRequestError, Result},
     model::{
-        armor::VanillaMinecraftArmorMaterialData,
-        request::{entry::RenderRequestEntryModel, RenderRequestFeatures, RenderRequestMode},
+        armor::{VanillaMinecraftArmorMaterialData, VanillaMinecraftHelmetData},
+        request::{
+            entry::{RenderRequestEntryModel, RenderRequestModel},
+            RenderRequestFeatures, RenderRequestMode,
+        },
     },
 };
 use enumset::EnumSet;
-use serde::Deserialize;
-use serde_with::TryFromInto;
-use serde_with::{formats::CommaSeparator, serde_as, DisplayFromStr, StringWithSeparator};
+use nmsr_rendering::high_level::{
+    model::{mob::HumanoidMob, ElytraState},
+    pose::{PartRotation, PlayerPose, PlayerPosePreset},
+    types::PlayerBodyPartType,
+};
+use serde::{
+    de::{self, IntoDeserializer, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use serde_json::Value;
+use serde_with::{serde_as, DeserializeAs, DisplayFromStr, Same, TryFromInto};
+use std::{collections::HashMap, fmt, marker::PhantomData, str::FromStr};
 
 ///  The options are:
 ///  - `?exclude=<features>` or `?no=<features>`: exclude a feature from the entry (comma-separated, or multiple query strings)
@@ -23,9 +36,11 @@ use serde_with::{formats::CommaSeparator, serde_as, DisplayFromStr, StringWithSe
 ///  - `?w=<width>` or `?width=<width>`: set the width of the image
 ///  - `?h=<height>` or `?height=<height>`: set the height of the image
 ///  - `?model=<steve|alex|wide|slim>`: set the model of the entry
+///  - `?model=<armor_stand|zombie|husk|drowned|skeleton|stray>`: render a mob wearing the armor instead of the entry
 ///  - `?alex`: set the model of the entry to alex [compatibility with old URLs]
 ///  - `?steve`: set the model of the entry to steve [compatibility with old URLs]
 ///  - `?process`: process the skin (upgrade skin to 1.8 format, strip alpha from the body regions, apply erase regions if Ears feature is enabled)
+///  - `?voxels`: build the skin layers out of voxels, like the 3D Skin Layers mod does
 ///  
 ///  - `?arms=<rotation>` or `arm=<rotation>`: set the rotation of the arms
 ///  - `?dist=<distance>` or `distance=<distance>`: set the distance of the camera
@@ -40,15 +55,49 @@ use serde_with::{formats::CommaSeparator, serde_as, DisplayFromStr, StringWithSe
 ///  - `?chestplate=<chestplate>`: set the chestplate of the entry
 ///  - `?leggings=<leggings>`: set the leggings of the entry
 ///  - `?boots=<boots>`: set the boots of the entry
+///    (each piece of armor can end with `:enchanted` to render the enchantment glint, e.g. `?chestplate=diamond:enchanted`,
+///    and be made of `uploaded` to wear the armor textures uploaded with a `POST` request, e.g. `chestplate=uploaded_sentry_gold`,
+///    which can end with `:dye=<hex color>` to dye their base layer, e.g. `chestplate=uploaded:dye=a06540`)
+///  - `?helmet=<headwear>`: make the entry wear block-style headwear instead of a helmet (`carved_pumpkin`, `skeleton_skull`,
+///    `wither_skeleton_skull`, `zombie_head`, `creeper_head`, or `head:<uuid>` for the head of another player)
+///  - `?elytra` or `?elytra=<folded|open>`: make the entry wear an elytra, using its cape texture if it has one
+///  - `?mainhand=<item>` and `?offhand=<item>`: make the entry hold an item or a block in its right and left hand
+///    (e.g. `?mainhand=diamond_sword&offhand=shield`), using the models of the asset packs
+///  - `?nametag` or `?nametag=<text>`: show a name tag above the head of the entry, with the name of its profile or the given
+///    text (which can use the formatting codes of the game, e.g. `?nametag=§cRed &lbold`)
+///  - `?glow=<strength>` or `?bloom=<strength>`: make the emissive parts of Ears skins glow, with the given strength
+///    (only available with the `ears` feature)
+///  - `?cape_angle=<angle>` or `?capeangle=<angle>`: set how far the cape swings out from the back
+///  - `?wind=<speed>` or `?velocity=<speed>`: render a wavy cape, blown back by wind of the given speed (in blocks per second)
+///
+///  - `?parts=<parts>`: render only the given body parts (comma-separated, e.g. `head,body,left_arm_layer`)
+///  - `?explode=<offset>` or `?exploded=<offset>`: move each body part away from the body by the given offset
+///
+///  - `?pose=<pose>`: set the pose of the entry (`walking`, `running`, `waving`, `sitting`, `sneaking` or `crossed_arms`)
+///  - `?head=<pitch>,<yaw>,<roll>`: set the rotation of the head
+///  - `?left_arm=<pitch>,<yaw>,<roll>` and `?right_arm=<pitch>,<yaw>,<roll>`: set the rotation of the arms
+///  - `?left_leg=<pitch>,<yaw>,<roll>` and `?right_leg=<pitch>,<yaw>,<roll>`: set the rotation of the legs
+///  - `?lean=<angle>`: set how much the upper body leans forward
+///
+///  - `?animation=<animation>`: render an animation (`walk`, `idle`, `arm_swing`, `look_around` or a custom one) as an animated PNG
+///  - `?frames=<frames>`: set the amount of frames to render the animation with
+///  - `?t=<ticks>` or `?time=<ticks>`: set the time of the render in ticks, which moves the animated parts of Ears skins,
+///    and is where animations start from
 #[serde_as]
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct RenderRequestQueryParams {
-    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, RenderRequestFeatures>>")]
-    #[serde(alias = "no")]
+    #[serde_as(as = "Option<CommaSeparated<RenderRequestFeatures>>")]
+    #[serde(default, alias = "no")]
     pub exclude: Option<EnumSet<RenderRequestFeatures>>,
 
+    #[serde_as(as = "Flag")]
+    #[serde(default)]
     pub noshading: Option<String>,
+    #[serde_as(as = "Flag")]
+    #[serde(default)]
     pub nolayers: Option<String>,
+    #[serde_as(as = "Flag")]
+    #[serde(default)]
     pub nooptifine: Option<String>,
 
     #[serde(alias = "y")]
@@ -64,11 +113,20 @@ pub struct RenderRequestQueryParams {
     pub height: Option<u32>,
 
     #[serde_as(as = "Option<DisplayFromStr>")]
-    pub model: Option<RenderRequestEntryModel>,
+    pub model: Option<RenderRequestModel>,
+    #[serde_as(as = "Flag")]
+    #[serde(default)]
     pub alex: Option<String>,
+    #[serde_as(as = "Flag")]
+    #[serde(default)]
     pub steve: Option<String>,
 
+    #[serde_as(as = "Flag")]
+    #[serde(default)]
     pub process: Option<String>,
+    #[serde_as(as = "Flag")]
+    #[serde(default)]
+    pub voxels: Option<String>,
 
     #[serde(alias = "arm")]
     pub arms: Option<f32>,
@@ -83,30 +141,258 @@ pub struct RenderRequestQueryParams {
     #[serde(alias = "zpos")]
     pub z_pos: Option<f32>,
 
-    #[serde_as(as = "Option<StringWithSeparator::<CommaSeparator, f32>>")]
+    #[serde_as(as = "Option<CommaSeparated<f32>>")]
+    #[serde(default)]
     pub pos: Option<Vec<f32>>,
 
     #[serde_as(as = "Option<TryFromInto<String>>")]
-    pub helmet: Option<VanillaMinecraftArmorMaterialData>,
+    pub helmet: Option<VanillaMinecraftHelmetData>,
     #[serde_as(as = "Option<TryFromInto<String>>")]
     pub chestplate: Option<VanillaMinecraftArmorMaterialData>,
     #[serde_as(as = "Option<TryFromInto<String>>")]
     pub leggings: Option<VanillaMinecraftArmorMaterialData>,
     #[serde_as(as = "Option<TryFromInto<String>>")]
     pub boots: Option<VanillaMinecraftArmorMaterialData>,
+    #[serde_as(as = "Flag<DisplayFromStr>")]
+    #[serde(default)]
+    pub elytra: Option<ElytraState>,
+    #[serde(alias = "main_hand")]
+    pub mainhand: Option<String>,
+    #[serde(alias = "off_hand")]
+    pub offhand: Option<String>,
+    #[serde_as(as = "Flag")]
+    #[serde(default, alias = "name_tag")]
+    pub nametag: Option<String>,
+    #[serde(alias = "bloom")]
+    pub glow: Option<f32>,
+
+    #[serde(alias = "capeangle")]
+    pub cape_angle: Option<f32>,
+    #[serde(alias = "velocity")]
+    pub wind: Option<f32>,
+
+    #[serde_as(as = "Option<CommaSeparated<PlayerBodyPartType>>")]
+    #[serde(default)]
+    pub parts: Option<Vec<PlayerBodyPartType>>,
+
+    #[serde(alias = "exploded")]
+    pub explode: Option<f32>,
+
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub pose: Option<PlayerPosePreset>,
+    #[serde_as(as = "Option<CommaSeparated<f32>>")]
+    #[serde(default)]
+    pub head: Option<Vec<f32>>,
+    #[serde_as(as = "Option<CommaSeparated<f32>>")]
+    #[serde(default)]
+    pub left_arm: Option<Vec<f32>>,
+    #[serde_as(as = "Option<CommaSeparated<f32>>")]
+    #[serde(default)]
+    pub right_arm: Option<Vec<f32>>,
+    #[serde_as(as = "Option<CommaSeparated<f32>>")]
+    #[serde(default)]
+    pub left_leg: Option<Vec<f32>>,
+    #[serde_as(as = "Option<CommaSeparated<f32>>")]
+    #[serde(default)]
+    pub right_leg: Option<Vec<f32>>,
+    pub lean: Option<f32>,
+
+    pub animation: Option<String>,
+    pub frames: Option<u32>,
+    #[serde(alias = "time")]
+    pub t: Option<f32>,
 }
 
+/// The body of a `POST` render request using `application/json`.
+///
+/// The options are the same as [`RenderRequestQueryParams`], with their JSON types: numbers are numbers, lists can
+/// also be arrays (e.g. `"head": [0, 10, 0]`), and flags such as `?noshading` can also be `true` or `false`.
 #[derive(Debug, Clone, Deserialize)]
-pub struct RenderRequestMultipartParams {
+pub struct RenderRequestJsonParams {
+    /// The skin texture encoded as base64, optionally as a `data:image/png;base64,` URL.
+    ///
+    /// Only optional when the skin comes from the skin pack in `geometry`.
+    #[serde(default, alias = "texture")]
+    pub skin: Option<String>,
+    /// The Bedrock Edition geometry of the skin, either as the object of a `.geo.json` file or as a base64 encoded
+    /// `.geo.json` file or skin pack.
+    #[serde(default)]
+    pub geometry: Option<Value>,
+    #[serde(flatten)]
+    pub options: RenderRequestQueryParams,
+    /// The properties that aren't options, which can only be the uploaded armor textures (see
+    /// [`UploadedArmorLayers::TEXTURE_NAMES`](crate::model::armor::UploadedArmorLayers::TEXTURE_NAMES)).
     #[serde(flatten)]
-    pub query: RenderRequestQueryParams,
-    #[serde(alias = "texture")]
-    pub skin: Vec<u8>,
+    pub uploads: HashMap<String, Value>,
+}
+
+impl From<&RenderPresetConfiguration> for RenderRequestQueryParams {
+    fn from(preset: &RenderPresetConfiguration) -> Self {
+        Self {
+            exclude: Some(preset.exclude.iter().copied().collect()),
+            yaw: preset.yaw,
+            pitch: preset.pitch,
+            roll: preset.roll,
+            width: preset.width,
+            height: preset.height,
+            model: preset.model,
+            arms: preset.arms,
+            distance: preset.distance,
+            x_pos: preset.x_pos,
+            y_pos: preset.y_pos,
+            z_pos: preset.z_pos,
+            helmet: preset.helmet.clone(),
+            chestplate: preset.chestplate.clone(),
+            leggings: preset.leggings.clone(),
+            boots: preset.boots.clone(),
+            elytra: preset.elytra,
+            mainhand: preset.mainhand.clone(),
+            offhand: preset.offhand.clone(),
+            nametag: preset.nametag.clone(),
+            glow: preset.glow,
+            cape_angle: preset.cape_angle,
+            wind: preset.wind,
+            parts: preset.parts.clone(),
+            explode: preset.explode,
+            pose: preset.pose,
+            head: preset.head.clone(),
+            left_arm: preset.left_arm.clone(),
+            right_arm: preset.right_arm.clone(),
+            left_leg: preset.left_leg.clone(),
+            right_leg: preset.right_leg.clone(),
+            lean: preset.lean,
+            animation: preset.animation.clone(),
+            frames: preset.frames,
+            t: preset.t,
+            ..Default::default()
+        }
+    }
+}
+
+/// The name of a limb rotation setting, how it's called in errors, its value and the range its angles are wrapped into.
+type LimbRotation<'a> = (&'static str, &'static str, &'a mut Option<Vec<f32>>, (f32, f32));
+
+/// Defines [`RenderRequestQueryParams::override_with`] along with the names of the settings it knows about.
+///
+/// Each setting covers one or more fields, which are only copied over when the setting is allowed.
+macro_rules! overridable_settings {
+    ($($setting:literal => [$($field:ident),+]),+ $(,)?) => {
+        impl RenderRequestQueryParams {
+            /// The settings that a preset can list as `overridable`.
+            pub const OVERRIDABLE_SETTINGS: &'static [&'static str] = &[$($setting),+];
+
+            /// Override the settings of this query (usually created from a preset) with the ones specified in `other`.
+            ///
+            /// Only the settings listed in `allowed` can be overridden, and trying to override any other setting is an error.
+            pub fn override_with(&mut self, other: Self, allowed: &[String]) -> Result<()> {
+                $(
+                    if $(other.$field.is_some())||+ {
+                        if !allowed.iter().any(|allowed| allowed == $setting) {
+                            return Err(RenderRequestError::PresetOverrideNotAllowedError($setting).into());
+                        }
+
+                        $(
+                            if other.$field.is_some() {
+                                self.$field = other.$field;
+                            }
+                        )+
+                    }
+                )+
+
+                Ok(())
+            }
+        }
+    };
 }
 
+overridable_settings!(
+    "exclude" => [exclude, noshading, nolayers, nooptifine, process, voxels],
+    "yaw" => [yaw],
+    "pitch" => [pitch],
+    "roll" => [roll],
+    "width" => [width],
+    "height" => [height],
+    "model" => [model, alex, steve],
+    "arms" => [arms],
+    "distance" => [distance],
+    "pos" => [x_pos, y_pos, z_pos, pos],
+    "helmet" => [helmet],
+    "chestplate" => [chestplate],
+    "leggings" => [leggings],
+    "boots" => [boots],
+    "elytra" => [elytra],
+    "mainhand" => [mainhand],
+    "offhand" => [offhand],
+    "nametag" => [nametag],
+    "glow" => [glow],
+    "cape" => [cape_angle, wind],
+    "parts" => [parts],
+    "explode" => [explode],
+    "pose" => [pose, head, left_arm, right_arm, left_leg, right_leg, lean],
+    "animation" => [animation, frames],
+    "time" => [t],
+);
+
 impl RenderRequestQueryParams {
+    pub const YAW_RANGE: (f32, f32) = (-180.0, 180.0);
+    pub const PITCH_RANGE: (f32, f32) = (-90.0, 90.0);
+    pub const ROLL_RANGE: (f32, f32) = (-180.0, 360.0);
+
+    pub const ARM_ROTATION_BOUNDS: (f32, f32) = (0.0, 180.0);
+    pub const DISTANCE_BOUNDS: (f32, f32) = (-15.0, 50.0);
+    pub const POSITION_BOUNDS: (f32, f32) = (-50.0, 50.0);
+    pub const EXPLODED_VIEW_BOUNDS: (f32, f32) = (0.0, 16.0);
+    pub const BODY_LEAN_BOUNDS: (f32, f32) = (-90.0, 90.0);
+    pub const CAPE_ANGLE_BOUNDS: (f32, f32) = (0.0, 120.0);
+    pub const WIND_BOUNDS: (f32, f32) = (0.0, 20.0);
+
+    pub const MAX_NAMETAG_LENGTH: usize = 128;
+    pub const GLOW_BOUNDS: (f32, f32) = (0.0, 4.0);
+
+    pub const DEFAULT_ANIMATION_FRAMES: u32 = 24;
+    pub const ANIMATION_FRAMES_BOUNDS: (u32, u32) = (2, 60);
+    /// The time of the render can be up to a day of the game, in ticks.
+    pub const TIME_BOUNDS: (f32, f32) = (0.0, 24000.0);
+
+    /// The settings that are rejected when they're out of bounds, along with their values and bounds.
+    pub const fn bounded_settings(&self) -> [(&'static str, Option<f32>, (f32, f32)); 11] {
+        [
+            ("arms", self.arms, Self::ARM_ROTATION_BOUNDS),
+            ("distance", self.distance, Self::DISTANCE_BOUNDS),
+            ("x_pos", self.x_pos, Self::POSITION_BOUNDS),
+            ("y_pos", self.y_pos, Self::POSITION_BOUNDS),
+            ("z_pos", self.z_pos, Self::POSITION_BOUNDS),
+            ("explode", self.explode, Self::EXPLODED_VIEW_BOUNDS),
+            ("lean", self.lean, Self::BODY_LEAN_BOUNDS),
+            ("cape_angle", self.cape_angle, Self::CAPE_ANGLE_BOUNDS),
+            ("wind", self.wind, Self::WIND_BOUNDS),
+            ("glow", self.glow, Self::GLOW_BOUNDS),
+            ("t", self.t, Self::TIME_BOUNDS),
+        ]
+    }
+
+    /// The camera angles, which are wrapped into their range instead of being rejected.
+    pub const fn camera_angles(&mut self) -> [(&'static str, &mut Option<f32>, (f32, f32)); 3] {
+        [
+            ("yaw", &mut self.yaw, Self::YAW_RANGE),
+            ("pitch", &mut self.pitch, Self::PITCH_RANGE),
+            ("roll", &mut self.roll, Self::ROLL_RANGE),
+        ]
+    }
+
+    /// The rotations of the limbs, as pitch, yaw and roll.
+    pub const fn limb_rotations(&mut self) -> [LimbRotation<'_>; 5] {
+        [
+            ("head", "head rotation (head parameter)", &mut self.head, Self::YAW_RANGE),
+            ("left_arm", "left arm rotation (left_arm parameter)", &mut self.left_arm, Self::YAW_RANGE),
+            ("right_arm", "right arm rotation (right_arm parameter)", &mut self.right_arm, Self::YAW_RANGE),
+            ("left_leg", "left leg rotation (left_leg parameter)", &mut self.left_leg, Self::YAW_RANGE),
+            ("right_leg", "right leg rotation (right_leg parameter)", &mut self.right_leg, Self::YAW_RANGE),
+        ]
+    }
+
     pub fn get_excluded_features(&self) -> EnumSet<RenderRequestFeatures> {
-        let mut excluded = self.exclude.unwrap_or(EnumSet::EMPTY);
+        let mut excluded = self.exclude.unwrap_or(EnumSet::empty());
 
         if self.nolayers.is_some() {
             excluded |= RenderRequestFeatures::BodyLayers | RenderRequestFeatures::HatLayer;
@@ -124,16 +410,62 @@ impl RenderRequestQueryParams {
             excluded |= RenderRequestFeatures::OptifineCape;
         }
 
+        if self.voxels.is_some() {
+            excluded |= RenderRequestFeatures::FlatLayers;
+        }
+
         excluded
     }
 
+    pub fn get_pose(&self) -> Option<PlayerPose> {
+        fn rotation_of(rotation: Option<&Vec<f32>>) -> Option<PartRotation> {
+            rotation
+                .and_then(|r| <[f32; 3]>::try_from(r.as_slice()).ok())
+                .map(PartRotation::from)
+        }
+
+        let limbs = [
+            (PlayerBodyPartType::Head, &self.head),
+            (PlayerBodyPartType::LeftArm, &self.left_arm),
+            (PlayerBodyPartType::RightArm, &self.right_arm),
+            (PlayerBodyPartType::LeftLeg, &self.left_leg),
+            (PlayerBodyPartType::RightLeg, &self.right_leg),
+        ];
+
+        if self.pose.is_none()
+            && self.lean.is_none()
+            && limbs.iter().all(|(_, rotation)| rotation.is_none())
+        {
+            return None;
+        }
+
+        let mut pose = self.pose.map(PlayerPose::from).unwrap_or_default();
+
+        for (part, rotation) in limbs {
+            if let (Some(rotation), Some(pose_rotation)) =
+                (rotation_of(rotation.as_ref()), pose.get_rotation_mut(part))
+            {
+                *pose_rotation = rotation;
+            }
+        }
+
+        if let Some(lean) = self.lean {
+            pose.body_lean = lean;
+        }
+
+        Some(pose)
+    }
+
     pub fn get_model(&self) -> Option<RenderRequestEntryModel> {
         let steve = self
             .steve
             .as_ref()
             .and(Some(RenderRequestEntryModel::Steve));
         let alex = self.alex.as_ref().and(Some(RenderRequestEntryModel::Alex));
-        let model = self.model;
+        let model = match self.model {
+            Some(RenderRequestModel::Player(model)) => Some(model),
+            _ => None,
+        };
 
         // Extract the model in the following order:
         // - First, check if the user specified that they wanted steve or alex (for compatibility with old URLs)
@@ -142,6 +474,14 @@ impl RenderRequestQueryParams {
         alex.or(steve).or(model)
     }
 
+    /// Get the mob to render instead of the entry, if any.
+    pub const fn get_mob(&self) -> Option<HumanoidMob> {
+        match self.model {
+            Some(RenderRequestModel::Mob(mob)) => Some(mob),
+            _ => None,
+        }
+    }
+
     pub fn validate(&mut self, mode: RenderRequestMode) -> Result<()> {
         fn clamp(value: &mut Option<f32>, min: f32, max: f32) {
             let epsilon = 0.01;
@@ -155,18 +495,12 @@ impl RenderRequestQueryParams {
         RenderRequestMode::validate_unit("width", self.width, min_w, max_w)?;
         RenderRequestMode::validate_unit("height", self.height, min_h, max_h)?;
 
-        RenderRequestMode::wrap_unit(self.yaw.as_mut(), -180.0, 180.0)?;
-        RenderRequestMode::wrap_unit(self.pitch.as_mut(), -90.0, 90.0)?;
-        RenderRequestMode::wrap_unit(self.roll.as_mut(), -180.0, 360.0)?;
-
-        RenderRequestMode::validate_unit("arm", self.arms, 0.0, 180.0)?;
+        for (_, angle, (min, max)) in self.camera_angles() {
+            RenderRequestMode::wrap_unit(angle.as_mut(), min, max);
 
-        RenderRequestMode::validate_unit("distance", self.distance, -15.0, 50.0)?;
-
-        // Clamp yaw, pitch, roll so that there is no weirdness with the camera
-        clamp(&mut self.yaw, -180.0, 180.0);
-        clamp(&mut self.pitch, -90.0, 90.0);
-        clamp(&mut self.roll, -180.0, 360.0);
+            // Clamp yaw, pitch, roll so that there is no weirdness with the camera
+            clamp(angle, min, max);
+        }
 
         if !mode.is_custom() && self.width.is_some() && self.height.is_some() {
             return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
@@ -200,10 +534,216 @@ impl RenderRequestQueryParams {
             self.z_pos.replace(pos[2]);
         }
 
-        RenderRequestMode::validate_unit("xpos", self.x_pos, -50.0, 50.0)?;
-        RenderRequestMode::validate_unit("ypos", self.y_pos, -50.0, 50.0)?;
-        RenderRequestMode::validate_unit("zpos", self.z_pos, -50.0, 50.0)?;
+        for (name, value, (min, max)) in self.bounded_settings() {
+            RenderRequestMode::validate_unit(name, value, min, max)?;
+        }
+
+        // Only Ears skins have emissive parts, so there's nothing to glow without them
+        #[cfg(not(feature = "ears"))]
+        if self.glow.is_some() {
+            return Err(RenderRequestError::SettingNeedsFeatureError("glow", "ears").into());
+        }
+
+        for (_, name, rotation, (min, max)) in self.limb_rotations() {
+            let Some(rotation) = rotation else {
+                continue;
+            };
+
+            if rotation.len() != 3 {
+                return Err(RenderRequestError::InvalidRenderSettingError(
+                    name,
+                    "3 valid numbers separated by commas (pitch, yaw and roll)".to_string(),
+                )
+                .into());
+            }
+
+            for angle in rotation.iter_mut() {
+                RenderRequestMode::wrap_unit(Some(angle), min, max);
+            }
+        }
+
+        let (frames_min, frames_max) = Self::ANIMATION_FRAMES_BOUNDS;
+        RenderRequestMode::validate_unit("frames", self.frames, frames_min, frames_max)?;
+
+        if self.frames.is_some() && self.animation.is_none() {
+            return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                "frames without an animation",
+                "Specify the animation to render with the animation setting.",
+            )
+            .into());
+        }
+
+        if self.animation.is_some() && (mode.is_skin() || mode.is_blockbench_export()) {
+            return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                "an animation",
+                "Animations are rendered as animated PNGs, so they're only available for the render modes.",
+            )
+            .into());
+        }
+
+        if mode.is_cape() && (self.animation.is_some() || self.parts.is_some()) {
+            return Err(RenderRequestError::InvalidModeSettingSpecifiedError(
+                "an animation or body parts",
+                "The cape modes render just the cape, without a player to animate or pick the body parts of.",
+            )
+            .into());
+        }
+
+        if self.parts.as_ref().is_some_and(Vec::is_empty) {
+            return Err(RenderRequestError::InvalidRenderSettingError(
+                "body parts (parts parameter)",
+                "at least one body part".to_string(),
+            )
+            .into());
+        }
+
+        if self.nametag.as_ref().is_some_and(|text| text.chars().count() > Self::MAX_NAMETAG_LENGTH) {
+            return Err(RenderRequestError::InvalidRenderSettingError(
+                "name tag (nametag parameter)",
+                format!("at most {} characters", Self::MAX_NAMETAG_LENGTH),
+            )
+            .into());
+        }
 
         Ok(())
     }
 }
+
+/// Deserializes a flag like `?noshading`, which is set by any value (or `true` in a JSON body) and unset by `false`.
+///
+/// The value of the flag is deserialized as `T`, with `true` standing for an empty value.
+struct Flag<T = Same>(PhantomData<T>);
+
+impl<'de, T, U> DeserializeAs<'de, Option<T>> for Flag<U>
+where
+    U: DeserializeAs<'de, T>,
+{
+    fn deserialize_as<D>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct FlagVisitor<T, U>(PhantomData<(T, U)>);
+
+        impl<'de, T, U> Visitor<'de> for FlagVisitor<T, U>
+        where
+            U: DeserializeAs<'de, T>,
+        {
+            type Value = Option<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string or a boolean")
+            }
+
+            fn visit_bool<E: de::Error>(self, value: bool) -> std::result::Result<Self::Value, E> {
+                if value {
+                    self.visit_str("")
+                } else {
+                    Ok(None)
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<Self::Value, E> {
+                U::deserialize_as(value.into_deserializer()).map(Some)
+            }
+
+            fn visit_none<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+                Ok(None)
+            }
+
+            fn visit_unit<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+                Ok(None)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error> {
+                deserializer.deserialize_any(self)
+            }
+        }
+
+        deserializer.deserialize_any(FlagVisitor::<T, U>(PhantomData))
+    }
+}
+
+/// Deserializes a list of `T`s from a comma-separated string (like `?head=0,10,0`), or from an array in a JSON body.
+struct CommaSeparated<T>(PhantomData<T>);
+
+impl<'de, T, I> DeserializeAs<'de, I> for CommaSeparated<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+    I: FromIterator<T>,
+{
+    fn deserialize_as<D>(deserializer: D) -> std::result::Result<I, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        /// An item of a JSON array, which can be either a string or a number.
+        struct Item(String);
+
+        impl<'de> Deserialize<'de> for Item {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                struct ItemVisitor;
+
+                impl Visitor<'_> for ItemVisitor {
+                    type Value = Item;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str("a string or a number")
+                    }
+
+                    fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<Item, E> {
+                        Ok(Item(value.to_owned()))
+                    }
+
+                    fn visit_u64<E: de::Error>(self, value: u64) -> std::result::Result<Item, E> {
+                        Ok(Item(value.to_string()))
+                    }
+
+                    fn visit_i64<E: de::Error>(self, value: i64) -> std::result::Result<Item, E> {
+                        Ok(Item(value.to_string()))
+                    }
+
+                    fn visit_f64<E: de::Error>(self, value: f64) -> std::result::Result<Item, E> {
+                        Ok(Item(value.to_string()))
+                    }
+                }
+
+                deserializer.deserialize_any(ItemVisitor)
+            }
+        }
+
+        struct ListVisitor<T, I>(PhantomData<(T, I)>);
+
+        impl<'de, T, I> Visitor<'de> for ListVisitor<T, I>
+        where
+            T: FromStr,
+            T::Err: fmt::Display,
+            I: FromIterator<T>,
+        {
+            type Value = I;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a comma-separated string or an array")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<I, E> {
+                if value.is_empty() {
+                    return Ok(std::iter::empty().collect());
+                }
+
+                value.split(',').map(|item| item.parse().map_err(E::custom)).collect()
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<I, A::Error> {
+                let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+
+                while let Some(Item(item)) = seq.next_element()? {
+                    items.push(item.parse().map_err(de::Error::custom)?);
+                }
+
+                Ok(items.into_iter().collect())
+            }
+        }
+
+        deserializer.deserialize_any(ListVisitor::<T, I>(PhantomData))
+    }
+}
@@ -2,6 +2,7 @@ use super::{NMSRState, bbmodel_export::internal_bbmodel_export};
 use crate::{
     error::{Result, RenderRequestError},
     model::request::{RenderRequest, RenderRequestMode},
+    routes::extractors::PresetRenderRequest,
     routes::render_model::internal_render_model,
     routes::render_skin::internal_render_skin,
 };
@@ -17,16 +18,16 @@ use hyper::{
 use tracing::instrument;
 use xxhash_rust::xxh3::xxh3_64;
 
-const IMAGE_PNG_MIME: &str = "image/png";
+pub(super) const IMAGE_PNG_MIME: &str = "image/png";
 
 #[axum::debug_handler]
 pub async fn render_post_warning() -> Result<Response> {
-    return Err(RenderRequestError::WrongHttpMethodError("POST", "GET").into())
+    Err(RenderRequestError::WrongHttpMethodError("POST", "GET").into())
 }
 
 #[axum::debug_handler]
 pub async fn render_get_warning() -> Result<Response> {
-    return Err(RenderRequestError::WrongHttpMethodError("GET", "POST").into())
+    Err(RenderRequestError::WrongHttpMethodError("GET", "POST").into())
 }
 
 #[axum::debug_handler]
@@ -62,6 +63,15 @@ pub async fn render(
     Ok(res)
 }
 
+#[axum::debug_handler]
+pub async fn render_preset(
+    state: State<NMSRState<'static>>,
+    method: Method,
+    PresetRenderRequest(request): PresetRenderRequest,
+) -> Result<Response> {
+    render(state, method, request).await
+}
+
 fn create_image_response<T>(
     skin: T,
     State(state): &State<NMSRState>,
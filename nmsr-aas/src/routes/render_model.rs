@@ -3,49 +3,72 @@ use image::{ImageFormat, RgbaImage};
 use nmsr_rendering::{
     errors::NMSRRenderingError,
     high_level::{
-        model::{PlayerArmorSlots, PlayerModel},
-        parts::provider::PlayerPartProviderContext,
-        pipeline::{pools::SceneContextPoolManager, scene::Scene},
+        animation::PlayerAnimation,
+        model::{headwear::Headwear, item::PlayerHand, nametag::Nametag, PlayerArmorSlots, PlayerModel},
+        parts::{
+            provider::{minecraft::compute_cape_showcase_parts, PlayerPartProviderContext, ANIMATED_PARTS_PERIOD},
+            voxels::SkinLayerMask,
+        },
+        pipeline::{
+            pools::SceneContextPoolManager,
+            scene::{GlintInformation, Scene, SunInformation},
+        },
+        types::PlayerPartTextureType,
     },
 };
+use std::time::Duration;
+use strum::IntoEnumIterator;
 use tracing::instrument;
 
 use super::NMSRState;
 use crate::{
-    error::Result,
+    error::{RenderRequestError, Result},
     model::{
-        armor::VanillaMinecraftArmorMaterialData,
+        armor::{item::HeldItem, VanillaMinecraftArmorMaterialData, VanillaMinecraftHelmetData},
+        nametag::{get_nametag_size, render_nametag, NametagFont},
         request::{RenderRequest, RenderRequestFeatures},
         resolver::{ResolvedRenderEntryTextureType, ResolvedRenderRequest},
     },
-    utils::png::create_png_from_bytes,
+    utils::{
+        glow::apply_glow,
+        png::{create_apng_from_frames, create_png_from_bytes},
+    },
 };
 
-pub(crate) async fn internal_render_model<'a>(
+/// How many ticks of the game there are in a second, which is what the time of the parts is counted in.
+const TICKS_PER_SECOND: f32 = 20.0;
+
+pub(crate) async fn internal_render_model(
     request: &RenderRequest,
-    state: &NMSRState<'a>,
+    state: &NMSRState<'_>,
     resolved: &ResolvedRenderRequest,
 ) -> Result<Vec<u8>> {
     let scene_context = state.create_scene_context().await?;
 
-    let mode = request.mode;
-    #[allow(unused_mut)] // We use mut when we have ears feature enabled
     let mut camera = request.get_camera();
 
     let size = request.get_size();
     let lighting = request.get_lighting();
 
-    let parts = mode.get_body_parts();
+    let parts = request.get_body_parts();
 
-    let mut part_context = create_part_context(request, resolved);
+    let mut part_context = create_part_context(request, resolved, state.armor_manager.get_nametag_font());
+
+    if request.mode.is_cape() && !part_context.has_cape {
+        return Err(RenderRequestError::MissingCapeError.into());
+    }
 
     #[cfg(feature = "ears")]
-    if request.features.contains(RenderRequestFeatures::Ears) {
+    if request.features.contains(RenderRequestFeatures::Ears) && !request.mode.is_cape() {
         if let Some(features) = part_context.ears_features.as_ref() {
-            NMSRState::apply_ears_camera_settings(features, mode, &mut camera);
+            NMSRState::apply_ears_camera_settings(features, request.mode, &mut camera);
         }
     }
 
+    if part_context.nametag.is_some() {
+        NMSRState::apply_nametag_camera_settings(request.mode, &mut camera);
+    }
+
     let mut scene = Scene::new(
         &state.graphics_context,
         scene_context,
@@ -56,40 +79,161 @@ pub(crate) async fn internal_render_model<'a>(
         &parts,
     );
 
+    if request.mode.is_cape() {
+        scene.set_parts(compute_cape_showcase_parts());
+    }
+
     //println!("Optifine Enabled: {} {}", part_context.is_optifine_cape, request.features.contains(RenderRequestFeatures::OptifineCape));
 
-    load_textures(resolved, state, request, &mut part_context, &mut scene).await?;
+    load_textures(resolved, state, request, &part_context, &mut scene)?;
+
+    #[cfg(feature = "ears")]
+    let glow = request
+        .get_glow()
+        .filter(|_| scene.parts().iter().any(|part| part.get_texture().is_emissive()));
+    #[cfg(not(feature = "ears"))]
+    let glow = None;
+
+    if let Some((animation, frames)) = request.get_animation() {
+        let mut rendered_frames = Vec::with_capacity(frames as usize);
+        let ticks = get_animated_parts_ticks(animation);
+
+        for (frame, pose) in animation.sample_frames(frames as usize).into_iter().enumerate() {
+            let progress = frame as f32 / frames as f32;
+            let seconds = animation.duration * progress;
+
+            // Animated Ears features keep moving along with the animation
+            part_context.pose = Some(pose);
+            part_context.t = ticks.mul_add(progress, request.get_time());
+            scene.rebuild_parts(&part_context, parts.clone());
+
+            // The enchantment glint keeps scrolling along with the animation
+            *scene.glint_information_mut() = GlintInformation::at_time(seconds);
+
+            rendered_frames.push(render_scene(&mut scene, state, glow).await?);
+        }
+
+        let frame_delay = Duration::try_from_secs_f32(animation.duration / frames as f32)
+            .map_err(|_| {
+                RenderRequestError::InvalidRenderSettingError(
+                    "animation duration",
+                    "a positive amount of seconds".to_owned(),
+                )
+            })?;
+
+        return create_apng_from_frames((size.width, size.height), &rendered_frames, frame_delay);
+    }
+
+    let render = render_scene(&mut scene, state, glow).await?;
+    let render_bytes = create_png_from_bytes((size.width, size.height), &render)?;
+
+    Ok(render_bytes)
+}
+
+/// How many ticks the animated parts of the player move for over the animation.
+///
+/// Looping animations are wrapped to a whole number of periods of the animated parts, so that they're back where they
+/// started when the animation loops, instead of jumping back.
+fn get_animated_parts_ticks(animation: &PlayerAnimation) -> f32 {
+    let ticks = animation.duration * TICKS_PER_SECOND;
+
+    if !animation.looping {
+        return ticks;
+    }
 
+    (ticks / ANIMATED_PARTS_PERIOD).round().max(1.0) * ANIMATED_PARTS_PERIOD
+}
+
+/// Render the scene, with the glow of its emissive parts added on top if it has one.
+async fn render_scene<'a>(
+    scene: &mut Scene<Object<SceneContextPoolManager<'a>>>,
+    state: &NMSRState<'a>,
+    glow: Option<f32>,
+) -> Result<Vec<u8>> {
     scene.render(&state.graphics_context)?;
 
     let render = scene
         .copy_output_texture(&state.graphics_context, true)
         .await?;
-    let render_bytes = create_png_from_bytes((size.width, size.height), &render)?;
 
-    Ok(render_bytes)
+    let Some(strength) = glow else {
+        return Ok(render);
+    };
+
+    // Without the sun, everything but the emissive parts is black, which keeps what's in front of them from glowing.
+    // The name tag and the glint give off their own light too, but they shouldn't glow.
+    let parts = scene.parts().to_vec();
+    let sun = *scene.sun_information_mut();
+
+    scene.set_parts(
+        parts
+            .iter()
+            .filter(|part| !part.get_texture().is_nametag() && !part.get_texture().is_glint())
+            .cloned()
+            .collect(),
+    );
+    *scene.sun_information_mut() = SunInformation::new(sun.direction, 0.0, 0.0);
+    scene.update(&state.graphics_context);
+    scene.render(&state.graphics_context)?;
+
+    let emissive = scene
+        .copy_output_texture(&state.graphics_context, false)
+        .await?;
+
+    scene.set_parts(parts);
+    *scene.sun_information_mut() = sun;
+    scene.update(&state.graphics_context);
+
+    let size = *scene.viewport_size_mut();
+    let (Some(mut render), Some(emissive)) = (
+        RgbaImage::from_raw(size.width, size.height, render),
+        RgbaImage::from_raw(size.width, size.height, emissive),
+    ) else {
+        unreachable!("Renders are always the size of the viewport");
+    };
+
+    apply_glow(&mut render, &emissive, strength);
+
+    Ok(render.into_raw())
 }
 
 #[cfg(feature = "ears")]
 fn load_ears_features(
     part_context: &mut PlayerPartProviderContext<VanillaMinecraftArmorMaterialData>,
-    resolved: &ResolvedRenderRequest,
+    skin_image: &RgbaImage,
 ) {
-    if let Some(skin_bytes) = resolved.textures.get(&ResolvedRenderEntryTextureType::Skin) {
-        if let Ok(skin_image) = load_image(skin_bytes) {
-            if let Ok(features) = ears_rs::parser::EarsParser::parse(&skin_image) {
-                part_context.ears_features = features;
-            }
-        }
+    if let Ok(features) = ears_rs::parser::EarsParser::parse(skin_image) {
+        part_context.ears_features = features;
+    }
+}
+
+fn load_voxel_layers(
+    part_context: &mut PlayerPartProviderContext<VanillaMinecraftArmorMaterialData>,
+    skin_image: RgbaImage,
+    request: &RenderRequest,
+) {
+    let PlayerPartTextureType::Skin { scale } = part_context.skin_texture else {
+        return;
+    };
+
+    if let Ok(skin_image) = NMSRState::process_skin(skin_image, request.features) {
+        // A pixel of an HD skin's layout is opaque if any of the pixels it's made of is
+        part_context.voxel_layers = Some(SkinLayerMask::from_fn(|x, y| {
+            (0..scale * scale).any(|i| {
+                skin_image
+                    .get_pixel_checked(x * scale + i % scale, y * scale + i / scale)
+                    .is_some_and(|pixel| pixel[3] > 0)
+            })
+        }));
     }
 }
 
 #[instrument(skip_all)]
-async fn load_textures<'a>(
+fn load_textures<'a>(
     resolved: &ResolvedRenderRequest,
     state: &NMSRState<'a>,
     request: &RenderRequest,
-    part_provider: &mut PlayerPartProviderContext<VanillaMinecraftArmorMaterialData>,
+    part_provider: &PlayerPartProviderContext<VanillaMinecraftArmorMaterialData>,
     scene: &mut Scene<Object<SceneContextPoolManager<'a>>>,
 ) -> Result<()> {
     for (&texture_type, texture_bytes) in &resolved.textures {
@@ -97,9 +241,13 @@ async fn load_textures<'a>(
 
         if texture_type == ResolvedRenderEntryTextureType::Skin {
             image_buffer = NMSRState::process_skin(image_buffer, request.features)?;
+        } else if texture_type == ResolvedRenderEntryTextureType::PlayerHead {
+            image_buffer = NMSRState::process_player_head(image_buffer)?;
         }
 
-        if texture_type == ResolvedRenderEntryTextureType::OptifineCape && request.features.contains(RenderRequestFeatures::OptifineCape) {
+        if texture_type == ResolvedRenderEntryTextureType::Skin {
+            scene.set_texture(&state.graphics_context, part_provider.skin_texture, &image_buffer);
+        } else if texture_type == ResolvedRenderEntryTextureType::OptifineCape && request.features.contains(RenderRequestFeatures::OptifineCape) {
             scene.set_texture(&state.graphics_context, ResolvedRenderEntryTextureType::Cape.into(), &image_buffer);
         } else if !(texture_type == ResolvedRenderEntryTextureType::Cape &&
             part_provider.is_optifine_cape &&
@@ -109,11 +257,39 @@ async fn load_textures<'a>(
         }
     }
 
+    if part_provider.elytra.is_some() && !part_provider.has_cape {
+        scene.set_texture(
+            &state.graphics_context,
+            PlayerPartTextureType::VANILLA_ELYTRA,
+            &state.armor_manager.get_elytra_texture()?,
+        );
+    }
+
+    if let Some(mob) = part_provider.mob {
+        scene.set_texture(
+            &state.graphics_context,
+            mob.get_texture(),
+            &state.armor_manager.get_mob_texture(mob)?,
+        );
+    }
+
+    if let Some(headwear) = part_provider.headwear.filter(|headwear| *headwear != Headwear::PlayerHead) {
+        scene.set_texture(
+            &state.graphics_context,
+            headwear.get_texture(),
+            &state.armor_manager.get_headwear_texture(headwear)?,
+        );
+    }
+
+    for (hand, item) in PlayerHand::iter().zip(get_held_items(request)) {
+        if let Some(item) = item {
+            scene.set_texture(&state.graphics_context, hand.get_texture(item.model.texture_size), &item.texture);
+        }
+    }
+
     if let Some(armor_slots) = part_provider.armor_slots.as_ref() {
-        let (main_layer, second_armor_layer) = state
-            .armor_manager
-            .create_armor_texture(armor_slots)
-            .await?;
+        let (main_layer, second_armor_layer) =
+            state.armor_manager.create_armor_texture(armor_slots, request.get_uploaded_armor().as_deref())?;
 
         scene.set_texture(
             &state.graphics_context,
@@ -128,6 +304,23 @@ async fn load_textures<'a>(
                 &second_armor_layer,
             );
         }
+
+        let is_enchanted = armor_slots
+            .get_all_materials_in_slots()
+            .iter()
+            .any(|(armor, _)| armor.enchanted);
+
+        if is_enchanted {
+            scene.set_texture(
+                &state.graphics_context,
+                PlayerPartTextureType::Glint,
+                &state.armor_manager.get_glint_texture()?,
+            );
+        }
+    }
+
+    if let (Some(nametag), Some(text)) = (part_provider.nametag, get_nametag_text(request, resolved)) {
+        scene.set_texture(&state.graphics_context, nametag.get_texture(), &render_nametag(text, state.armor_manager.get_nametag_font()));
     }
 
     Ok(())
@@ -139,13 +332,60 @@ pub(crate) fn load_image(texture: &[u8]) -> Result<RgbaImage> {
     Ok(img.into_rgba8())
 }
 
+/// The items held in the main hand and the off hand, in the order of [`PlayerHand`].
+pub(crate) fn get_held_items(request: &RenderRequest) -> [Option<&HeldItem>; 2] {
+    let settings = request.extra_settings.as_ref();
+
+    [
+        settings.and_then(|settings| settings.main_hand.as_ref()),
+        settings.and_then(|settings| settings.off_hand.as_ref()),
+    ]
+}
+
+/// The text of the name tag shown above the head, which is the name of the profile of the entry unless it's given.
+pub(crate) fn get_nametag_text<'a>(request: &'a RenderRequest, resolved: &'a ResolvedRenderRequest) -> Option<&'a str> {
+    let text = request.extra_settings.as_ref()?.nametag.as_deref()?;
+
+    if text.is_empty() {
+        resolved.profile_name.as_deref()
+    } else {
+        Some(text)
+    }
+}
+
+fn get_nametag(request: &RenderRequest, resolved: &ResolvedRenderRequest, font: &NametagFont) -> Option<Nametag> {
+    let camera = request.get_camera();
+
+    get_nametag_text(request, resolved).map(|text| Nametag {
+        texture_size: get_nametag_size(text, font),
+        yaw: camera.get_yaw(),
+        pitch: camera.get_pitch(),
+    })
+}
+
+fn get_armor_slots(request: &RenderRequest) -> PlayerArmorSlots<VanillaMinecraftArmorMaterialData> {
+    let settings = request.extra_settings.as_ref();
+
+    PlayerArmorSlots {
+        helmet: settings
+            .and_then(|x| x.helmet.as_ref())
+            .and_then(VanillaMinecraftHelmetData::armor)
+            .cloned(),
+        chestplate: settings.and_then(|x| x.chestplate.clone()),
+        leggings: settings.and_then(|x| x.leggings.clone()),
+        boots: settings.and_then(|x| x.boots.clone()),
+    }
+}
+
 pub(crate) fn create_part_context(
     request: &RenderRequest,
     resolved: &ResolvedRenderRequest,
+    nametag_font: &NametagFont,
 ) -> PlayerPartProviderContext<VanillaMinecraftArmorMaterialData> {
     let arm_rotation = request.get_arm_rotation();
 
     let final_model = request.model.unwrap_or(resolved.model);
+    let mob = request.get_mob();
 
     let has_layers = request.features.contains(RenderRequestFeatures::BodyLayers);
     let has_hat_layer = request.features.contains(RenderRequestFeatures::HatLayer);
@@ -179,49 +419,80 @@ pub(crate) fn create_part_context(
                 crate::model::resolver::ResolvedRenderEntryEarsTextureType::Cape,
             ));
 
-        has_cape_feature && (has_cape || (has_ears_feature && has_ears_cape) || (has_optifine_cape_feature && has_optifine_cape))
+        mob.is_none() && has_cape_feature && (has_cape || (has_ears_feature && has_ears_cape) || (has_optifine_cape_feature && has_optifine_cape))
     };
 
     let shadow_y_pos = request.get_shadow_y_pos();
 
-    let player_armor_slots = PlayerArmorSlots::<VanillaMinecraftArmorMaterialData> {
-        helmet: request
-            .extra_settings
-            .as_ref()
-            .and_then(|x| x.helmet.clone()),
-        chestplate: request
-            .extra_settings
-            .as_ref()
-            .and_then(|x| x.chestplate.clone()),
-        leggings: request
-            .extra_settings
-            .as_ref()
-            .and_then(|x| x.leggings.clone()),
-        boots: request
-            .extra_settings
-            .as_ref()
-            .and_then(|x| x.boots.clone()),
-    };
+    let skin_image = resolved
+        .textures
+        .get(&ResolvedRenderEntryTextureType::Skin)
+        .and_then(|skin_bytes| load_image(skin_bytes).ok());
+
+    let skin_texture = skin_image
+        .as_ref()
+        .and_then(|skin| PlayerPartTextureType::skin_of_size(skin.width(), skin.height()))
+        .unwrap_or_default();
+
+    let helmet = request
+        .extra_settings
+        .as_ref()
+        .and_then(|x| x.helmet.as_ref());
+
+    let [main_hand, off_hand] = get_held_items(request).map(|item| item.map(|item| item.model.clone()));
 
-    #[cfg_attr(not(feature = "ears"), allow(unused_mut))]
     let mut context = PlayerPartProviderContext::<VanillaMinecraftArmorMaterialData> {
         model: PlayerModel::from(final_model),
+        mob,
+        skin_texture,
+        geometry: request.get_geometry(),
         has_layers,
         has_hat_layer,
+        voxel_layers: None,
         has_cape,
         is_optifine_cape: has_optifine_cape,
+        cape_state: request.get_cape_state(),
+        elytra: request.get_elytra(),
         arm_rotation,
+        pose: request
+            .get_pose()
+            .or_else(|| mob.map(|mob| mob.get_default_pose())),
+        t: request.get_time(),
+        exploded_view_offset: request.get_exploded_view_offset(),
         shadow_y_pos,
         shadow_is_square: request.mode.is_head() || request.mode.is_head_iso(),
-        armor_slots: Some(player_armor_slots),
+        armor_slots: Some(get_armor_slots(request)),
+        headwear: helmet.and_then(VanillaMinecraftHelmetData::headwear),
+        main_hand,
+        off_hand,
+        nametag: get_nametag(request, resolved, nametag_font),
         #[cfg(feature = "ears")]
         ears_features: None,
     };
-    
-    
+
+    // Mobs don't wear the skin, so there's nothing to read from it
+    let Some(skin_image) = skin_image.filter(|_| mob.is_none()) else {
+        return context;
+    };
+
+    // Ears features can only be stored in skins with the vanilla layout
     #[cfg(feature = "ears")]
-    if request.features.contains(RenderRequestFeatures::Ears) {
-        load_ears_features(&mut context, resolved);
+    if request.features.contains(RenderRequestFeatures::Ears)
+        && skin_texture == PlayerPartTextureType::VANILLA_SKIN
+    {
+        load_ears_features(&mut context, &skin_image);
+
+        // The emissive texture is only resolved for skins with an emissive palette
+        if let Some(features) = context.ears_features.as_mut() {
+            features.emissive &= request.features.contains(RenderRequestFeatures::EarsEmissive)
+                && resolved.textures.contains_key(&ResolvedRenderEntryTextureType::Ears(
+                    crate::model::resolver::ResolvedRenderEntryEarsTextureType::Emissive,
+                ));
+        }
+    }
+
+    if (has_layers || has_hat_layer) && !request.features.contains(RenderRequestFeatures::FlatLayers) {
+        load_voxel_layers(&mut context, skin_image, request);
     }
     
     context
@@ -3,6 +3,7 @@ use std::{
     fs::Metadata,
     marker::PhantomData,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 use async_trait::async_trait;
@@ -110,7 +111,7 @@ where
     pub async fn new(base_path: PathBuf, config: Config, handler: Handler) -> Result<Self> {
         fs::create_dir_all(&base_path)
             .await
-            .explain(format!("Unable to create cache directory {:?}", &base_path))?;
+            .explain(format!("Unable to create cache directory {}", base_path.display()))?;
 
         Ok(Self {
             base_path,
@@ -159,6 +160,43 @@ where
         }
     }
 
+    /// How long ago the given entry was cached, if it's in the cache.
+    ///
+    /// Expired entries aren't cleaned up here, so this is only meaningful right after reading the entry.
+    pub async fn get_cache_entry_age(&self, entry: &Key) -> Result<Option<Duration>> {
+        let Some(path) = self.get_cache_entry_path(entry).await? else {
+            return Ok(None);
+        };
+
+        let marker_path = self.get_marker_file_path(entry, &path).await?;
+
+        if !marker_path.exists() {
+            return Ok(None);
+        }
+
+        let modified = marker_path
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .explain(format!(
+                "Unable to read marker for entry {:?} ({})",
+                entry,
+                marker_path.display()
+            ))?;
+
+        Ok(Some(modified.elapsed().unwrap_or_default()))
+    }
+
+    /// The marker file of the given entry, which is the entry itself if the handler has no marker path.
+    async fn get_marker_file_path(&self, entry: &Key, path: &Path) -> Result<PathBuf> {
+        let marker_path = self.handler.get_marker_path(entry, &self.config).await?;
+
+        Ok(if marker_path.is_empty() {
+            path.to_owned()
+        } else {
+            path.join(marker_path)
+        })
+    }
+
     #[instrument(name = "check_entry", skip(self, path))]
     async fn get_marker_and_clean_expired_if_needed(
         &self,
@@ -170,12 +208,7 @@ where
             return Ok(None);
         }
 
-        let marker_path = self.handler.get_marker_path(entry, &self.config).await?;
-        let marker_path = if marker_path.is_empty() {
-            path.to_owned()
-        } else {
-            path.join(marker_path)
-        };
+        let marker_path = self.get_marker_file_path(entry, path).await?;
 
         if !marker_path.exists() {
             trace!("Cache entry path {} doesn't exist.", marker_path.display());
@@ -238,12 +271,7 @@ where
                 return Ok(Some(path.clone()));
             }
 
-            let marker_path = self.handler.get_marker_path(entry, &self.config).await?;
-            let marker_path = if marker_path.is_empty() {
-                path.to_owned()
-            } else {
-                path.join(marker_path)
-            };
+            let marker_path = self.get_marker_file_path(entry, path).await?;
 
             self.handler
                 .write_cache(entry, value, &self.config, path)
@@ -261,7 +289,7 @@ where
     pub async fn perform_cache_cleanup(&self) -> Result<()> {
         let entries = fs::read_dir(&self.base_path).await.explain(format!(
             "Unable to read cache directory {}",
-            &self.base_path.display()
+            self.base_path.display()
         ))?;
 
         let mut stream = ReadDirStream::new(entries);
@@ -269,7 +297,7 @@ where
         while let Some(file) = stream.next().await {
             let file = file.explain(format!(
                 "Unable to read cache entry while cleaning {}",
-                &self.base_path.display()
+                self.base_path.display()
             ))?;
 
             let path = file.path();
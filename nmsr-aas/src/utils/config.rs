@@ -7,16 +7,25 @@ use std::{
 
 use chrono::{DateTime, Local};
 use derive_more::Debug;
+use nmsr_rendering::high_level::{
+    model::ElytraState, pose::PlayerPosePreset, types::PlayerBodyPartType,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr, TryFromInto};
 use tracing::trace;
 use twelf::config;
 
 use crate::{
-    error::ExplainableExt,
-    model::request::{
-        cache::CacheBias, entry::RenderRequestEntry, RenderRequestFeatures, RenderRequestMode,
+    error::{self, ExplainableExt, RenderRequestError},
+    model::{
+        armor::{VanillaMinecraftArmorMaterialData, VanillaMinecraftHelmetData},
+        request::{
+            cache::CacheBias,
+            entry::{RenderRequestEntry, RenderRequestModel},
+            RenderRequestFeatures, RenderRequestMode,
+        },
     },
+    routes::query::RenderRequestQueryParams,
 };
 
 #[config]
@@ -28,6 +37,10 @@ pub struct NmsrConfiguration {
     pub mojank: MojankConfiguration,
     pub rendering: Option<RenderingConfiguration>,
     pub features: Option<FeaturesConfiguration>,
+    pub assets: AssetsConfiguration,
+    pub presets: HashMap<String, RenderPresetConfiguration>,
+    /// Custom animations, mapping their name to the JSON file they are defined in.
+    pub animations: HashMap<String, PathBuf>,
 }
 
 #[serde_as]
@@ -62,9 +75,9 @@ pub struct ModelCacheConfiguration {
 impl Default for ModelCacheConfiguration {
     fn default() -> Self {
         Self {
-            cleanup_interval: Duration::from_secs(60 * 60),
-            resolve_cache_duration: Duration::from_secs(60 * 60 * 15),
-            texture_cache_duration: Duration::from_secs(60 * 60 * 24 * 2),
+            cleanup_interval: Duration::from_hours(1),
+            resolve_cache_duration: Duration::from_hours(15),
+            texture_cache_duration: Duration::from_hours(48),
             cache_biases: HashMap::new(),
         }
     }
@@ -79,7 +92,7 @@ pub struct MojankConfiguration {
     /// The textures server to use for downloading player textures.
     pub textures_server: String,
 
-    /// The GeyserMC API server to use for resolving Bedrock Edition player textures.
+    /// The `GeyserMC` API server to use for resolving Bedrock Edition player textures.
     pub geysermc_api_server: String,
 
     /// The rate limit to use for requests to the session server in a 1 second window.
@@ -153,6 +166,15 @@ pub struct RenderingConfiguration {
     pub use_smaa: bool,
 }
 
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AssetsConfiguration {
+    /// The asset packs to load armor, trims, the elytra and mob textures from.
+    /// Each one can be a vanilla client jar, or a resource pack (either a directory or a ZIP file).
+    /// Packs later in the list replace the textures of the ones before them.
+    pub packs: Vec<PathBuf>,
+}
+
 #[serde_as]
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
 pub struct FeaturesConfiguration {
@@ -163,6 +185,123 @@ pub struct FeaturesConfiguration {
     pub disabled_modes: Vec<RenderRequestMode>,
 }
 
+#[serde_as]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RenderPresetConfiguration {
+    /// The render mode this preset is based on.
+    #[serde_as(as = "DisplayFromStr")]
+    pub mode: RenderRequestMode,
+
+    /// The camera settings of this preset.
+    pub yaw: Option<f32>,
+    pub pitch: Option<f32>,
+    pub roll: Option<f32>,
+    pub distance: Option<f32>,
+
+    /// The camera position of this preset (requires using Custom mode as the base mode).
+    pub x_pos: Option<f32>,
+    pub y_pos: Option<f32>,
+    pub z_pos: Option<f32>,
+
+    /// The size of the rendered image.
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+
+    /// The rotation of the arms.
+    pub arms: Option<f32>,
+
+    /// The model to render the entry with, instead of the entry's own model. Can also be a mob, like `armor_stand`.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub model: Option<RenderRequestModel>,
+
+    /// The features to exclude from the render.
+    #[serde_as(as = "Vec<DisplayFromStr>")]
+    #[serde(default)]
+    pub exclude: Vec<RenderRequestFeatures>,
+
+    /// The armor worn by the entry, using the same syntax as the query parameters.
+    #[serde_as(as = "Option<TryFromInto<String>>")]
+    #[serde(default)]
+    pub helmet: Option<VanillaMinecraftHelmetData>,
+    #[serde_as(as = "Option<TryFromInto<String>>")]
+    #[serde(default)]
+    pub chestplate: Option<VanillaMinecraftArmorMaterialData>,
+    #[serde_as(as = "Option<TryFromInto<String>>")]
+    #[serde(default)]
+    pub leggings: Option<VanillaMinecraftArmorMaterialData>,
+    #[serde_as(as = "Option<TryFromInto<String>>")]
+    #[serde(default)]
+    pub boots: Option<VanillaMinecraftArmorMaterialData>,
+
+    /// The elytra worn by the entry (`folded` or `open`).
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub elytra: Option<ElytraState>,
+
+    /// The items held by the entry in its right and left hand (e.g. `diamond_sword`).
+    pub mainhand: Option<String>,
+    pub offhand: Option<String>,
+
+    /// The name tag shown above the head of the entry, which is the name of its profile if empty.
+    pub nametag: Option<String>,
+
+    /// The strength of the glow around the emissive parts of Ears skins.
+    pub glow: Option<f32>,
+
+    /// How far the cape swings out from the back, and the speed of the wind blowing a wavy cape back.
+    pub cape_angle: Option<f32>,
+    pub wind: Option<f32>,
+
+    /// The body parts to render, instead of the ones used by the mode.
+    #[serde_as(as = "Option<Vec<DisplayFromStr>>")]
+    #[serde(default)]
+    pub parts: Option<Vec<PlayerBodyPartType>>,
+
+    /// The offset to move each body part away from the body by, for exploded views.
+    pub explode: Option<f32>,
+
+    /// The pose of the entry, and how much its upper body leans forward.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    pub pose: Option<PlayerPosePreset>,
+    pub lean: Option<f32>,
+
+    /// The rotations of the head and limbs as `[pitch, yaw, roll]`, which override the ones of the pose.
+    pub head: Option<Vec<f32>>,
+    pub left_arm: Option<Vec<f32>>,
+    pub right_arm: Option<Vec<f32>>,
+    pub left_leg: Option<Vec<f32>>,
+    pub right_leg: Option<Vec<f32>>,
+
+    /// The animation to render, and the amount of frames to render it with.
+    pub animation: Option<String>,
+    pub frames: Option<u32>,
+
+    /// The time of the render in ticks, which moves the animated parts of Ears skins and is where animations start from.
+    #[serde(alias = "time")]
+    pub t: Option<f32>,
+
+    /// The query parameters that are allowed to override this preset's settings (e.g. `["width", "yaw"]`).
+    #[serde(default)]
+    pub overridable: Vec<String>,
+}
+
+impl RenderPresetConfiguration {
+    /// Checks that the settings of this preset are valid for its mode, and that it only lists known settings as overridable.
+    pub fn validate(&self) -> error::Result<()> {
+        RenderRequestQueryParams::from(self).validate(self.mode)?;
+
+        if let Some(setting) = self.overridable.iter().find(|setting| {
+            !RenderRequestQueryParams::OVERRIDABLE_SETTINGS.contains(&setting.as_str())
+        }) {
+            return Err(RenderRequestError::UnknownOverridableSettingError(setting.clone()).into());
+        }
+
+        Ok(())
+    }
+}
+
 impl ModelCacheConfiguration {
     #[must_use]
     pub fn get_cache_duration(&self, entry: &RenderRequestEntry) -> &Duration {
@@ -209,8 +348,7 @@ impl ModelCacheConfiguration {
         }
 
         let expiry = marker_metadata.modified().explain(format!(
-            "Unable to get marker modified date for entry {:?}",
-            &entry
+            "Unable to get marker modified date for entry {entry:?}"
         ))? + *duration;
 
         trace!("Entry expires on {}", Into::<DateTime<Local>>::into(expiry));
@@ -223,13 +361,7 @@ impl ModelCacheConfiguration {
     #[must_use]
     pub fn validate_png_data(&self, data: &[u8]) -> bool {
         // Cheeky hack to validate that the texture is valid
-        let data_header = data.get(0..Self::VALID_PNG_HEADER.len());
-
-        if let Some(data_header) = data_header {
-            data_header == Self::VALID_PNG_HEADER
-        } else {
-            false
-        }
+        data.starts_with(&Self::VALID_PNG_HEADER)
     }
 }
 
@@ -1,7 +1,18 @@
 use std::path::PathBuf;
 
-use axum::response::IntoResponse;
-use hyper::StatusCode;
+use axum::{
+    body::Body,
+    extract::Request,
+    http::HeaderValue,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use hyper::{
+    header::{ACCEPT, ALLOW, CONTENT_LENGTH, CONTENT_TYPE},
+    StatusCode,
+};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
 use thiserror::Error;
 use tower_http::BoxError;
 use uuid::Uuid;
@@ -51,18 +62,49 @@ pub enum RenderRequestError {
     MultipartError(#[from] axum_extra::extract::multipart::MultipartError),
     #[error("Multipart Rejection: {0}")]
     MultipartRejection(#[from] axum_extra::extract::multipart::MultipartRejection),
-    #[error("Unable to decode multipart: {0} ({1})")]
-    MultipartDecodeError(serde_json::Error, serde_json::Value),
+    #[error("Unable to decode multipart: {0}")]
+    MultipartDecodeError(String),
+    #[error("Json Rejection: {0}")]
+    JsonRejection(#[from] axum::extract::rejection::JsonRejection),
+    #[error("Unable to decode json options: {0}")]
+    JsonDecodeError(String),
+    #[error("Unable to decode base64 skin: {0}")]
+    InvalidBase64Skin(base64::DecodeError),
     #[error("Invalid render mode: {0}")]
     InvalidRenderMode(String),
     #[error("Unable to upgrade legacy skin to modern format")]
     LegacySkinUpgradeError,
+    #[error("The skin you've specified is {0}x{1}, which isn't a valid skin size. Skins should be 64x64 or 64x32, or a power of two times that for HD skins.")]
+    InvalidSkinSizeError(u32, u32),
+    #[error("Unable to read the skin geometry: {0}")]
+    InvalidGeometryError(String),
+    #[cfg(feature = "ears")]
+    #[error("Invalid Ears {0} texture: {1}")]
+    InvalidEarsTextureError(&'static str, String),
     #[error("The render setting you've specified ({0}) is invalid. Valid values should be {1}.")]
     InvalidRenderSettingError(&'static str, String),
+    #[error("The render setting you've specified ({0}) is invalid. Valid values should be between {1} and {2}.")]
+    InvalidRenderSettingBoundsError(&'static str, Value, Value),
     #[error("You've specified {0} which is invalid for this mode. {1}")]
     InvalidModeSettingSpecifiedError(&'static str, &'static str),
+    #[error("The {0} setting needs NMSR to be built with the {1} feature.")]
+    SettingNeedsFeatureError(&'static str, &'static str),
     #[error("Missing render request texture. Did you forget to specify a texture?")]
     MissingRenderRequestEntry,
+    #[error("There's no cape to render. Make sure the player has a cape, or that you've specified a cape texture hash.")]
+    MissingCapeError,
+    #[error("Unknown render preset: {0}")]
+    UnknownPresetError(String),
+    #[error("This preset doesn't allow overriding the {0} setting.")]
+    PresetOverrideNotAllowedError(&'static str),
+    #[error("Unknown overridable setting: {0}")]
+    UnknownOverridableSettingError(String),
+    #[error("Invalid render preset {0}: {1}")]
+    InvalidPresetError(String, Box<NMSRaaSError>),
+    #[error("Unknown animation: {0}")]
+    UnknownAnimationError(String),
+    #[error("Unable to load animation from {0:?}: {1}")]
+    InvalidAnimationDefinitionError(PathBuf, serde_json::Error),
     #[error("Invalid HTTP Method. Did you mean to use \"{1}\" instead of \"{0}\"? This endpoint only supports \"{0}\".")]
     WrongHttpMethodError(&'static str, &'static str),
 }
@@ -70,6 +112,11 @@ pub enum RenderRequestError {
 impl RenderRequestError {
     #[must_use]
     pub const fn is_bad_request(&self) -> bool {
+        #[cfg(feature = "ears")]
+        if matches!(self, Self::InvalidEarsTextureError(_, _)) {
+            return true;
+        }
+
         matches!(
             self,
             Self::InvalidUUID(_)
@@ -77,9 +124,19 @@ impl RenderRequestError {
                 | Self::InvalidPlayerRequest(_)
                 | Self::InvalidRenderMode(_)
                 | Self::InvalidRenderSettingError(_, _)
+                | Self::InvalidRenderSettingBoundsError(_, _, _)
                 | Self::InvalidModeSettingSpecifiedError(_, _)
+                | Self::SettingNeedsFeatureError(_, _)
                 | Self::MissingRenderRequestEntry
                 | Self::WrongHttpMethodError(_, _)
+                | Self::MultipartDecodeError(_)
+                | Self::JsonRejection(_)
+                | Self::JsonDecodeError(_)
+                | Self::InvalidBase64Skin(_)
+                | Self::InvalidSkinSizeError(_, _)
+                | Self::InvalidGeometryError(_)
+                | Self::PresetOverrideNotAllowedError(_)
+                | Self::UnknownAnimationError(_)
         )
     }
 }
@@ -138,9 +195,11 @@ pub enum ArmorManagerError {
     #[error("Unable to parse armor: {0}")]
     ArmorParseError(#[from] strum::ParseError),
     #[error("Missing Armor texture: {0:?}")]
-    MissingArmorTextureError(PathBuf),
+    MissingArmorTextureError(String),
     #[error("Unable to load armor texture for {0:?}: {1}")]
-    ArmorTextureLoadError(PathBuf, image::error::ImageError),
+    ArmorTextureLoadError(String, image::error::ImageError),
+    #[error("Unable to load asset pack {0:?}: {1}")]
+    AssetPackLoadError(PathBuf, String),
     #[error("Unable to upgrade armor texture to 64x64")]
     ArmorTextureUpgradeError,
     #[error("Empty armor slot")]
@@ -149,6 +208,18 @@ pub enum ArmorManagerError {
     UnknownPartialArmorMaterialName(String),
     #[error("Invalid trim count: {0}")]
     InvalidTrimCountError(usize),
+    #[error("Unknown armor flag: {0}")]
+    UnknownArmorFlagError(String),
+    #[error("Invalid player head: {0}")]
+    InvalidPlayerHeadError(String),
+    #[error("Unknown item: {0}")]
+    UnknownItemError(String),
+    #[error("Invalid item model {0:?}: {1}")]
+    InvalidItemModelError(String, String),
+    #[error("Invalid uploaded armor texture {0:?}: {1}")]
+    InvalidArmorUploadError(String, String),
+    #[error("Invalid armor dye {0:?}: {1}")]
+    InvalidArmorDyeError(String, String),
 }
 
 pub(crate) type Result<T> = std::result::Result<T, NMSRaaSError>;
@@ -174,6 +245,300 @@ impl<T> ExplainableExt<T> for std::result::Result<T, std::io::Error> {
     }
 }
 
+const PROBLEM_JSON_MIME: &str = "application/problem+json";
+const PROBLEM_TYPE_PREFIX: &str = "https://nmsr.nickac.dev/errors/";
+
+/// An [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807) problem details object.
+#[derive(Debug, Serialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: &'static str,
+    pub status: u16,
+    pub detail: String,
+    /// Stable, machine-readable code for this error.
+    pub code: &'static str,
+    #[serde(flatten)]
+    pub details: Map<String, Value>,
+}
+
+fn details_of<const N: usize>(entries: [(&str, Value); N]) -> Map<String, Value> {
+    entries
+        .into_iter()
+        .map(|(key, value)| (key.to_owned(), value))
+        .collect()
+}
+
+impl NMSRaaSError {
+    /// The stable, machine-readable code for this error.
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::RenderRequestError(error) => error.code(),
+            Self::ModelCacheError(error) => error.code(),
+            Self::MojangRequestError(error) => error.code(),
+            Self::RenderError(_) => "render_error",
+            Self::ArmorManagerError(error) => error.code(),
+            Self::ClonedError(_) => "internal_error",
+            #[cfg(feature = "ears")]
+            Self::EarsError(_) => "ears_error",
+            Self::BlockbenchGeneratorError(_) => "blockbench_generator_error",
+        }
+    }
+
+    #[must_use]
+    pub const fn title(&self) -> &'static str {
+        match self {
+            Self::RenderRequestError(_) => "Invalid render request",
+            Self::ModelCacheError(_) => "Model cache error",
+            Self::MojangRequestError(_) => "Mojang request error",
+            Self::RenderError(_) => "Render error",
+            Self::ArmorManagerError(_) => "Armor manager error",
+            Self::ClonedError(_) => "Internal error",
+            #[cfg(feature = "ears")]
+            Self::EarsError(_) => "Ears error",
+            Self::BlockbenchGeneratorError(_) => "Blockbench generator error",
+        }
+    }
+
+    #[must_use]
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::RenderRequestError(error) => error.status_code(),
+            Self::MojangRequestError(error) => error.status_code(),
+            Self::ArmorManagerError(error) => error.status_code(),
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn details(&self) -> Map<String, Value> {
+        match self {
+            Self::RenderRequestError(error) => error.details(),
+            Self::MojangRequestError(error) => error.details(),
+            Self::ArmorManagerError(error) => error.details(),
+            _ => Map::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn to_problem_details(&self) -> ProblemDetails {
+        let code = self.code();
+
+        ProblemDetails {
+            problem_type: format!("{PROBLEM_TYPE_PREFIX}{code}"),
+            title: self.title(),
+            status: self.status_code().as_u16(),
+            detail: self.to_string(),
+            code,
+            details: self.details(),
+        }
+    }
+}
+
+impl RenderRequestError {
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidUUID(_) => "invalid_uuid",
+            Self::InvalidPlayerUuidRequest(_, _) => "invalid_player_uuid_version",
+            Self::InvalidPlayerRequest(_) => "invalid_player_request",
+            Self::ExplainedIoError(_, _) => "io_error",
+            Self::PathRejection(_) => "invalid_path",
+            Self::QueryRejection(_) => "invalid_query",
+            Self::MultipartError(_) | Self::MultipartRejection(_) => "invalid_multipart",
+            Self::MultipartDecodeError(_) => "invalid_multipart_fields",
+            Self::JsonRejection(_) => "invalid_json",
+            Self::JsonDecodeError(_) => "invalid_json_fields",
+            Self::InvalidBase64Skin(_) => "invalid_base64_skin",
+            Self::InvalidRenderMode(_) => "invalid_render_mode",
+            Self::LegacySkinUpgradeError => "legacy_skin_upgrade_failed",
+            Self::InvalidSkinSizeError(_, _) => "invalid_skin_size",
+            Self::InvalidGeometryError(_) => "invalid_geometry",
+            #[cfg(feature = "ears")]
+            Self::InvalidEarsTextureError(_, _) => "invalid_ears_texture",
+            Self::InvalidRenderSettingError(_, _) => "invalid_render_setting",
+            Self::InvalidRenderSettingBoundsError(_, _, _) => "render_setting_out_of_bounds",
+            Self::InvalidModeSettingSpecifiedError(_, _) => "setting_invalid_for_mode",
+            Self::SettingNeedsFeatureError(_, _) => "setting_needs_feature",
+            Self::MissingRenderRequestEntry => "missing_render_entry",
+            Self::MissingCapeError => "missing_cape",
+            Self::WrongHttpMethodError(_, _) => "wrong_http_method",
+            Self::UnknownPresetError(_) => "unknown_preset",
+            Self::PresetOverrideNotAllowedError(_) => "preset_override_not_allowed",
+            Self::UnknownOverridableSettingError(_) => "unknown_overridable_setting",
+            Self::InvalidPresetError(_, _) => "invalid_preset",
+            Self::UnknownAnimationError(_) => "unknown_animation",
+            Self::InvalidAnimationDefinitionError(_, _) => "invalid_animation_definition",
+        }
+    }
+
+    #[must_use]
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::WrongHttpMethodError(_, _) => StatusCode::METHOD_NOT_ALLOWED,
+            Self::UnknownPresetError(_) | Self::MissingCapeError => StatusCode::NOT_FOUND,
+            Self::PathRejection(rejection) => rejection.status(),
+            Self::QueryRejection(rejection) => rejection.status(),
+            Self::MultipartError(error) => error.status(),
+            Self::MultipartRejection(rejection) => rejection.status(),
+            Self::JsonRejection(rejection) => rejection.status(),
+            _ if self.is_bad_request() => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn details(&self) -> Map<String, Value> {
+        match self {
+            Self::InvalidPlayerUuidRequest(uuid, version) => {
+                details_of([("uuid", json!(uuid)), ("version", json!(version))])
+            }
+            Self::InvalidRenderMode(mode) => details_of([("mode", json!(mode))]),
+            Self::InvalidSkinSizeError(width, height) => {
+                details_of([("width", json!(width)), ("height", json!(height))])
+            }
+            Self::InvalidRenderSettingError(setting, expected) => {
+                details_of([("parameter", json!(setting)), ("expected", json!(expected))])
+            }
+            Self::InvalidRenderSettingBoundsError(setting, min, max) => details_of([
+                ("parameter", json!(setting)),
+                ("min", min.clone()),
+                ("max", max.clone()),
+            ]),
+            Self::InvalidModeSettingSpecifiedError(setting, _)
+            | Self::PresetOverrideNotAllowedError(setting) => {
+                details_of([("setting", json!(setting))])
+            }
+            Self::UnknownPresetError(preset) | Self::InvalidPresetError(preset, _) => {
+                details_of([("preset", json!(preset))])
+            }
+            Self::UnknownOverridableSettingError(setting) => {
+                details_of([("setting", json!(setting))])
+            }
+            Self::SettingNeedsFeatureError(setting, feature) => {
+                details_of([("setting", json!(setting)), ("feature", json!(feature))])
+            }
+            #[cfg(feature = "ears")]
+            Self::InvalidEarsTextureError(texture, _) => details_of([("texture", json!(texture))]),
+            Self::UnknownAnimationError(animation) => {
+                details_of([("animation", json!(animation))])
+            }
+            Self::WrongHttpMethodError(method, allowed) => {
+                details_of([("method", json!(method)), ("allowed", json!([allowed]))])
+            }
+            _ => Map::new(),
+        }
+    }
+}
+
+impl ModelCacheError {
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::MarkerMetadataError(_) => "cache_marker_unreadable",
+            Self::InvalidCacheEntryMarkerRequest(_) => "invalid_cache_marker_request",
+            Self::InvalidCacheBiasConfiguration(_) => "invalid_cache_bias_configuration",
+        }
+    }
+}
+
+impl MojangRequestError {
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::Base64Error(_) => "profile_base64_invalid",
+            Self::Utf8Error(_) => "profile_utf8_invalid",
+            Self::JsonError(_) => "profile_json_invalid",
+            Self::MissingTexturesPropertyError => "profile_missing_textures",
+            Self::InvalidTexturesPropertyError(_) => "profile_invalid_textures",
+            Self::UrlParseError(_) => "invalid_url",
+            Self::HttpRequestError(_) => "http_error",
+            Self::BoxedRequestError(_) | Self::RequestError(_) => "upstream_request_failed",
+            Self::MissingSkinPropertyError(_) => "profile_missing_skin",
+            Self::InvalidTextureUrlError(_) => "invalid_texture_url",
+            Self::MojangFetchRequestError(_) => "upstream_fetch_failed",
+            Self::UnableToResolveRenderRequestEntity(_, _) => "unresolvable_render_entry",
+            Self::UnableToParseUuidIntoXuid(_) => "invalid_geyser_uuid",
+            Self::InvalidTextureHashError(_) => "invalid_texture_hash",
+            Self::GameProfileNotFound(_) => "profile_not_found",
+        }
+    }
+
+    #[must_use]
+    pub const fn status_code(&self) -> StatusCode {
+        match self {
+            Self::GameProfileNotFound(_) => StatusCode::NOT_FOUND,
+            Self::InvalidTextureHashError(_) | Self::UnableToParseUuidIntoXuid(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            Self::BoxedRequestError(_) | Self::RequestError(_) | Self::MojangFetchRequestError(_) => {
+                StatusCode::BAD_GATEWAY
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn details(&self) -> Map<String, Value> {
+        match self {
+            Self::MissingSkinPropertyError(uuid)
+            | Self::UnableToParseUuidIntoXuid(uuid)
+            | Self::GameProfileNotFound(uuid) => details_of([("uuid", json!(uuid))]),
+            Self::InvalidTextureHashError(hash) => details_of([("hash", json!(hash))]),
+            _ => Map::new(),
+        }
+    }
+}
+
+impl ArmorManagerError {
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::ArmorParseError(_) => "invalid_armor",
+            Self::MissingArmorTextureError(_) => "missing_armor_texture",
+            Self::ArmorTextureLoadError(_, _) => "armor_texture_load_failed",
+            Self::AssetPackLoadError(_, _) => "asset_pack_load_failed",
+            Self::ArmorTextureUpgradeError => "armor_texture_upgrade_failed",
+            Self::EmptyArmorSlotError => "empty_armor_slot",
+            Self::UnknownPartialArmorMaterialName(_) => "unknown_armor_material",
+            Self::InvalidTrimCountError(_) => "invalid_trim_count",
+            Self::UnknownArmorFlagError(_) => "unknown_armor_flag",
+            Self::InvalidPlayerHeadError(_) => "invalid_player_head",
+            Self::UnknownItemError(_) => "unknown_item",
+            Self::InvalidItemModelError(_, _) => "invalid_item_model",
+            Self::InvalidArmorUploadError(_, _) => "invalid_armor_upload",
+            Self::InvalidArmorDyeError(_, _) => "invalid_armor_dye",
+        }
+    }
+
+    #[must_use]
+    pub const fn status_code(&self) -> StatusCode {
+        match self {
+            Self::ArmorParseError(_)
+            | Self::UnknownPartialArmorMaterialName(_)
+            | Self::InvalidTrimCountError(_)
+            | Self::UnknownArmorFlagError(_)
+            | Self::InvalidPlayerHeadError(_)
+            | Self::UnknownItemError(_)
+            | Self::InvalidArmorUploadError(_, _)
+            | Self::InvalidArmorDyeError(_, _) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn details(&self) -> Map<String, Value> {
+        match self {
+            Self::UnknownPartialArmorMaterialName(name) => details_of([("material", json!(name))]),
+            Self::MissingArmorTextureError(path) => details_of([("texture", json!(path))]),
+            Self::InvalidTrimCountError(count) => details_of([("count", json!(count))]),
+            Self::UnknownArmorFlagError(flag) => details_of([("flag", json!(flag))]),
+            Self::UnknownItemError(item) => details_of([("item", json!(item))]),
+            Self::InvalidItemModelError(model, _) => details_of([("model", json!(model))]),
+            Self::InvalidArmorUploadError(texture, _) => details_of([("texture", json!(texture))]),
+            Self::InvalidArmorDyeError(dye, _) => details_of([("dye", json!(dye))]),
+            _ => Map::new(),
+        }
+    }
+}
+
 pub struct NmsrErrorExtension(pub NMSRaaSError);
 
 impl Clone for NmsrErrorExtension {
@@ -187,22 +552,169 @@ impl IntoResponse for NMSRaaSError {
     fn into_response(self) -> axum::response::Response {
         let mut res = axum::response::IntoResponse::into_response(self.to_string());
 
-        let is_bad_request = if let Self::RenderRequestError(error) = &self {
-            error.is_bad_request()
-        } else {
-            false
-        };
+        *res.status_mut() = self.status_code();
 
-        let error = if is_bad_request {
-            StatusCode::BAD_REQUEST
-        } else {
-            StatusCode::INTERNAL_SERVER_ERROR
-        };
-
-        *res.status_mut() = error;
+        if let Self::RenderRequestError(RenderRequestError::WrongHttpMethodError(_, allowed)) = &self {
+            res.headers_mut().insert(ALLOW, HeaderValue::from_static(allowed));
+        }
 
         res.extensions_mut().insert(NmsrErrorExtension(self));
 
         res
     }
 }
+
+fn accepts_problem_json(request: &Request) -> bool {
+    request
+        .headers()
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|media_type| media_type.split(';').next().unwrap_or_default().trim())
+        .any(|media_type| media_type == PROBLEM_JSON_MIME || media_type == "application/json")
+}
+
+/// Middleware that turns error responses into `application/problem+json` bodies for clients
+/// that ask for JSON in their `Accept` header. Everyone else keeps getting the plain-text error.
+pub async fn problem_details_middleware(request: Request, next: Next) -> Response {
+    let wants_problem_json = accepts_problem_json(&request);
+
+    let response = next.run(request).await;
+
+    if !wants_problem_json {
+        return response;
+    }
+
+    let Some(problem) = response
+        .extensions()
+        .get::<NmsrErrorExtension>()
+        .map(|NmsrErrorExtension(error)| error.to_problem_details())
+    else {
+        return response;
+    };
+
+    let Ok(body) = serde_json::to_vec(&problem) else {
+        return response;
+    };
+
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(CONTENT_LENGTH);
+    parts
+        .headers
+        .insert(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON_MIME));
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::Request, middleware, routing::get, Router};
+    use http_body_util::BodyExt;
+    use hyper::{
+        header::{HeaderName, ACCEPT, ALLOW, CONTENT_TYPE},
+        HeaderMap, StatusCode,
+    };
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    use super::{problem_details_middleware, NMSRaaSError, RenderRequestError, Result};
+    use crate::model::request::RenderRequestMode;
+
+    async fn out_of_bounds_handler() -> Result<()> {
+        RenderRequestMode::validate_unit("distance", Some(100.0f32), -15.0, 50.0)
+    }
+
+    async fn wrong_method_handler() -> Result<()> {
+        Err(RenderRequestError::WrongHttpMethodError("GET", "POST").into())
+    }
+
+    async fn send(url: &str, accept: Option<&str>) -> (StatusCode, HeaderMap, Vec<u8>) {
+        let app = Router::new()
+            .route("/bounds", get(out_of_bounds_handler))
+            .route("/method", get(wrong_method_handler))
+            .layer(middleware::from_fn(problem_details_middleware));
+
+        let mut request = Request::builder().uri(url);
+        if let Some(accept) = accept {
+            request = request.header(ACCEPT, accept);
+        }
+
+        let response = app
+            .oneshot(request.body(Body::empty()).expect("Failed to build request"))
+            .await
+            .expect("Failed to send request");
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .expect("Failed to read body")
+            .to_bytes()
+            .to_vec();
+
+        (status, headers, body)
+    }
+
+    fn header(headers: &HeaderMap, name: HeaderName) -> &str {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_problem_details_response() {
+        for accept in ["application/problem+json", "text/html, application/json;q=0.9"] {
+            let (status, headers, body) = send("/bounds", Some(accept)).await;
+
+            assert_eq!(StatusCode::BAD_REQUEST, status);
+            assert_eq!("application/problem+json", header(&headers, CONTENT_TYPE));
+
+            let body: Value = serde_json::from_slice(&body).expect("Body should be json");
+            assert_eq!(json!("render_setting_out_of_bounds"), body["code"]);
+            assert_eq!(
+                json!("https://nmsr.nickac.dev/errors/render_setting_out_of_bounds"),
+                body["type"]
+            );
+            assert_eq!(json!(400), body["status"]);
+            assert_eq!(json!("distance"), body["parameter"]);
+            assert_eq!(json!(-15.0), body["min"]);
+            assert_eq!(json!(50.0), body["max"]);
+        }
+
+        let (status, headers, body) = send("/method", Some("application/problem+json")).await;
+        let body: Value = serde_json::from_slice(&body).expect("Body should be json");
+        assert_eq!(StatusCode::METHOD_NOT_ALLOWED, status);
+        assert_eq!("application/problem+json", header(&headers, CONTENT_TYPE));
+        assert_eq!("POST", header(&headers, ALLOW));
+        assert_eq!(json!("wrong_http_method"), body["code"]);
+        assert_eq!(json!(405), body["status"]);
+    }
+
+    #[tokio::test]
+    async fn test_plain_text_response() {
+        for accept in [None, Some("image/png")] {
+            let (status, headers, body) = send("/bounds", accept).await;
+            let content_type = header(&headers, CONTENT_TYPE);
+
+            assert_eq!(StatusCode::BAD_REQUEST, status);
+            assert!(content_type.starts_with("text/plain"), "Unexpected content type {content_type}");
+            assert_eq!(
+                NMSRaaSError::from(RenderRequestError::InvalidRenderSettingBoundsError(
+                    "distance",
+                    json!(-15.0),
+                    json!(50.0)
+                ))
+                .to_string(),
+                String::from_utf8(body).expect("Body should be text")
+            );
+        }
+
+        let (status, headers, _) = send("/method", None).await;
+        assert_eq!(StatusCode::METHOD_NOT_ALLOWED, status);
+        assert_eq!("POST", header(&headers, ALLOW));
+    }
+}
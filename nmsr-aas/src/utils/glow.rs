@@ -0,0 +1,73 @@
+use image::{imageops, RgbaImage};
+
+/// How far the glow spreads around the emissive pixels, relative to the largest side of the render.
+const GLOW_SPREAD: f32 = 1.0 / 64.0;
+
+/// Add the glow of the emissive pixels of a render on top of it, like the bloom of shaders does.
+///
+/// `emissive` is the same render with premultiplied alpha where everything but the emissive pixels is black, so that
+/// what's in front of them doesn't glow. The glow is blurred out of it, and added to the render with the given
+/// strength (spreading onto its transparent background too).
+pub(crate) fn apply_glow(render: &mut RgbaImage, emissive: &RgbaImage, strength: f32) {
+    let spread = render.width().max(render.height()) as f32 * GLOW_SPREAD;
+
+    let mut light = emissive.clone();
+    for pixel in light.pixels_mut() {
+        pixel[3] = u8::MAX;
+    }
+
+    let glow = imageops::blur(&light, spread);
+
+    for (pixel, glow) in render.pixels_mut().zip(glow.pixels()) {
+        let glow = [0, 1, 2].map(|channel| (f32::from(glow[channel]) / 255.0 * strength).min(1.0));
+        let glow_alpha = glow.into_iter().fold(0.0, f32::max);
+
+        let alpha = f32::from(pixel[3]) / 255.0;
+        let new_alpha = glow_alpha.mul_add(1.0 - alpha, alpha);
+
+        if new_alpha <= 0.0 {
+            continue;
+        }
+
+        for (channel, glow) in glow.into_iter().enumerate() {
+            let color = (f32::from(pixel[channel]) / 255.0).mul_add(alpha, glow) / new_alpha;
+            pixel[channel] = (color.min(1.0) * 255.0).round() as u8;
+        }
+
+        pixel[3] = (new_alpha * 255.0).round() as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgba, RgbaImage};
+
+    use super::apply_glow;
+
+    #[test]
+    fn test_glow_spreads_around_emissive_pixels() {
+        let mut render = RgbaImage::new(64, 64);
+        let mut emissive = RgbaImage::new(64, 64);
+
+        for y in 28..36 {
+            for x in 28..36 {
+                render.put_pixel(x, y, Rgba([0x40, 0x40, 0x40, 0xFF]));
+            }
+
+            // Only the left half of the square is emissive, and it's red
+            for x in 28..32 {
+                emissive.put_pixel(x, y, Rgba([0xFF, 0x00, 0x00, 0xFF]));
+            }
+        }
+
+        apply_glow(&mut render, &emissive, 1.0);
+
+        let next_to_square = render.get_pixel(26, 32);
+        assert!(next_to_square[3] > 0, "The glow doesn't spread onto the background");
+        assert!(next_to_square[0] > 0 && next_to_square[1] == 0, "The glow isn't the color of the emissive pixels");
+
+        // Far from the emissive pixels, nothing changes
+        assert_eq!(Rgba([0, 0, 0, 0]), *render.get_pixel(0, 0));
+        assert!(render.get_pixel(31, 32)[0] > render.get_pixel(35, 32)[0]);
+    }
+}
@@ -90,7 +90,7 @@ impl NmsrHttpClient {
 
         body.collect()
             .await
-            .map(|b| b.to_bytes())
+            .map(http_body_util::Collected::to_bytes)
             .map_err(|e| MojangRequestError::BoxedRequestError(Box::new(e)))
     }
 
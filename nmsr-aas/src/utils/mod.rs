@@ -1,6 +1,8 @@
 pub mod caching;
 pub mod config;
 pub mod error;
+pub mod glow;
 pub mod http_client;
 pub mod png;
+pub mod skin;
 pub mod tracing;
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use mtpng::{
     encoder::{Encoder, Options},
     ColorType, Header,
@@ -34,3 +36,138 @@ pub(crate) fn create_png_from_bytes(size: (u32, u32), bytes: &[u8]) -> Result<Ve
         .finish()
         .explain_closure(|| "Unable to finish writing output PNG".to_string())
 }
+
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+/// Iterate over the chunks of a PNG, as `(chunk type, chunk data)` pairs.
+fn png_chunks(png: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+    let mut remaining = png.get(PNG_SIGNATURE.len()..).unwrap_or_default();
+
+    std::iter::from_fn(move || {
+        let length = u32::from_be_bytes(remaining.get(0..4)?.try_into().ok()?) as usize;
+        let chunk_type = remaining.get(4..8)?;
+        let data = remaining.get(8..8 + length)?;
+
+        // Skip the length, type, data and CRC of this chunk
+        remaining = remaining.get(12 + length..)?;
+
+        Some((chunk_type, data))
+    })
+}
+
+fn write_png_chunk(output: &mut Vec<u8>, chunk_type: [u8; 4], data: &[u8]) {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&chunk_type);
+    hasher.update(data);
+
+    output.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    output.extend_from_slice(&chunk_type);
+    output.extend_from_slice(data);
+    output.extend_from_slice(&hasher.finalize().to_be_bytes());
+}
+
+/// Create an animated PNG (APNG) out of frames of raw RGBA bytes, shown for `frame_delay` each.
+///
+/// Every frame is encoded as a regular PNG first, and its image data is then moved into the frame chunks of the APNG.
+pub(crate) fn create_apng_from_frames(
+    size: (u32, u32),
+    frames: &[Vec<u8>],
+    frame_delay: Duration,
+) -> Result<Vec<u8>> {
+    let _guard = trace_span!("write_animated_image_bytes", frames = frames.len()).entered();
+
+    let mut output = Vec::from(PNG_SIGNATURE);
+    let mut sequence_number = 0u32;
+
+    // Frame delays are a fraction of a second, so use milliseconds for them
+    let delay_millis = u16::try_from(frame_delay.as_millis()).unwrap_or(u16::MAX);
+
+    for (index, frame) in frames.iter().enumerate() {
+        let png = create_png_from_bytes(size, frame)?;
+
+        if index == 0 {
+            if let Some((_, header)) = png_chunks(&png).find(|&(chunk_type, _)| chunk_type == b"IHDR") {
+                write_png_chunk(&mut output, *b"IHDR", header);
+            }
+
+            let mut animation_control = Vec::with_capacity(8);
+            animation_control.extend_from_slice(&(frames.len() as u32).to_be_bytes());
+            // Play the animation forever
+            animation_control.extend_from_slice(&0u32.to_be_bytes());
+
+            write_png_chunk(&mut output, *b"acTL", &animation_control);
+        }
+
+        let mut frame_control = Vec::with_capacity(26);
+        frame_control.extend_from_slice(&sequence_number.to_be_bytes());
+        frame_control.extend_from_slice(&size.0.to_be_bytes());
+        frame_control.extend_from_slice(&size.1.to_be_bytes());
+        // Frame offset
+        frame_control.extend_from_slice(&0u32.to_be_bytes());
+        frame_control.extend_from_slice(&0u32.to_be_bytes());
+        // Frame delay
+        frame_control.extend_from_slice(&delay_millis.to_be_bytes());
+        frame_control.extend_from_slice(&1000u16.to_be_bytes());
+        // Clear the frame before rendering the next one (our frames are transparent) and replace its contents
+        frame_control.extend_from_slice(&[1, 0]);
+
+        write_png_chunk(&mut output, *b"fcTL", &frame_control);
+        sequence_number += 1;
+
+        for (_, data) in png_chunks(&png).filter(|&(chunk_type, _)| chunk_type == b"IDAT") {
+            if index == 0 {
+                // The first frame is also the default image, shown by decoders without APNG support
+                write_png_chunk(&mut output, *b"IDAT", data);
+            } else {
+                let mut frame_data = Vec::with_capacity(data.len() + 4);
+                frame_data.extend_from_slice(&sequence_number.to_be_bytes());
+                frame_data.extend_from_slice(data);
+
+                write_png_chunk(&mut output, *b"fdAT", &frame_data);
+                sequence_number += 1;
+            }
+        }
+    }
+
+    write_png_chunk(&mut output, *b"IEND", &[]);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, time::Duration};
+
+    use image::{codecs::png::PngDecoder, AnimationDecoder};
+
+    use super::create_apng_from_frames;
+
+    #[test]
+    fn test_create_apng_from_frames() {
+        let size = (4, 2);
+        let frames: Vec<Vec<u8>> = (0..3u8)
+            .map(|frame| vec![frame * 50; (size.0 * size.1 * 4) as usize])
+            .collect();
+
+        let apng = create_apng_from_frames(size, &frames, Duration::from_millis(40))
+            .expect("Failed to create APNG");
+
+        let png_decoder = PngDecoder::new(Cursor::new(apng)).expect("Output should be a valid PNG");
+        assert!(png_decoder.is_apng().expect("Failed to read APNG info"));
+
+        let decoded = png_decoder
+            .apng()
+            .expect("Output should be a valid APNG")
+            .into_frames()
+            .collect_frames()
+            .expect("Failed to decode frames");
+
+        assert_eq!(frames.len(), decoded.len());
+
+        for (frame, decoded) in frames.iter().zip(&decoded) {
+            assert_eq!(size, decoded.buffer().dimensions());
+            assert_eq!(frame, decoded.buffer().as_raw());
+            assert_eq!(Duration::from_millis(40), Duration::from(decoded.delay()));
+        }
+    }
+}
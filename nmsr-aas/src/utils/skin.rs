@@ -0,0 +1,83 @@
+use image::{imageops, RgbaImage};
+
+/// The regions of a skin (laid out like the vanilla 64×64 one) that are always rendered without transparency.
+const OPAQUE_REGIONS: [(u32, u32, u32, u32); 3] = [(0, 0, 32, 16), (0, 16, 64, 32), (16, 48, 48, 64)];
+
+/// The regions of a legacy skin that are mirrored to make the left arm and leg, as `(x, y, offset x, offset y, width, height)`.
+const LEGACY_SKIN_COPIES: [(u32, u32, i32, u32, u32, u32); 12] = [
+    (4, 16, 16, 32, 4, 4),
+    (8, 16, 16, 32, 4, 4),
+    (0, 20, 24, 32, 4, 12),
+    (4, 20, 16, 32, 4, 12),
+    (8, 20, 8, 32, 4, 12),
+    (12, 20, 16, 32, 4, 12),
+    (44, 16, -8, 32, 4, 4),
+    (48, 16, -8, 32, 4, 4),
+    (40, 20, 0, 32, 4, 12),
+    (44, 20, -8, 32, 4, 12),
+    (48, 20, -16, 32, 4, 12),
+    (52, 20, -8, 32, 4, 12),
+];
+
+/// Upgrade a legacy (2:1) skin scaled up `scale` times to the modern layout, the same way the game does.
+pub(crate) fn upgrade_legacy_skin(skin: RgbaImage, scale: u32) -> RgbaImage {
+    if skin.height() * 2 != skin.width() {
+        return skin;
+    }
+
+    let mut upgraded = RgbaImage::new(skin.width(), skin.width());
+    imageops::replace(&mut upgraded, &skin, 0, 0);
+
+    for (x, y, offset_x, offset_y, width, height) in LEGACY_SKIN_COPIES {
+        let region = imageops::crop_imm(&skin, x * scale, y * scale, width * scale, height * scale);
+        let mirrored = imageops::flip_horizontal(&*region);
+
+        imageops::replace(
+            &mut upgraded,
+            &mirrored,
+            (i64::from(x) + i64::from(offset_x)) * i64::from(scale),
+            i64::from((y + offset_y) * scale),
+        );
+    }
+
+    upgraded
+}
+
+/// Remove the transparency from the parts of a skin scaled up `scale` times that can't be transparent.
+pub(crate) fn strip_alpha(skin: &mut RgbaImage, scale: u32) {
+    for (min_x, min_y, max_x, max_y) in OPAQUE_REGIONS {
+        for y in min_y * scale..max_y * scale {
+            for x in min_x * scale..max_x * scale {
+                skin.get_pixel_mut(x, y)[3] = u8::MAX;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgba, RgbaImage};
+
+    use super::{strip_alpha, upgrade_legacy_skin};
+
+    #[test]
+    fn test_upgrade_hd_legacy_skin() {
+        let mut skin = RgbaImage::new(128, 64);
+        // The first column of the front of the right leg
+        for y in 40..64 {
+            skin.put_pixel(8, y, Rgba([255, 0, 0, 255]));
+            skin.put_pixel(9, y, Rgba([255, 0, 0, 255]));
+        }
+
+        let mut upgraded = upgrade_legacy_skin(skin, 2);
+        assert_eq!((128, 128), upgraded.dimensions());
+
+        // The front of the left leg is the mirrored front of the right leg, so the column ends up on the other side
+        assert_eq!(Rgba([255, 0, 0, 255]), *upgraded.get_pixel(47, 104));
+        assert_eq!(Rgba([0, 0, 0, 0]), *upgraded.get_pixel(40, 104));
+
+        strip_alpha(&mut upgraded, 2);
+        assert_eq!(255, upgraded.get_pixel(40, 104)[3]);
+        assert_eq!(0, upgraded.get_pixel(64, 0)[3], "The hat layer can be transparent");
+    }
+}
@@ -55,7 +55,8 @@ impl<B> Default for NmsrTracing<B> {
         }
     }
 }
-type NmsrTraceLayer<B, R> = TraceLayer<
+
+pub type NmsrTraceLayer<B, R> = TraceLayer<
     SharedClassifier<ServerErrorsAsFailures>,
     NmsrTracing<B>,
     NmsrTracing<B>,
@@ -127,7 +128,7 @@ impl<B> OnRequest<B> for NmsrTracing<B> {
         let path = request
             .extensions()
             .get::<MatchedPath>()
-            .map_or(request.uri().path(), |p| p.as_str());
+            .map_or_else(|| request.uri().path(), |p| p.as_str());
 
         let client_ip = Self::extract_header_as_str(request.headers(), X_FORWARDED_FOR_HEADER)
             .or_else(|| {
@@ -22,13 +22,13 @@ impl BlockbenchGroupEntry {
             }
             BlockbenchGroupEntry::Root { elements } => {
                 json!(elements
-                    .iter()
-                    .map(|(_, e)| e.to_value())
+                    .values()
+                    .map(|e| e.to_value())
                     .collect::<Vec<_>>())
             }
             BlockbenchGroupEntry::Group { name, elements } => json!({
                 "name": name,
-                "children": elements.iter().map(|(_, e)| e.to_value()).collect::<Vec<_>>(),
+                "children": elements.values().map(|e| e.to_value()).collect::<Vec<_>>(),
             }),
         }
     }
@@ -66,7 +66,7 @@ fn generate_outliner_groups<M: ArmorMaterial, I: ModelProjectImageIO>(
     let mut root_group = BlockbenchGroupEntry::new_root();
 
     for (index, part) in parts
-        .into_iter()
+        .iter()
         .enumerate()
         .sorted_by_key(|(_, p)| p.get_group().len())
     {
@@ -93,8 +93,8 @@ fn convert_to_raw_elements<M: ArmorMaterial, I: ModelProjectImageIO>(
     grouped_parts: HashMap<PlayerPartTextureType, Vec<Part>>,
 ) -> Result<Vec<RawProjectElement>> {
     let parts = grouped_parts
-        .into_iter()
-        .flat_map(|(_, parts)| parts)
+        .into_values()
+        .flatten()
         //.filter(|p| p.get_name().map(|n| n.contains("Tail")).unwrap_or_default())
         .enumerate()
         .map(|(index, part)| -> Result<_> {
@@ -103,13 +103,8 @@ fn convert_to_raw_elements<M: ArmorMaterial, I: ModelProjectImageIO>(
 
             let name = part.part_tracking_data().name().map(|s| s.as_str());
 
-            let element = match &part {
-                part => {
-                    let name = project.get_part_name(name, index);
-
-                    RawProjectElement::new_primitive(name, &part, part.get_texture(), project)?
-                }
-            };
+            let name = project.get_part_name(name, index);
+            let element = RawProjectElement::new_primitive(name, &part, part.get_texture(), project)?;
 
             #[cfg(feature = "markers")]
             {
@@ -61,8 +61,7 @@ impl RawProjectElement {
                 "origin": origin,
                 "rotation": rotation,
                 "faces": faces,
-            })
-            .into(),
+            }),
         )
     }
 
@@ -73,8 +72,7 @@ impl RawProjectElement {
                 "name": name,
                 "type": "null_object",
                 "position": origin,
-            })
-            .into(),
+            }),
         )
     }
 
@@ -89,7 +87,7 @@ impl RawProjectElement {
 
             (format!("{a}{a_new:x}"), format!("{b}{b_new:x}"))
         }
-        let converted = primitive_convert(&part);
+        let converted = primitive_convert(part);
         
         let affine = part.get_transformation();
         let (_, rotation, translation) = affine.to_scale_rotation_translation();
@@ -189,8 +187,7 @@ impl RawProjectElement {
                 "rotation": rotation,
                 "vertices": vertices_map,
                 "faces": faces,
-            })
-            .into(),
+            }),
         ))
     }
 }
@@ -10,7 +10,9 @@ use nmsr_rendering::high_level::{
     model::{ArmorMaterial, PlayerModel},
     parts::{
         part::Part,
-        provider::{PartsProvider, PlayerPartProviderContext, PlayerPartsProvider},
+        provider::{
+            pose_player_parts, PartsProvider, PlayerPartProviderContext, PlayerPartsProvider,
+        },
         uv::{FaceUv, FaceUvPoint},
     },
     types::{PlayerBodyPartType, PlayerPartTextureType},
@@ -64,14 +66,27 @@ pub fn new_model_generator_without_part_context<I: ModelProjectImageIO>(
 ) -> ModelGenerationProject<(), I> {
     let context = PlayerPartProviderContext::<()> {
         model,
+        skin_texture: Default::default(),
+        geometry: None,
+        mob: None,
         has_hat_layer: layers,
         has_layers: layers,
+        voxel_layers: None,
         has_cape: false,
         is_optifine_cape: false,
+        cape_state: Default::default(),
+        elytra: None,
         arm_rotation: 10.0,
+        pose: None,
+        t: 0.0,
+        exploded_view_offset: None,
         shadow_y_pos: None,
         shadow_is_square: false,
         armor_slots: None,
+        headwear: None,
+        main_hand: None,
+        off_hand: None,
+        nametag: None,
         #[cfg(feature = "ears")]
         ears_features: None,
     };
@@ -108,17 +123,31 @@ impl<M: ArmorMaterial, I: ModelProjectImageIO> ModelGenerationProject<M, I> {
 
     pub fn add_texture(
         &mut self,
-        texture_type: PlayerPartTextureType,
+        mut texture_type: PlayerPartTextureType,
         mut texture: RgbaImage,
         do_ears_processing: bool,
     ) -> Result<()> {
+        if texture_type.is_skin() {
+            texture_type = PlayerPartTextureType::skin_of_size(texture.width(), texture.height())
+                .ok_or_else(|| {
+                    BlockbenchGeneratorError::ExplainedError(format!(
+                        "Invalid skin size {}x{}",
+                        texture.width(),
+                        texture.height()
+                    ))
+                })?;
+
+            self.part_context.skin_texture = texture_type;
+        }
+
         if do_ears_processing {
             #[cfg(feature = "ears")]
             {
                 use ears_rs::{alfalfa::AlfalfaDataKey, parser::EarsParser};
                 use nmsr_rendering::high_level::parts::provider::ears::PlayerPartEarsTextureType;
                 
-                if texture_type == PlayerPartTextureType::Skin {
+                // Ears features can only be read from skins with the vanilla layout
+                if texture_type == PlayerPartTextureType::VANILLA_SKIN {
                     if let Ok(Some(alfalfa)) = ears_rs::alfalfa::read_alfalfa(&texture) {
                         if let Some(wings) = alfalfa.get_data(AlfalfaDataKey::Wings) {
                             self.load_texture(
@@ -149,7 +178,7 @@ impl<M: ArmorMaterial, I: ModelProjectImageIO> ModelGenerationProject<M, I> {
                 }
             }
 
-            if texture_type == PlayerPartTextureType::Skin {
+            if texture_type == PlayerPartTextureType::VANILLA_SKIN {
                 ears_rs::utils::strip_alpha(&mut texture);
             } else if texture_type == PlayerPartTextureType::Cape {
                 self.part_context.has_cape = true;
@@ -174,14 +203,20 @@ impl<M: ArmorMaterial, I: ModelProjectImageIO> ModelGenerationProject<M, I> {
     }
 
     pub(crate) fn generate_parts(&self) -> Vec<Part> {
-        PlayerBodyPartType::iter()
+        let mut parts = PlayerBodyPartType::iter()
             .filter(|p| !(p.is_layer() || p.is_hat_layer()) || self.part_context.has_layers)
             .flat_map(|p| {
                 self.providers
                     .iter()
                     .flat_map(move |provider| provider.get_parts(&self.part_context, p))
             })
-            .collect_vec()
+            // Blockbench has no way of showing the enchantment glint
+            .filter(|part| !part.get_texture().is_glint())
+            .collect_vec();
+
+        pose_player_parts(&self.part_context, &mut parts);
+
+        parts
     }
 
     pub(crate) fn get_texture(&self, texture_type: PlayerPartTextureType) -> Option<&RgbaImage> {
@@ -405,14 +405,27 @@ async fn process_group_logic(
         } else {
             PlayerModel::Steve
         },
+        skin_texture: Default::default(),
+        geometry: None,
+        mob: None,
         has_hat_layer: parts.iter().any(|p| p.is_hat_layer()),
         has_layers: parts.iter().any(|p| p.is_layer()),
+        voxel_layers: None,
         has_cape: false,
         is_optifine_cape: false,
+        cape_state: Default::default(),
+        elytra: None,
         arm_rotation,
+        pose: None,
+        t: 0.0,
+        exploded_view_offset: None,
         shadow_y_pos,
         shadow_is_square: false,
         armor_slots: None,
+        headwear: None,
+        main_hand: None,
+        off_hand: None,
+        nametag: None,
         #[cfg(feature = "ears")]
         ears_features: None,
     };
@@ -460,7 +473,7 @@ async fn process_group_logic(
 
     scene.set_texture(
         &graphics_context,
-        PlayerPartTextureType::Skin,
+        PlayerPartTextureType::VANILLA_SKIN,
         &RgbaImage::new(64, 64),
     );
 
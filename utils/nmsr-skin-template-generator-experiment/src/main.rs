@@ -122,14 +122,27 @@ fn main() -> anyhow::Result<()> {
     
     let context: PlayerPartProviderContext<()> = PlayerPartProviderContext {
         model: nmsr_player_parts::model::PlayerModel::Alex,
+        skin_texture: Default::default(),
+        geometry: None,
+        mob: None,
         has_hat_layer: true,
         has_layers: true,
+        voxel_layers: None,
         has_cape: false,
         is_optifine_cape: false,
+        cape_state: Default::default(),
+        elytra: None,
         arm_rotation: 0f32,
+        pose: None,
+        t: 0.0,
+        exploded_view_offset: None,
         shadow_y_pos: None,
         shadow_is_square: false,
         armor_slots: None,
+        headwear: None,
+        main_hand: None,
+        off_hand: None,
+        nametag: None,
         #[cfg(feature = "ears")]
         ears_features: Some(ears_features),
     };
@@ -163,7 +176,7 @@ fn main() -> anyhow::Result<()> {
             handle_part_texture(&part_template_context, body_part, part, part_texture);
         }
 
-        if texture == PlayerPartTextureType::Skin {
+        if texture.is_skin() {
             ears_rs::utils::strip_alpha(part_texture);
 
             #[cfg(feature = "ears")]
@@ -34,6 +34,8 @@ fn bench(c: &mut Criterion) {
         has_layers: true,
         has_cape: false,
         arm_rotation: 10.0,
+        pose: None,
+        exploded_view_offset: None,
         shadow_y_pos: None,
         shadow_is_square: false,
         armor_slots: None,
@@ -4,7 +4,7 @@
 use std::{fs, hint::black_box, sync::Arc};
 
 use glam::Vec3;
-use nmsr_rendering::high_level::{parts::provider::PlayerPartProviderContext, types::PlayerBodyPartType, IntoEnumIterator};
+use nmsr_rendering::high_level::{parts::{provider::PlayerPartProviderContext, voxels::SkinLayerMask}, types::PlayerBodyPartType, IntoEnumIterator};
 
 pub use crate::{camera::CameraRotation, model::{RenderEntry, Size}, shader::ShaderState};
 
@@ -34,14 +34,27 @@ fn main() {
     
     let context: PlayerPartProviderContext<()> = PlayerPartProviderContext {
         model: nmsr_rendering::high_level::model::PlayerModel::Alex,
+        skin_texture: Default::default(),
+        geometry: None,
+        mob: None,
         has_hat_layer: true,
         has_layers: true,
+        voxel_layers: Some(SkinLayerMask::from_fn(|x, y| texture.get_pixel_checked(x, y).is_some_and(|p| p[3] > 0))),
         has_cape: false,
         is_optifine_cape: false,
+        cape_state: Default::default(),
+        elytra: None,
         arm_rotation: 10.0,
+        pose: None,
+        t: 0.0,
+        exploded_view_offset: None,
         shadow_y_pos: None,
         shadow_is_square: false,
         armor_slots: None,
+        headwear: None,
+        main_hand: None,
+        off_hand: None,
+        nametag: None,
         #[cfg(feature = "ears")]
         ears_features: ears_rs::parser::EarsParser::parse(&texture).expect("Yes"),
     };
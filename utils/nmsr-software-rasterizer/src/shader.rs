@@ -2,7 +2,7 @@ use std::{sync::Arc, fmt::Debug};
 
 use glam::{Vec2, Vec4, Vec3A};
 use image::{Rgba, RgbaImage};
-use nmsr_rendering::{high_level::{parts::{provider::{PlayerPartProviderContext, PlayerPartsProvider, PartsProvider}, part::Part}, types::PlayerBodyPartType, utils::parts::primitive_convert}, low_level::primitives::mesh::{PrimitiveDispatch, Mesh}};
+use nmsr_rendering::{high_level::{parts::{provider::{PlayerPartProviderContext, PlayerPartsProvider, PartsProvider, pose_player_parts}, part::Part}, types::PlayerBodyPartType, utils::parts::primitive_convert}, low_level::primitives::mesh::{PrimitiveDispatch, Mesh}};
 
 use crate::camera::Camera;
 
@@ -127,12 +127,14 @@ impl ShaderState {
             PlayerPartsProvider::Ears,
         ];
 
-        let parts = providers
+        let mut parts = providers
             .iter()
             .flat_map(|provider| { 
                 parts.iter().flat_map(|part| provider.get_parts(&context, *part))
              })
             .collect::<Vec<Part>>();
+
+        pose_player_parts(context, &mut parts);
         
         let parts = parts
             .into_iter()